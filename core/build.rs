@@ -1,3 +1,15 @@
 fn main() {
-    tauri_build::build()
+    #[cfg(feature = "tauri")]
+    tauri_build::build();
+
+    // Header of the C functions in `src/ffi.rs`
+    #[cfg(feature = "ffi")]
+    {
+        println!("cargo:rerun-if-changed=src/ffi.rs");
+        println!("cargo:rerun-if-changed=cbindgen.toml");
+        let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        cbindgen::generate(&crate_dir)
+            .expect("Failed to generate the C header")
+            .write_to_file(std::path::Path::new(&crate_dir).join("include/vla.h"));
+    }
 }
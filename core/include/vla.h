@@ -0,0 +1,192 @@
+#ifndef VLA_H
+#define VLA_H
+
+/* Generated from src/ffi.rs by cbindgen, don't edit by hand */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+typedef enum VlaStatus {
+  VLA_STATUS_OK = 0,
+  /**
+   * A pointer was null or a string wasn't UTF-8
+   */
+  VLA_STATUS_INVALID_ARGUMENT,
+  /**
+   * The graph couldn't be parsed or has errors
+   */
+  VLA_STATUS_INVALID_GRAPH,
+  VLA_STATUS_UNKNOWN_NODE,
+  /**
+   * The node isn't a manual trigger
+   */
+  VLA_STATUS_NOT_LISTENING,
+  VLA_STATUS_ALREADY_RUNNING,
+  VLA_STATUS_NOT_RUNNING,
+  /**
+   * Nodes ended the run errored
+   */
+  VLA_STATUS_FAILED,
+  /**
+   * The call panicked, the engine may be left in an inconsistent state
+   */
+  VLA_STATUS_PANICKED,
+  /**
+   * The engine's thread couldn't be started
+   */
+  VLA_STATUS_SPAWN_FAILED,
+} VlaStatus;
+
+typedef enum VlaPhase {
+  VLA_PHASE_WAITING,
+  VLA_PHASE_QUEUED,
+  VLA_PHASE_PAUSED,
+  VLA_PHASE_RUNNING,
+  VLA_PHASE_COMPLETED,
+  VLA_PHASE_ERRORED,
+} VlaPhase;
+
+/**
+ * A graph loaded into an engine, see `vla_engine_new`
+ */
+typedef struct VlaEngine VlaEngine;
+
+/**
+ * Called with the id of a node and its new state, serialized as JSON
+ *
+ * Called on the engine's threads, the strings are only valid during the call. The only
+ * function it may call on the engine is `vla_engine_set_state_callback`.
+ */
+typedef void (*VlaStateCallback)(void *user_data,
+                                 const char *node_id,
+                                 enum VlaPhase phase,
+                                 const char *state_json);
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Create an engine for the JSON of a graph, started paused when `stepped` is set
+ *
+ * Returns null when the graph can't be loaded, with the reason in `error` (when it isn't
+ * null) to be freed with `vla_string_free`.
+ *
+ * # Safety
+ * `graph_json` has to be a nul-terminated string, `error` null or writable.
+ */
+struct VlaEngine *vla_engine_new(const char *graph_json, bool stepped, char **error);
+
+/**
+ * Stop an engine and free it
+ *
+ * # Safety
+ * `engine` has to be null or come from `vla_engine_new`, and isn't used afterwards.
+ */
+void vla_engine_free(struct VlaEngine *engine);
+
+/**
+ * Free a string returned by these functions
+ *
+ * # Safety
+ * `string` has to be null or come from these functions, and isn't used afterwards.
+ */
+void vla_string_free(char *string);
+
+/**
+ * What went wrong in the last call on an engine that didn't return `VLA_STATUS_OK`, null
+ * when it succeeded
+ *
+ * The string belongs to the engine and is valid until the next call on it.
+ *
+ * # Safety
+ * `engine` has to be null or come from `vla_engine_new`.
+ */
+const char *vla_engine_last_error(const struct VlaEngine *engine);
+
+/**
+ * Call `callback` with `user_data` whenever a node's state changes, null stops calling
+ *
+ * Can be called from a callback, while the engine is running, and leaves
+ * `vla_engine_last_error` as it was.
+ *
+ * # Safety
+ * `engine` has to come from `vla_engine_new`. `callback` can be called from any thread
+ * until it is replaced or the engine is freed.
+ */
+enum VlaStatus vla_engine_set_state_callback(const struct VlaEngine *engine,
+                                             VlaStateCallback callback,
+                                             void *user_data);
+
+/**
+ * Start executing the graph on a thread of its own
+ *
+ * # Safety
+ * `engine` has to come from `vla_engine_new`.
+ */
+enum VlaStatus vla_engine_start(struct VlaEngine *engine);
+
+/**
+ * Trigger a manual trigger node of the running graph
+ *
+ * # Safety
+ * `engine` has to come from `vla_engine_new`, `node_id` has to be a nul-terminated string.
+ */
+enum VlaStatus vla_engine_trigger(struct VlaEngine *engine, const char *node_id);
+
+/**
+ * Execute the next node, then pause again
+ *
+ * # Safety
+ * `engine` has to come from `vla_engine_new`.
+ */
+enum VlaStatus vla_engine_step(struct VlaEngine *engine);
+
+/**
+ * Run until the next breakpoint
+ *
+ * # Safety
+ * `engine` has to come from `vla_engine_new`.
+ */
+enum VlaStatus vla_engine_continue(struct VlaEngine *engine);
+
+/**
+ * Pause before the next node
+ *
+ * # Safety
+ * `engine` has to come from `vla_engine_new`.
+ */
+enum VlaStatus vla_engine_pause(struct VlaEngine *engine);
+
+/**
+ * Whether the engine was started and hasn't finished yet
+ *
+ * # Safety
+ * `engine` has to be null or come from `vla_engine_new`.
+ */
+bool vla_engine_is_running(const struct VlaEngine *engine);
+
+/**
+ * Wait for the run to finish, `VLA_STATUS_FAILED` when nodes errored
+ *
+ * # Safety
+ * `engine` has to come from `vla_engine_new`.
+ */
+enum VlaStatus vla_engine_wait(struct VlaEngine *engine);
+
+/**
+ * Stop the run, `VLA_STATUS_FAILED` when nodes errored
+ *
+ * # Safety
+ * `engine` has to come from `vla_engine_new`.
+ */
+enum VlaStatus vla_engine_stop(struct VlaEngine *engine);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* VLA_H */
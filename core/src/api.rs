@@ -1,3 +1,4 @@
+use std::path::Path;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::sync::OnceLock;
@@ -5,9 +6,14 @@ use tauri::{AppHandle, Runtime};
 use tokio::sync::Mutex as TokioMutex;
 
 use crate::prelude::*;
-use crate::{bricks, canvas, engine::Engine};
+use crate::{bricks, canvas, engine::Engine, subgraph};
 
+use crate::bricks::registry::BrickRegistry;
+use crate::engine::debugger::{Breakpoint, Debugger, NodeInspection, WaveCache};
 use crate::engine::events::ExecutionEvent;
+use crate::engine::observer::{ExecutionObserver, StdoutObserver};
+use crate::engine::validation::{self, Diagnostic};
+use crate::engine::ExecutionStateUpdate;
 use std::sync::mpsc;
 
 /// Global running engine handle
@@ -21,6 +27,9 @@ static STOP_ENGINE: OnceLock<Arc<AtomicBool>> = OnceLock::new();
 static EVENT_SENDER: OnceLock<Arc<TokioMutex<Option<mpsc::Sender<ExecutionEvent>>>>> =
     OnceLock::new();
 
+/// Global debugger of the running engine
+static DEBUGGER: OnceLock<Arc<TokioMutex<Option<Arc<Debugger>>>>> = OnceLock::new();
+
 fn get_engine_handle() -> Arc<TokioMutex<Option<tokio::task::JoinHandle<()>>>> {
     Arc::clone(ENGINE_HANDLE.get_or_init(|| Arc::new(TokioMutex::new(None))))
 }
@@ -33,8 +42,49 @@ fn get_event_sender() -> Arc<TokioMutex<Option<mpsc::Sender<ExecutionEvent>>>> {
     Arc::clone(EVENT_SENDER.get_or_init(|| Arc::new(TokioMutex::new(None))))
 }
 
+fn get_debugger() -> Arc<TokioMutex<Option<Arc<Debugger>>>> {
+    Arc::clone(DEBUGGER.get_or_init(|| Arc::new(TokioMutex::new(None))))
+}
+
+/// Run an action on the debugger of the running engine
+async fn with_debugger<T>(action: impl FnOnce(&Debugger) -> T) -> Result<T, String> {
+    let debugger = get_debugger();
+    let debugger = debugger.lock().await;
+    let debugger = debugger.as_ref().ok_or("Engine not running")?;
+    Ok(action(debugger))
+}
+
+/// Re-attach brick definitions (execution functions are skipped during serialization)
+fn attach_bricks(graph: &mut Graph) {
+    for node in &mut graph.nodes {
+        node.data.brick = canvas::get_brick(&node.data.brick_id);
+    }
+}
+
+/// Broadcasts node state changes of an engine to the frontend
+pub struct TauriObserver<R: Runtime> {
+    app_handle: AppHandle<R>,
+}
+
+impl<R: Runtime> TauriObserver<R> {
+    pub fn new(app_handle: AppHandle<R>) -> Self {
+        Self { app_handle }
+    }
+}
+
+impl<R: Runtime> ExecutionObserver for TauriObserver<R> {
+    fn node_state_changed(&self, update: &ExecutionStateUpdate) {
+        if let Err(e) =
+            ApiEventTrigger::new(self.app_handle.clone()).node_execution_updated(update.clone())
+        {
+            eprintln!("Failed to broadcast node execution state update: {}", e);
+        }
+    }
+}
+
 /// Result of executing the entire graph
-#[derive(Clone, serde::Serialize, serde::Deserialize, specta::Type)]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "tauri", derive(specta::Type))]
 pub struct ExecutionResult {
     pub total_nodes: u32,
     pub success: bool,
@@ -66,9 +116,27 @@ pub trait CoreApi {
         brick_id: String,
         position: Point,
     ) -> Result<Graph, String>;
+    // Move the given nodes into a new subgraph, replacing them with a node of the subgraph
+    async fn create_subgraph<R: Runtime>(
+        app_handle: AppHandle<R>,
+        graph_path: String,
+        node_ids: Vec<String>,
+        id: String,
+        label: String,
+    ) -> Result<Graph, String>;
+    // Connect two handles, routing the value through a conversion node when `convert` is set
+    // and the types differ
+    async fn connect<R: Runtime>(
+        app_handle: AppHandle<R>,
+        graph_path: String,
+        edge: Edge,
+        convert: bool,
+    ) -> Result<Graph, String>;
 
+    // Execute a graph, its subgraphs are stored next to the file at `graph_path`
     async fn execute_graph<R: Runtime>(
         app_handle: AppHandle<R>,
+        graph_path: String,
         graph: Graph,
         mode: crate::engine::ExecutionMode,
     ) -> Result<ExecutionResult, String>;
@@ -77,6 +145,29 @@ pub trait CoreApi {
         app_handle: AppHandle<R>,
         node_id: String,
     ) -> Result<(), String>;
+
+    async fn validate_graph(graph_path: String, graph: Graph) -> Vec<Diagnostic>;
+
+    // Execute the next node, then pause again
+    async fn step_execution() -> Result<(), String>;
+    // Run until the next breakpoint
+    async fn continue_execution() -> Result<(), String>;
+    // Pause before the next node
+    async fn pause_execution() -> Result<(), String>;
+    // Set or remove (None) a node's breakpoint in the running execution
+    async fn set_breakpoint(node_id: String, breakpoint: Option<Breakpoint>) -> Result<(), String>;
+
+    // Cached outputs of every running wave
+    async fn get_execution_cache() -> Result<Vec<WaveCache>, String>;
+    // Outputs and resolved inputs of a node in every running wave
+    async fn inspect_node(node_id: String) -> Result<Vec<NodeInspection>, String>;
+    // Replace a cached output value, in one wave or (None) every wave
+    async fn override_output(
+        wave_id: Option<String>,
+        node_id: String,
+        output_id: String,
+        value: Value,
+    ) -> Result<(), String>;
 }
 
 #[derive(Clone)]
@@ -98,19 +189,23 @@ impl CoreApi for CoreApiImpl {
         app_handle: AppHandle<R>,
         graph_path: String,
     ) -> Result<Graph, String> {
+        // The subgraphs stored next to the graph can be placed in it
+        for brick in subgraph::bricks(&subgraph::directory_of(Path::new(&graph_path))) {
+            BrickRegistry::global().register(brick);
+        }
         canvas::load_graph(app_handle, &graph_path).await
     }
 
     async fn get_brick<R: Runtime>(
         self,
-        app_handle: AppHandle<R>,
+        _app_handle: AppHandle<R>,
         brick_id: String,
     ) -> Option<bricks::types::Brick> {
         canvas::get_brick(&brick_id)
     }
 
-    async fn get_bricks<R: Runtime>(self, app_handle: AppHandle<R>) -> Vec<bricks::types::Brick> {
-        bricks::all_bricks()
+    async fn get_bricks<R: Runtime>(self, _app_handle: AppHandle<R>) -> Vec<bricks::types::Brick> {
+        BrickRegistry::global().all()
     }
 
     async fn insert_node<R: Runtime>(
@@ -123,12 +218,63 @@ impl CoreApi for CoreApiImpl {
         canvas::insert_node(app_handle, &graph_path, &brick_id, position).await
     }
 
+    async fn create_subgraph<R: Runtime>(
+        self,
+        app_handle: AppHandle<R>,
+        graph_path: String,
+        node_ids: Vec<String>,
+        id: String,
+        label: String,
+    ) -> Result<Graph, String> {
+        canvas::create_subgraph(app_handle, &graph_path, &node_ids, &id, &label).await
+    }
+
+    async fn connect<R: Runtime>(
+        self,
+        app_handle: AppHandle<R>,
+        graph_path: String,
+        edge: Edge,
+        convert: bool,
+    ) -> Result<Graph, String> {
+        canvas::connect(app_handle, &graph_path, edge, convert).await
+    }
+
     async fn execute_graph<R: Runtime>(
         self,
         app_handle: AppHandle<R>,
-        mut graph: Graph,
+        graph_path: String,
+        graph: Graph,
         mode: crate::engine::ExecutionMode,
     ) -> Result<ExecutionResult, String> {
+        let total_nodes = graph.nodes.len() as u32;
+        let directory = subgraph::directory_of(Path::new(&graph_path));
+        let mut graph = match subgraph::expand(graph, Some(&directory)) {
+            Ok(graph) => graph,
+            Err(error) => {
+                return Ok(ExecutionResult {
+                    total_nodes,
+                    success: false,
+                    error: Some(error),
+                })
+            }
+        };
+        attach_bricks(&mut graph);
+
+        // Refuse graphs that can't run, leaving the current execution alone
+        let diagnostics = validation::validate(&graph);
+        if validation::has_errors(&diagnostics) {
+            let errors: Vec<String> = diagnostics
+                .into_iter()
+                .filter(validation::Diagnostic::is_error)
+                .map(|diagnostic| diagnostic.message)
+                .collect();
+            return Ok(ExecutionResult {
+                total_nodes,
+                success: false,
+                error: Some(errors.join("\n")),
+            });
+        }
+
         // Signal any existing engine to stop
         let stop_signal = get_stop_signal();
         stop_signal.store(true, Ordering::SeqCst);
@@ -148,13 +294,6 @@ impl CoreApi for CoreApiImpl {
         // Reset stop signal for new engine
         stop_signal.store(false, Ordering::SeqCst);
 
-        // Re-attach brick definitions (execution functions are skipped during serialization)
-        for node in &mut graph.nodes {
-            node.data.brick = canvas::get_brick(&node.data.brick_id);
-        }
-
-        let total_nodes = graph.nodes.len() as u32;
-
         // Clone stop signal for the background task
         let stop_signal_clone = Arc::clone(&stop_signal);
 
@@ -167,10 +306,14 @@ impl CoreApi for CoreApiImpl {
             *sender_guard.lock().await = Some(event_tx.clone());
         }
 
+        let mut engine = Engine::new(graph);
+        engine.add_observer(Arc::new(TauriObserver::new(app_handle)));
+        engine.add_observer(Arc::new(StdoutObserver::logs()));
+        engine.set_execution_mode(mode);
+        *get_debugger().lock().await = Some(engine.debugger());
+
         // Spawn engine in background task
         let handle = tokio::task::spawn_blocking(move || {
-            let mut engine = Engine::with_app_handle(graph, app_handle);
-            engine.set_execution_mode(mode);
             engine.start_with_event_channel(event_rx, event_tx);
 
             // Execute all steps (events are automatically broadcast via engine)
@@ -180,9 +323,9 @@ impl CoreApi for CoreApiImpl {
                     break;
                 }
 
+                // A failed run stops on its own, the listeners keep going
                 if let Err(e) = result {
                     eprintln!("Engine error: {}", e);
-                    break;
                 }
             }
             // Engine dropped here, which stops all listeners
@@ -233,4 +376,56 @@ impl CoreApi for CoreApiImpl {
             Err("Engine not running".to_string())
         }
     }
+
+    async fn step_execution(self) -> Result<(), String> {
+        with_debugger(Debugger::step).await
+    }
+
+    async fn continue_execution(self) -> Result<(), String> {
+        with_debugger(Debugger::resume).await
+    }
+
+    async fn pause_execution(self) -> Result<(), String> {
+        with_debugger(Debugger::pause).await
+    }
+
+    async fn set_breakpoint(
+        self,
+        node_id: String,
+        breakpoint: Option<Breakpoint>,
+    ) -> Result<(), String> {
+        with_debugger(|debugger| debugger.set_breakpoint(&node_id, breakpoint)).await
+    }
+
+    async fn get_execution_cache(self) -> Result<Vec<WaveCache>, String> {
+        with_debugger(Debugger::caches).await
+    }
+
+    async fn inspect_node(self, node_id: String) -> Result<Vec<NodeInspection>, String> {
+        with_debugger(|debugger| debugger.inspect(&node_id)).await?
+    }
+
+    async fn override_output(
+        self,
+        wave_id: Option<String>,
+        node_id: String,
+        output_id: String,
+        value: Value,
+    ) -> Result<(), String> {
+        with_debugger(|debugger| {
+            debugger.override_output(wave_id.as_deref(), &node_id, &output_id, value)
+        })
+        .await?
+    }
+
+    async fn validate_graph(self, graph_path: String, graph: Graph) -> Vec<Diagnostic> {
+        // Problems inside subgraphs are reported on the expanded nodes
+        let directory = subgraph::directory_of(Path::new(&graph_path));
+        let mut graph = match subgraph::expand(graph, Some(&directory)) {
+            Ok(graph) => graph,
+            Err(error) => return vec![Diagnostic::error(error)],
+        };
+        attach_bricks(&mut graph);
+        validation::validate(&graph)
+    }
 }
@@ -0,0 +1,441 @@
+use crate::bricks::macros::brick;
+use crate::prelude::*;
+use std::collections::BTreeMap;
+
+pub fn all_bricks() -> Vec<Brick> {
+    vec![
+        list_length_brick(),
+        list_get_brick(),
+        list_set_brick(),
+        list_push_brick(),
+        list_slice_brick(),
+        list_concat_brick(),
+        list_contains_brick(),
+        list_zip_brick(),
+        list_sort_brick(),
+        list_unique_brick(),
+        map_get_brick(),
+        map_set_brick(),
+        map_keys_brick(),
+        map_values_brick(),
+    ]
+}
+
+/// Resolve a possibly negative index (counting from the end) into a list position
+fn resolve_index(len: usize, index: i32) -> Option<usize> {
+    let index = if index < 0 {
+        len as i64 + index as i64
+    } else {
+        index as i64
+    };
+
+    (0..len as i64).contains(&index).then_some(index as usize)
+}
+
+/// Clamp a possibly negative slice bound into the range 0..=len
+fn clamp_bound(len: usize, bound: i32) -> usize {
+    let bound = if bound < 0 {
+        len as i64 + bound as i64
+    } else {
+        bound as i64
+    };
+
+    bound.clamp(0, len as i64) as usize
+}
+
+brick! {
+    #[id("list_length")]
+    #[label("List Length")]
+    #[description("Counts the items in a list")]
+    #[keywords(&["list", "length", "count", "size"])]
+    #[category("Collections")]
+    fn list_length(
+        #[input] #[label("List")] list: Vec<Value>
+    ) -> (
+        #[label("Length")] i32
+    )
+    {
+        (list.len() as i32,)
+    }
+}
+
+brick! {
+    #[id("list_get")]
+    #[label("Get Item")]
+    #[description("Gets the item at an index of a list (negative indices count from the end)")]
+    #[keywords(&["list", "get", "index", "item", "at"])]
+    #[category("Collections")]
+    fn list_get(
+        #[input] #[label("List")] list: Vec<Value>,
+        #[input] #[label("Index")] index: i32
+    ) -> (
+        #[label("Item")] Value,
+        #[label("Found")] bool
+    )
+    {
+        match resolve_index(list.len(), index) {
+            Some(position) => (list[position].clone(), true),
+            None => (Value::Null, false),
+        }
+    }
+}
+
+brick! {
+    #[id("list_set")]
+    #[label("Set Item")]
+    #[description("Replaces the item at an index of a list (negative indices count from the end)")]
+    #[keywords(&["list", "set", "index", "replace", "update"])]
+    #[category("Collections")]
+    fn list_set(
+        #[input] #[label("List")] list: Vec<Value>,
+        #[input] #[label("Index")] index: i32,
+        #[input] #[label("Item")] item: Value
+    ) -> (
+        #[label("List")] Vec<Value>,
+        #[label("Updated")] bool
+    )
+    {
+        let mut list = list;
+        match resolve_index(list.len(), index) {
+            Some(position) => {
+                list[position] = item;
+                (list, true)
+            }
+            None => (list, false),
+        }
+    }
+}
+
+brick! {
+    #[id("list_push")]
+    #[label("Push Item")]
+    #[description("Appends an item to the end of a list")]
+    #[keywords(&["list", "push", "append", "add"])]
+    #[category("Collections")]
+    fn list_push(
+        #[input] #[label("List")] list: Vec<Value>,
+        #[input] #[label("Item")] item: Value
+    ) -> (
+        #[label("List")] Vec<Value>
+    )
+    {
+        let mut list = list;
+        list.push(item);
+        (list,)
+    }
+}
+
+brick! {
+    #[id("list_slice")]
+    #[label("Slice List")]
+    #[description("Takes the items from start up to (not including) end; negative bounds count from the end")]
+    #[keywords(&["list", "slice", "range", "sublist", "take", "skip"])]
+    #[category("Collections")]
+    fn list_slice(
+        #[input] #[label("List")] list: Vec<Value>,
+        #[input] #[label("Start")] start: i32 = 0,
+        #[input] #[label("End")] end: i32 = 2147483647
+    ) -> (
+        #[label("List")] Vec<Value>
+    )
+    {
+        let start = clamp_bound(list.len(), start);
+        let end = clamp_bound(list.len(), end);
+
+        if start >= end {
+            (Vec::new(),)
+        } else {
+            (list[start..end].to_vec(),)
+        }
+    }
+}
+
+brick! {
+    #[id("list_concat")]
+    #[label("Concatenate Lists")]
+    #[description("Joins two lists into one")]
+    #[keywords(&["list", "concat", "join", "merge", "combine"])]
+    #[category("Collections")]
+    fn list_concat(
+        #[input] #[label("A")] a: Vec<Value>,
+        #[input] #[label("B")] b: Vec<Value>
+    ) -> (
+        #[label("A + B")] Vec<Value>
+    )
+    {
+        let mut list = a;
+        list.extend(b);
+        (list,)
+    }
+}
+
+brick! {
+    #[id("list_contains")]
+    #[label("List Contains")]
+    #[description("Checks whether a list contains an item")]
+    #[keywords(&["list", "contains", "includes", "has", "find"])]
+    #[category("Collections")]
+    fn list_contains(
+        #[input] #[label("List")] list: Vec<Value>,
+        #[input] #[label("Item")] item: Value
+    ) -> (
+        #[label("Contains")] bool
+    )
+    {
+        (list.iter().any(|value| value.compare(&item).is_eq()),)
+    }
+}
+
+brick! {
+    #[id("list_zip")]
+    #[label("Zip Lists")]
+    #[description("Pairs up the items of two lists, stopping at the shorter one")]
+    #[keywords(&["list", "zip", "pair", "combine"])]
+    #[category("Collections")]
+    fn list_zip(
+        #[input] #[label("A")] a: Vec<Value>,
+        #[input] #[label("B")] b: Vec<Value>
+    ) -> (
+        #[label("Pairs")] Vec<Vec<Value>>
+    )
+    {
+        (a.into_iter().zip(b).map(|(a, b)| vec![a, b]).collect(),)
+    }
+}
+
+brick! {
+    #[id("list_sort")]
+    #[label("Sort List")]
+    #[description("Sorts the items of a list")]
+    #[keywords(&["list", "sort", "order", "ascending", "descending"])]
+    #[category("Collections")]
+    fn list_sort(
+        #[input] #[label("List")] list: Vec<Value>,
+        #[argument] #[label("Descending")] descending: bool = false
+    ) -> (
+        #[label("List")] Vec<Value>
+    )
+    {
+        let mut list = list;
+        list.sort_by(|a, b| a.compare(b));
+        if descending {
+            list.reverse();
+        }
+        (list,)
+    }
+}
+
+brick! {
+    #[id("list_unique")]
+    #[label("Unique Items")]
+    #[description("Removes duplicate items from a list, keeping the first occurrence")]
+    #[keywords(&["list", "unique", "distinct", "dedupe", "duplicates"])]
+    #[category("Collections")]
+    fn list_unique(
+        #[input] #[label("List")] list: Vec<Value>
+    ) -> (
+        #[label("List")] Vec<Value>
+    )
+    {
+        let mut unique: Vec<Value> = Vec::with_capacity(list.len());
+        for item in list {
+            if !unique.iter().any(|value| value.compare(&item).is_eq()) {
+                unique.push(item);
+            }
+        }
+        (unique,)
+    }
+}
+
+brick! {
+    #[id("map_get")]
+    #[label("Get Entry")]
+    #[description("Gets the value stored under a key of a map")]
+    #[keywords(&["map", "get", "key", "lookup", "dictionary", "object"])]
+    #[category("Collections")]
+    fn map_get(
+        #[input] #[label("Map")] map: BTreeMap<String, Value>,
+        #[input] #[label("Key")] key: String
+    ) -> (
+        #[label("Value")] Value,
+        #[label("Found")] bool
+    )
+    {
+        match map.get(&key) {
+            Some(value) => (value.clone(), true),
+            None => (Value::Null, false),
+        }
+    }
+}
+
+brick! {
+    #[id("map_set")]
+    #[label("Set Entry")]
+    #[description("Stores a value under a key of a map, replacing any existing value")]
+    #[keywords(&["map", "set", "key", "insert", "dictionary", "object"])]
+    #[category("Collections")]
+    fn map_set(
+        #[input] #[label("Map")] map: BTreeMap<String, Value>,
+        #[input] #[label("Key")] key: String,
+        #[input] #[label("Value")] value: Value
+    ) -> (
+        #[label("Map")] BTreeMap<String, Value>
+    )
+    {
+        let mut map = map;
+        map.insert(key, value);
+        (map,)
+    }
+}
+
+brick! {
+    #[id("map_keys")]
+    #[label("Map Keys")]
+    #[description("Lists the keys of a map in sorted order")]
+    #[keywords(&["map", "keys", "dictionary", "object"])]
+    #[category("Collections")]
+    fn map_keys(
+        #[input] #[label("Map")] map: BTreeMap<String, Value>
+    ) -> (
+        #[label("Keys")] Vec<String>
+    )
+    {
+        (map.into_keys().collect(),)
+    }
+}
+
+brick! {
+    #[id("map_values")]
+    #[label("Map Values")]
+    #[description("Lists the values of a map, ordered by key")]
+    #[keywords(&["map", "values", "dictionary", "object"])]
+    #[category("Collections")]
+    fn map_values(
+        #[input] #[label("Map")] map: BTreeMap<String, Value>
+    ) -> (
+        #[label("Values")] Vec<Value>
+    )
+    {
+        (map.into_values().collect(),)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bricks::types::{BrickInputValue, ConnectionType};
+
+    fn list(items: &[i32]) -> Vec<Value> {
+        items.iter().map(|&item| Value::from(item)).collect()
+    }
+
+    #[test]
+    fn list_length_test() {
+        let brick = list_length_brick();
+        assert_eq!(
+            brick.inputs[0].r#type,
+            ConnectionType::List(Box::new(ConnectionType::Any))
+        );
+        assert_eq!(list_length(list(&[1, 2, 3])), (3,));
+        assert_eq!(list_length(vec![]), (0,));
+    }
+
+    #[test]
+    fn list_get_and_set_test() {
+        assert_eq!(list_get(list(&[1, 2, 3]), 1), (Value::Integer(2), true));
+        assert_eq!(list_get(list(&[1, 2, 3]), -1), (Value::Integer(3), true));
+        assert_eq!(list_get(list(&[1, 2, 3]), 3), (Value::Null, false));
+
+        assert_eq!(
+            list_set(list(&[1, 2, 3]), 0, Value::from("a")),
+            (vec![Value::from("a"), 2.into(), 3.into()], true)
+        );
+        assert_eq!(list_set(list(&[1]), 5, Value::Null), (list(&[1]), false));
+    }
+
+    #[test]
+    fn list_push_and_concat_test() {
+        assert_eq!(list_push(list(&[1]), 2.into()), (list(&[1, 2]),));
+        assert_eq!(list_concat(list(&[1]), list(&[2, 3])), (list(&[1, 2, 3]),));
+    }
+
+    #[test]
+    fn list_slice_test() {
+        let items = list(&[1, 2, 3, 4, 5]);
+        assert_eq!(list_slice(items.clone(), 1, 3), (list(&[2, 3]),));
+        assert_eq!(list_slice(items.clone(), -2, i32::MAX), (list(&[4, 5]),));
+        assert_eq!(list_slice(items.clone(), 0, -1), (list(&[1, 2, 3, 4]),));
+        assert_eq!(list_slice(items, 4, 2), (vec![],));
+    }
+
+    #[test]
+    fn list_contains_test() {
+        // Integers and numbers compare by value
+        assert_eq!(list_contains(list(&[1, 2]), Value::Number(2.0)), (true,));
+        assert_eq!(list_contains(list(&[1, 2]), Value::from("2")), (false,));
+    }
+
+    #[test]
+    fn list_zip_test() {
+        let brick = list_zip_brick();
+        assert_eq!(
+            brick.outputs[0].r#type,
+            ConnectionType::List(Box::new(ConnectionType::List(Box::new(
+                ConnectionType::Any
+            ))))
+        );
+        assert_eq!(
+            list_zip(list(&[1, 2, 3]), vec!["a".into(), "b".into()]),
+            (vec![
+                vec![Value::Integer(1), Value::from("a")],
+                vec![Value::Integer(2), Value::from("b")],
+            ],)
+        );
+    }
+
+    #[test]
+    fn list_sort_and_unique_test() {
+        assert_eq!(list_sort(list(&[3, 1, 2]), false), (list(&[1, 2, 3]),));
+        assert_eq!(list_sort(list(&[3, 1, 2]), true), (list(&[3, 2, 1]),));
+        assert_eq!(list_unique(list(&[1, 2, 1, 3, 2])), (list(&[1, 2, 3]),));
+    }
+
+    #[test]
+    fn map_test() {
+        let map = map_set(BTreeMap::new(), "b".to_string(), 2.into()).0;
+        let map = map_set(map, "a".to_string(), 1.into()).0;
+
+        assert_eq!(
+            map_get(map.clone(), "a".to_string()),
+            (Value::Integer(1), true)
+        );
+        assert_eq!(map_get(map.clone(), "c".to_string()), (Value::Null, false));
+        assert_eq!(
+            map_keys(map.clone()),
+            (vec!["a".to_string(), "b".to_string()],)
+        );
+        assert_eq!(map_values(map), (list(&[1, 2]),));
+    }
+
+    #[test]
+    fn collection_execution_test() {
+        let brick = map_keys_brick();
+        let inputs = vec![BrickInputValue {
+            id: "map".to_string(),
+            value: Value::parse(r#"{"x": 1, "y": 2}"#, &brick.inputs[0].r#type).unwrap(),
+        }];
+
+        let outputs = (brick.execution)(&BrickContext::default(), vec![], inputs).unwrap();
+        assert_eq!(
+            outputs[0].value,
+            Value::List(vec![Value::from("x"), Value::from("y")])
+        );
+
+        // Lists are rejected where maps are expected
+        let inputs = vec![BrickInputValue {
+            id: "map".to_string(),
+            value: Value::List(vec![]),
+        }];
+        assert!((brick.execution)(&BrickContext::default(), vec![], inputs).is_err());
+    }
+}
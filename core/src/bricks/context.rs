@@ -0,0 +1,148 @@
+/// Context handed to a brick for a single execution
+///
+/// Everything a brick needs from the engine goes through this object instead of
+/// thread-local state, so bricks can run on any thread and in parallel: the data of
+/// the event that started the flow, the node being executed, firing execution outputs,
+/// logging and cancellation.
+use crate::engine::trigger::{ExecutionContext, Trigger};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, Default)]
+pub struct BrickContext {
+    node_id: String,
+    event: ExecutionContext,
+    loop_iteration: usize,
+    cancelled: Arc<AtomicBool>,
+    triggers: Mutex<Vec<Trigger>>,
+    logs: Mutex<Vec<String>>,
+}
+
+impl BrickContext {
+    pub fn new(node_id: impl Into<String>) -> Self {
+        Self {
+            node_id: node_id.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Attach the data of the event that started the flow
+    pub fn with_event(mut self, event: ExecutionContext) -> Self {
+        self.event = event;
+        self
+    }
+
+    /// Set the iteration a loop node is executing (0 on its first execution)
+    pub fn with_loop_iteration(mut self, iteration: usize) -> Self {
+        self.loop_iteration = iteration;
+        self
+    }
+
+    /// Share a cancellation token with the flow this brick runs in
+    pub fn with_cancellation(mut self, cancelled: Arc<AtomicBool>) -> Self {
+        self.cancelled = cancelled;
+        self
+    }
+
+    /// Id of the node being executed
+    pub fn node_id(&self) -> &str {
+        &self.node_id
+    }
+
+    /// Data of the event that started the flow
+    pub fn event(&self) -> &ExecutionContext {
+        &self.event
+    }
+
+    /// Iteration the current loop node is executing
+    pub fn loop_iteration(&self) -> usize {
+        self.loop_iteration
+    }
+
+    /// Whether the flow was cancelled, long running bricks should stop early when it was
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Token that is set once the flow is cancelled
+    pub fn cancellation_token(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.cancelled)
+    }
+
+    /// Fire an execution output once the brick has finished
+    pub fn trigger(&self, output_id: &str) {
+        self.triggers
+            .lock()
+            .unwrap()
+            .push(Trigger::new(self.node_id.clone(), output_id.to_string()));
+    }
+
+    /// Like `trigger`, but runs the triggered flow as a loop iteration and then executes
+    /// this node again (see `loop_iteration`)
+    pub fn iterate(&self, output_id: &str) {
+        self.triggers.lock().unwrap().push(Trigger::iteration(
+            self.node_id.clone(),
+            output_id.to_string(),
+        ));
+    }
+
+    /// Record a log message for this node
+    pub fn log(&self, message: impl Into<String>) {
+        self.logs.lock().unwrap().push(message.into());
+    }
+
+    /// Take the execution outputs fired so far
+    pub fn take_triggers(&self) -> Vec<Trigger> {
+        std::mem::take(&mut *self.triggers.lock().unwrap())
+    }
+
+    /// Take the messages logged so far
+    pub fn take_logs(&self) -> Vec<String> {
+        std::mem::take(&mut *self.logs.lock().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_triggers_carry_node_id() {
+        let ctx = BrickContext::new("node123");
+        ctx.trigger("output1");
+        ctx.iterate("body");
+
+        assert_eq!(
+            ctx.take_triggers(),
+            vec![
+                Trigger::new("node123".to_string(), "output1".to_string()),
+                Trigger::iteration("node123".to_string(), "body".to_string())
+            ]
+        );
+        assert!(ctx.take_triggers().is_empty());
+    }
+
+    #[test]
+    fn test_logs_and_cancellation() {
+        let token = Arc::new(AtomicBool::new(false));
+        let ctx = BrickContext::new("node1").with_cancellation(Arc::clone(&token));
+
+        ctx.log("hello");
+        assert_eq!(ctx.take_logs(), vec!["hello"]);
+
+        assert!(!ctx.is_cancelled());
+        token.store(true, Ordering::SeqCst);
+        assert!(ctx.is_cancelled());
+    }
+
+    #[test]
+    fn test_contexts_are_independent() {
+        let first = BrickContext::new("a").with_loop_iteration(3);
+        let second = BrickContext::new("b");
+
+        first.trigger("done");
+        assert!(second.take_triggers().is_empty());
+        assert_eq!(first.loop_iteration(), 3);
+        assert_eq!(second.loop_iteration(), 0);
+    }
+}
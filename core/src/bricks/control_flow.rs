@@ -1,6 +1,5 @@
 use crate::bricks::macros::brick;
 use crate::prelude::*;
-use crate::trigger;
 
 #[cfg(test)]
 mod tests {
@@ -34,10 +33,117 @@ mod tests {
         assert_eq!(start.execution_outputs[0].id, "begin");
         assert_eq!(start.execution_outputs[0].label, "Begin Execution");
     }
+
+    /// Run a loop brick for the given iteration, returning its outputs and triggered outputs
+    fn run_iteration(
+        brick: &Brick,
+        iteration: usize,
+        inputs: Vec<crate::bricks::types::BrickInputValue>,
+    ) -> (Vec<Value>, Vec<crate::engine::trigger::Trigger>) {
+        let ctx = BrickContext::new("loop").with_loop_iteration(iteration);
+        let outputs = (brick.execution)(&ctx, vec![], inputs).unwrap();
+
+        (
+            outputs.into_iter().map(|o| o.value).collect(),
+            ctx.take_triggers(),
+        )
+    }
+
+    #[test]
+    fn test_loop_brick_labels() {
+        for brick in [for_range_brick(), for_each_brick(), while_loop_brick()] {
+            assert_eq!(brick.execution_inputs[0].id, "execute");
+            assert_eq!(brick.execution_outputs[0].id, "body");
+            assert_eq!(brick.execution_outputs[1].id, "completed");
+        }
+
+        assert_eq!(while_loop_brick().id, "while");
+    }
+
+    #[test]
+    fn test_for_range_iterations() {
+        let brick = for_range_brick();
+        let inputs = || {
+            vec![crate::bricks::types::BrickInputValue {
+                id: "end".to_string(),
+                value: Value::Integer(2),
+            }]
+        };
+
+        let (outputs, triggers) = run_iteration(&brick, 1, inputs());
+        assert_eq!(outputs, vec![Value::Integer(1)]);
+        assert_eq!(triggers[0].output_id, "body");
+        assert!(triggers[0].iteration);
+
+        let (outputs, triggers) = run_iteration(&brick, 2, inputs());
+        assert_eq!(outputs, vec![Value::Integer(2)]);
+        assert_eq!(triggers[0].output_id, "completed");
+        assert!(!triggers[0].iteration);
+    }
+
+    #[test]
+    fn test_for_each_iterations() {
+        let brick = for_each_brick();
+        let inputs = || {
+            vec![crate::bricks::types::BrickInputValue {
+                id: "list".to_string(),
+                value: Value::List(vec![Value::from("a"), Value::from("b")]),
+            }]
+        };
+
+        let (outputs, triggers) = run_iteration(&brick, 1, inputs());
+        assert_eq!(outputs, vec![Value::from("b"), Value::Integer(1)]);
+        assert_eq!(triggers[0].output_id, "body");
+
+        let (_, triggers) = run_iteration(&brick, 2, inputs());
+        assert_eq!(triggers[0].output_id, "completed");
+    }
+
+    #[test]
+    fn test_while_stops_at_max_iterations() {
+        let brick = while_loop_brick();
+        let inputs = || {
+            vec![crate::bricks::types::BrickInputValue {
+                id: "condition".to_string(),
+                value: Value::Bool(true),
+            }]
+        };
+
+        let (_, triggers) = run_iteration(&brick, 999, inputs());
+        assert_eq!(triggers[0].output_id, "body");
+
+        let (_, triggers) = run_iteration(&brick, 1000, inputs());
+        assert_eq!(triggers[0].output_id, "completed");
+    }
+
+    #[test]
+    fn test_delay_is_async() {
+        let brick = delay_brick();
+        assert!(brick.async_execution.is_some());
+        assert!(if_else_brick().async_execution.is_none());
+
+        // Async bricks can still be executed directly
+        let ctx = BrickContext::new("delay");
+        let inputs = vec![crate::bricks::types::BrickInputValue {
+            id: "duration_ms".to_string(),
+            value: Value::Integer(10),
+        }];
+        let outputs = (brick.execution)(&ctx, vec![], inputs).unwrap();
+
+        assert_eq!(outputs[0].value, Value::Integer(10));
+        assert_eq!(ctx.take_triggers()[0].output_id, "completed");
+    }
 }
 
 pub fn all_bricks() -> Vec<Brick> {
-    vec![if_else_brick(), start_brick()]
+    vec![
+        if_else_brick(),
+        start_brick(),
+        for_range_brick(),
+        for_each_brick(),
+        while_loop_brick(),
+        delay_brick(),
+    ]
 }
 
 // Test brick with execution flow
@@ -50,14 +156,15 @@ brick! {
     #[execution_output("true_branch", "True")]
     #[execution_output("false_branch", "False")]
     fn if_else(
+        #[context] ctx: &BrickContext,
         #[input] #[label("Condition")] condition: bool
     ) -> (
         #[label("Condition Value")] bool
     ) {
         if condition {
-            trigger!("true_branch");
+            ctx.trigger("true_branch");
         } else {
-            trigger!("false_branch");
+            ctx.trigger("false_branch");
         }
 
         (condition,)
@@ -71,10 +178,126 @@ brick! {
     #[description("Starts execution flow")]
     #[category("Control Flow")]
     #[execution_output("begin", "Begin Execution")]
-    fn start() -> (
+    fn start(#[context] ctx: &BrickContext) -> (
         #[label("Started")] bool
     ) {
-        trigger!("begin");
+        ctx.trigger("begin");
         (true,)
     }
 }
+
+// Loop over a range of numbers
+brick! {
+    #[id("for_range")]
+    #[label("For Range")]
+    #[description("Runs the body once for every index from start up to (not including) end")]
+    #[keywords(&["loop", "for", "range", "repeat", "iterate", "times"])]
+    #[category("Control Flow")]
+    #[execution_input("execute", "Execute")]
+    #[execution_output("body", "Body")]
+    #[execution_output("completed", "Completed")]
+    fn for_range(
+        #[context] ctx: &BrickContext,
+        #[input] #[label("Start")] start: i32 = 0,
+        #[input] #[label("End")] end: i32 = 10,
+        #[input] #[label("Step")] step: i32 = 1
+    ) -> (
+        #[label("Index")] i32
+    ) {
+        let index = start as i64 + ctx.loop_iteration() as i64 * step as i64;
+        let in_range = (step > 0 && index < end as i64) || (step < 0 && index > end as i64);
+
+        if in_range {
+            ctx.iterate("body");
+        } else {
+            ctx.trigger("completed");
+        }
+
+        (index.clamp(i32::MIN as i64, i32::MAX as i64) as i32,)
+    }
+}
+
+// Loop over the items of a list
+brick! {
+    #[id("for_each")]
+    #[label("For Each")]
+    #[description("Runs the body once for every item in a list")]
+    #[keywords(&["loop", "for", "each", "list", "iterate", "items"])]
+    #[category("Control Flow")]
+    #[execution_input("execute", "Execute")]
+    #[execution_output("body", "Body")]
+    #[execution_output("completed", "Completed")]
+    fn for_each(
+        #[context] ctx: &BrickContext,
+        #[input] #[label("List")] list: Vec<Value>
+    ) -> (
+        #[label("Item")] Value,
+        #[label("Index")] i32
+    ) {
+        let index = ctx.loop_iteration();
+
+        match list.get(index) {
+            Some(item) => {
+                ctx.iterate("body");
+                (item.clone(), index as i32)
+            }
+            None => {
+                ctx.trigger("completed");
+                (Value::Null, index as i32)
+            }
+        }
+    }
+}
+
+// Loop while a condition holds
+brick! {
+    #[id("while")]
+    #[label("While")]
+    #[description("Runs the body as long as the condition is true, re-evaluating it before every iteration")]
+    #[keywords(&["loop", "while", "repeat", "until", "condition"])]
+    #[category("Control Flow")]
+    #[execution_input("execute", "Execute")]
+    #[execution_output("body", "Body")]
+    #[execution_output("completed", "Completed")]
+    fn while_loop(
+        #[context] ctx: &BrickContext,
+        #[input] #[label("Condition")] condition: bool,
+        #[argument] #[label("Max Iterations")] max_iterations: i32 = 1000
+    ) -> (
+        #[label("Index")] i32
+    ) {
+        let index = ctx.loop_iteration();
+
+        // Guard against conditions that never become false
+        if condition && (index as i64) < max_iterations as i64 {
+            ctx.iterate("body");
+        } else {
+            ctx.trigger("completed");
+        }
+
+        (index as i32,)
+    }
+}
+
+// Wait before continuing, without holding up other branches of the flow
+brick! {
+    #[id("delay")]
+    #[label("Delay")]
+    #[description("Waits for the given number of milliseconds before continuing the flow")]
+    #[keywords(&["delay", "wait", "sleep", "pause"])]
+    #[category("Control Flow")]
+    #[execution_input("execute", "Execute")]
+    #[execution_output("completed", "Completed")]
+    async fn delay(
+        #[context] ctx: &BrickContext,
+        #[input] #[label("Duration (ms)")] duration_ms: i32 = 1000
+    ) -> (
+        #[label("Duration (ms)")] i32
+    ) {
+        let duration = std::time::Duration::from_millis(duration_ms.max(0) as u64);
+        tokio::time::sleep(duration).await;
+
+        ctx.trigger("completed");
+        (duration_ms,)
+    }
+}
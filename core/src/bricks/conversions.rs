@@ -0,0 +1,120 @@
+use crate::bricks::macros::brick;
+use crate::bricks::types::ConnectionType;
+use crate::prelude::*;
+
+pub fn all_bricks() -> Vec<Brick> {
+    vec![
+        number_to_string_brick(),
+        boolean_to_string_brick(),
+        string_to_number_brick(),
+        string_to_boolean_brick(),
+    ]
+}
+
+/// The brick converting values of one connection type to another, if there is one
+pub fn conversion_brick(from: &ConnectionType, to: &ConnectionType) -> Option<Brick> {
+    match (from, to) {
+        (ConnectionType::Number, ConnectionType::String) => Some(number_to_string_brick()),
+        (ConnectionType::Boolean, ConnectionType::String) => Some(boolean_to_string_brick()),
+        (ConnectionType::String, ConnectionType::Number) => Some(string_to_number_brick()),
+        (ConnectionType::String, ConnectionType::Boolean) => Some(string_to_boolean_brick()),
+        _ => None,
+    }
+}
+
+brick! {
+    #[id("number_to_string")]
+    #[label("Number to Text")]
+    #[description("Formats a number as text")]
+    #[keywords(&["convert", "number", "string", "text", "format"])]
+    #[category("Conversion")]
+    fn number_to_string(
+        #[input] #[label("Number")] number: f64
+    ) -> (
+        #[label("Text")] String
+    )
+    {
+        (Value::from(number).to_string(),)
+    }
+}
+
+brick! {
+    #[id("boolean_to_string")]
+    #[label("Boolean to Text")]
+    #[description("Formats a boolean as 'true' or 'false'")]
+    #[keywords(&["convert", "boolean", "string", "text", "format"])]
+    #[category("Conversion")]
+    fn boolean_to_string(
+        #[input] #[label("Boolean")] boolean: bool
+    ) -> (
+        #[label("Text")] String
+    )
+    {
+        (boolean.to_string(),)
+    }
+}
+
+brick! {
+    #[id("string_to_number")]
+    #[label("Text to Number")]
+    #[description("Parses a number from text, failing when the text isn't a number")]
+    #[keywords(&["convert", "number", "string", "text", "parse"])]
+    #[category("Conversion")]
+    fn string_to_number(
+        #[input] #[label("Text")] text: String
+    ) -> Result<(#[label("Number")] f64), BrickError>
+    {
+        let number = Value::parse(&text, &ConnectionType::Number)?;
+        Ok((f64::try_from(number)?,))
+    }
+}
+
+brick! {
+    #[id("string_to_boolean")]
+    #[label("Text to Boolean")]
+    #[description("Parses 'true' or 'false' from text, failing on anything else")]
+    #[keywords(&["convert", "boolean", "string", "text", "parse"])]
+    #[category("Conversion")]
+    fn string_to_boolean(
+        #[input] #[label("Text")] text: String
+    ) -> Result<(#[label("Boolean")] bool), BrickError>
+    {
+        let boolean = Value::parse(&text.to_lowercase(), &ConnectionType::Boolean)?;
+        Ok((bool::try_from(boolean)?,))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn number_to_string_test() {
+        assert_eq!(number_to_string(3.5), ("3.5".to_string(),));
+        assert_eq!(number_to_string(-2.0), ("-2".to_string(),));
+        assert_eq!(boolean_to_string(true), ("true".to_string(),));
+    }
+
+    #[test]
+    fn string_to_number_test() {
+        assert_eq!(string_to_number(" 42 ".to_string()), Ok((42.0,)));
+        assert_eq!(string_to_number("0.25".to_string()), Ok((0.25,)));
+        assert!(string_to_number("forty-two".to_string()).is_err());
+    }
+
+    #[test]
+    fn string_to_boolean_test() {
+        assert_eq!(string_to_boolean("True".to_string()), Ok((true,)));
+        assert_eq!(string_to_boolean("false".to_string()), Ok((false,)));
+        assert!(string_to_boolean("yes".to_string()).is_err());
+    }
+
+    #[test]
+    fn conversion_brick_test() {
+        let brick = conversion_brick(&ConnectionType::String, &ConnectionType::Number).unwrap();
+        assert_eq!(brick.id, "string_to_number");
+        assert_eq!(brick.inputs[0].r#type, ConnectionType::String);
+        assert_eq!(brick.outputs[0].r#type, ConnectionType::Number);
+        assert!(conversion_brick(&ConnectionType::Number, &ConnectionType::Boolean).is_none());
+    }
+}
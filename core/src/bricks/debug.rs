@@ -1,4 +1,4 @@
-use crate::{bricks::macros::brick, prelude::*, trigger};
+use crate::{bricks::macros::brick, prelude::*};
 
 brick! {
     #[id("print")]
@@ -7,9 +7,10 @@ brick! {
     #[category("Debug")]
     #[execution_input("execute", "Execute")]
     fn print(
+        #[context] ctx: &BrickContext,
         #[input] #[label("Value")] value: String
     ) -> () {
-        println!("{}", value);
+        ctx.log(value);
     }
 }
 
@@ -19,10 +20,10 @@ brick! {
     #[description("Manual trigger to start execution flow")]
     #[category("Debug")]
     #[execution_output("triggered", "Triggered")]
-    fn trigger() -> (
+    fn trigger(#[context] ctx: &BrickContext) -> (
         #[label("Done")] bool
     ) {
-        trigger!("triggered");
+        ctx.trigger("triggered");
         (true,)
     }
 }
@@ -51,13 +52,27 @@ mod tests {
     #[test]
     fn test_trigger_execution() {
         let brick = trigger_brick();
-        let outputs = (brick.execution)(vec![], vec![]);
+        let ctx = BrickContext::new("trigger");
+        let outputs = (brick.execution)(&ctx, vec![], vec![]).unwrap();
         println!("Trigger execution returned {} outputs", outputs.len());
         assert_eq!(outputs.len(), 1, "Should return 1 output");
 
-        // Check if triggers were set
-        let trigger_count = crate::engine::trigger::trigger_count();
-        println!("Trigger count: {}", trigger_count);
-        assert!(trigger_count > 0, "Should have set at least one trigger");
+        // Check that the trigger was fired on this context
+        let triggers = ctx.take_triggers();
+        assert_eq!(triggers.len(), 1, "Should have fired one trigger");
+        assert_eq!(triggers[0].output_id, "triggered");
+    }
+
+    #[test]
+    fn test_print_logs_value() {
+        let brick = print_brick();
+        let ctx = BrickContext::new("print");
+        let inputs = vec![crate::bricks::types::BrickInputValue {
+            id: "value".to_string(),
+            value: Value::from("hello"),
+        }];
+
+        (brick.execution)(&ctx, vec![], inputs).unwrap();
+        assert_eq!(ctx.take_logs(), vec!["hello"]);
     }
 }
@@ -0,0 +1,75 @@
+/// Errors a brick execution can end with
+///
+/// Bricks declared as `fn ... -> Result<(...), BrickError>` return these from their body,
+/// anything convertible into a `BrickError` can be propagated with `?`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BrickError {
+    /// An argument or input couldn't be converted to the type the brick expects
+    InvalidInput(String),
+    /// The brick ran but couldn't do its job
+    Failed(String),
+    /// The brick panicked
+    Panicked,
+    /// The brick ran longer than its node's timeout allows
+    TimedOut(std::time::Duration),
+}
+
+impl BrickError {
+    pub fn failed(message: impl Into<String>) -> Self {
+        BrickError::Failed(message.into())
+    }
+
+    /// Message without the error kind, as shown on the brick's error output
+    pub fn message(&self) -> String {
+        match self {
+            BrickError::InvalidInput(message) | BrickError::Failed(message) => message.clone(),
+            BrickError::Panicked => "execution panicked".to_string(),
+            BrickError::TimedOut(timeout) => format!("timed out after {}ms", timeout.as_millis()),
+        }
+    }
+}
+
+impl std::fmt::Display for BrickError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message())
+    }
+}
+
+impl std::error::Error for BrickError {}
+
+impl From<String> for BrickError {
+    fn from(message: String) -> Self {
+        BrickError::Failed(message)
+    }
+}
+
+impl From<&str> for BrickError {
+    fn from(message: &str) -> Self {
+        BrickError::Failed(message.to_string())
+    }
+}
+
+impl From<std::io::Error> for BrickError {
+    fn from(error: std::io::Error) -> Self {
+        BrickError::Failed(error.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversions_and_messages() {
+        fn read() -> Result<(), BrickError> {
+            std::fs::read("/definitely/not/a/file")?;
+            Ok(())
+        }
+        assert!(matches!(read(), Err(BrickError::Failed(_))));
+
+        let error: BrickError = "boom".into();
+        assert_eq!(error, BrickError::failed("boom"));
+        assert_eq!(error.to_string(), "boom");
+        assert_eq!(BrickError::Panicked.to_string(), "execution panicked");
+    }
+}
@@ -1,10 +1,19 @@
 use crate::bricks::macros::brick;
-use crate::engine::trigger;
+#[cfg(feature = "net")]
+use crate::engine::events::HttpResponseData;
+#[cfg(feature = "net")]
+use crate::engine::webhook;
 use crate::prelude::*;
-use crate::trigger;
 
 pub fn all_bricks() -> Vec<Brick> {
-    vec![manual_trigger_brick(), timer_brick()]
+    [
+        vec![manual_trigger_brick(), timer_brick()],
+        #[cfg(feature = "net")]
+        vec![webhook_brick(), respond_http_brick()],
+        #[cfg(feature = "fs")]
+        vec![file_watch_brick()],
+    ]
+    .concat()
 }
 
 // Manual trigger brick - can be triggered from UI
@@ -16,11 +25,13 @@ brick! {
     #[category("Events")]
     #[emission_type(ManualTrigger)]
     #[execution_output("triggered", "Triggered")]
-    fn manual_trigger() -> (
+    fn manual_trigger(
+        #[context] ctx: &BrickContext,
+        #[argument] #[label("Concurrency")] concurrency: String = "queue"
+    ) -> (
         #[label("Timestamp")] String
     ) {
-        let ctx = trigger::get_execution_context();
-        let timestamp = match ctx.manual_trigger_timestamp() {
+        let timestamp = match ctx.event().manual_trigger_timestamp() {
             Some(ts) => ts.to_string(),
             None => {
                 // Use system time as fallback
@@ -30,7 +41,7 @@ brick! {
             }
         };
 
-        trigger!("triggered");
+        ctx.trigger("triggered");
         (timestamp,)
     }
 }
@@ -45,13 +56,14 @@ brick! {
     #[emission_type(Timer { default_interval_ms: 1000 })]
     #[execution_output("tick", "Tick")]
     fn timer(
-        #[argument] #[label("Interval (ms)")] interval_ms: String = "1000"
+        #[context] ctx: &BrickContext,
+        #[argument] #[label("Interval (ms)")] interval_ms: String = "1000",
+        #[argument] #[label("Concurrency")] concurrency: String = "queue"
     ) -> (
         #[label("Tick Count")] String,
         #[label("Timestamp")] String
     ) {
-        let ctx = trigger::get_execution_context();
-        let (tick_count, timestamp) = match ctx.timer_tick() {
+        let (tick_count, timestamp) = match ctx.event().timer_tick() {
             Some((count, ts)) => (count.to_string(), ts.to_string()),
             None => {
                 // Use system time as fallback
@@ -62,11 +74,114 @@ brick! {
             }
         };
 
-        trigger!("tick");
+        ctx.trigger("tick");
         (tick_count, timestamp)
     }
 }
 
+// Webhook brick - emits when a local HTTP request hits its route
+#[cfg(feature = "net")]
+brick! {
+    #[id("webhook")]
+    #[label("Webhook")]
+    #[description("Triggers execution when an HTTP request is received on a local path")]
+    #[keywords(&["webhook", "http", "request", "api", "curl"])]
+    #[category("Events")]
+    #[emission_type(HttpWebhook { default_path: "/webhook", default_method: "POST" })]
+    #[execution_output("received", "Received")]
+    fn webhook(
+        #[context] ctx: &BrickContext,
+        #[argument] #[label("Path")] path: String = "/webhook",
+        #[argument] #[label("Method")] method: String = "POST",
+        #[argument] #[label("Port")] port: String = "7878",
+        #[argument] #[label("Response Timeout (ms)")] timeout_ms: String = "30000",
+        #[argument] #[label("Concurrency")] concurrency: String = "queue"
+    ) -> (
+        #[label("Method")] String,
+        #[label("Path")] String,
+        #[label("Body")] String,
+        #[label("Headers")] String,
+        #[label("Query")] String
+    ) {
+        let request = ctx.event().http_request().cloned().unwrap_or_default();
+
+        ctx.trigger("received");
+        (
+            request.method,
+            request.path,
+            request.body,
+            request.headers,
+            request.query,
+        )
+    }
+}
+
+// Respond HTTP brick - answers the webhook request that triggered the current flow
+#[cfg(feature = "net")]
+brick! {
+    #[id("respond_http")]
+    #[label("Respond HTTP")]
+    #[description("Sends a response back to the caller of the webhook that triggered this flow")]
+    #[keywords(&["webhook", "http", "response", "reply", "api"])]
+    #[category("Events")]
+    #[execution_input("execute", "Execute")]
+    #[execution_output("sent", "Sent")]
+    fn respond_http(
+        #[context] ctx: &BrickContext,
+        #[input] #[label("Status")] status: i32 = 200,
+        #[input] #[label("Body")] body: String,
+        #[input] #[label("Headers")] headers: String = "{}"
+    ) -> (
+        #[label("Delivered")] bool
+    ) {
+        let delivered = match ctx.event().http_request() {
+            Some(request) => {
+                let response = HttpResponseData {
+                    status: u16::try_from(status).unwrap_or(500),
+                    body,
+                    headers,
+                };
+                webhook::respond(&request.request_id, response).is_ok()
+            }
+            // Not triggered by a webhook, there is nobody to answer
+            None => false,
+        };
+
+        ctx.trigger("sent");
+        (delivered,)
+    }
+}
+
+// File watch brick - emits when a file matching the pattern changes
+#[cfg(feature = "fs")]
+brick! {
+    #[id("file_watch")]
+    #[label("File Watch")]
+    #[description("Triggers execution when a file matching a glob pattern is created, modified, deleted or renamed")]
+    #[keywords(&["file", "watch", "folder", "directory", "change", "glob"])]
+    #[category("Events")]
+    #[emission_type(FileWatcher { default_pattern: "*" })]
+    #[execution_output("changed", "Changed")]
+    fn file_watch(
+        #[context] ctx: &BrickContext,
+        #[argument] #[label("Directory")] directory: String = ".",
+        #[argument] #[label("Pattern")] pattern: String = "*",
+        #[argument] #[label("Debounce (ms)")] debounce_ms: String = "200",
+        #[argument] #[label("Concurrency")] concurrency: String = "queue"
+    ) -> (
+        #[label("Path")] String,
+        #[label("Event Type")] String
+    ) {
+        let (path, event_type) = match ctx.event().file_change() {
+            Some((path, event_type)) => (path.to_string(), event_type.as_str().to_string()),
+            None => (String::new(), String::new()),
+        };
+
+        ctx.trigger("changed");
+        (path, event_type)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -120,4 +235,70 @@ mod tests {
         // Should have two outputs (tick_count and timestamp)
         assert_eq!(brick.outputs.len(), 2);
     }
+
+    #[cfg(feature = "net")]
+    #[test]
+    fn test_webhook_brick_metadata() {
+        let brick = webhook_brick();
+        assert_eq!(brick.id, "webhook");
+        assert_eq!(brick.category, "Events");
+
+        // Check emission_type is HttpWebhook with the default route
+        match &brick.emission_type {
+            crate::bricks::types::BrickEmissionType::HttpWebhook {
+                default_path,
+                default_method,
+            } => {
+                assert_eq!(default_path, "/webhook");
+                assert_eq!(default_method, "POST");
+            }
+            _ => panic!("Expected HttpWebhook emission type"),
+        }
+
+        assert_eq!(brick.arguments.len(), 5);
+        assert_eq!(brick.arguments[4].id, "concurrency");
+        assert_eq!(brick.execution_outputs.len(), 1);
+        assert_eq!(brick.execution_outputs[0].id, "received");
+        assert_eq!(brick.execution_inputs.len(), 0);
+
+        // method, path, body, headers and query
+        assert_eq!(brick.outputs.len(), 5);
+        assert_eq!(brick.outputs[4].label, "Query");
+    }
+
+    #[cfg(feature = "net")]
+    #[test]
+    fn test_respond_http_brick_metadata() {
+        let brick = respond_http_brick();
+        assert_eq!(brick.id, "respond_http");
+        assert_eq!(brick.execution_inputs.len(), 1);
+        assert_eq!(brick.execution_outputs[0].id, "sent");
+
+        // status, body and headers
+        assert_eq!(brick.inputs.len(), 3);
+        assert_eq!(brick.inputs[0].default_value, Some("200".to_string()));
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn test_file_watch_brick_metadata() {
+        let brick = file_watch_brick();
+        assert_eq!(brick.id, "file_watch");
+        assert_eq!(brick.category, "Events");
+
+        match &brick.emission_type {
+            crate::bricks::types::BrickEmissionType::FileWatcher { default_pattern } => {
+                assert_eq!(default_pattern, "*");
+            }
+            _ => panic!("Expected FileWatcher emission type"),
+        }
+
+        // directory, pattern, debounce and concurrency
+        assert_eq!(brick.arguments.len(), 4);
+        assert_eq!(brick.execution_outputs[0].id, "changed");
+        assert_eq!(brick.execution_inputs.len(), 0);
+
+        // path and event type
+        assert_eq!(brick.outputs.len(), 2);
+    }
 }
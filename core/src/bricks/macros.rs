@@ -1,5 +1,13 @@
 /// Brick macro supporting separate attribute syntax.
 /// Each function parameter MUST have one of: #[argument], #[input], or #[output]
+/// A single `#[context] ctx: &BrickContext` parameter gives the brick access to its
+/// execution context (event data, firing execution outputs, logging, cancellation)
+/// Bricks that wait on I/O can be declared as `async fn`, the engine awaits them on its
+/// tokio runtime instead of blocking a worker
+/// Bricks that can fail return `Result<(...), BrickError>`; flow bricks declared this way get
+/// an optional `error` execution output and an `error_message` output for handling failures
+/// Changing a brick's interface means bumping its `#[version(n)]`, with a
+/// `#[migration(n, fn)]` rewriting nodes saved with version n - 1 (see `NodeMigration`)
 ///
 /// Usage:
 /// ```rust,ignore
@@ -61,20 +69,41 @@ macro_rules! brick {
                $return_type, $body)
     };
 
+    // `async fn` bricks: expand the regular patterns in async mode
+    (
+        $(#[$meta:ident $(($($meta_args:tt)*))?])*
+        async fn $($rest:tt)*
+    ) => {
+        brick!([async] $(#[$meta $(($($meta_args)*))?])* fn $($rest)*);
+    };
+
+    // Bricks returning `Result<(...), BrickError>`: expand the regular patterns in fallible mode
+    (
+        $([$($mode:ident)*])?
+        $(#[$meta:ident $(($($meta_args:tt)*))?])*
+        fn $fn_name:ident($($params:tt)*) -> Result<($($outputs:tt)*), BrickError>
+        $body:block
+    ) => {
+        brick!([$($($mode)*)? fallible] $(#[$meta $(($($meta_args)*))?])* fn $fn_name($($params)*) -> ($($outputs)*) $body);
+    };
+
     // Pattern with execution inputs and outputs - single tuple return
     (
+        $([$($mode:ident)*])?
         #[id($id:expr)]
         $(#[label($label:expr)])?
         $(#[description($description:expr)])?
         $(#[keywords($keywords:expr)])?
         #[category($category:expr)]
+        $(#[version($version:expr)])?
+        $(#[migration($migration_version:expr, $migration:expr)])*
         $(#[emission_type($($emission_type_args:tt)*)])?
         $(#[execution_input($($exec_input_args:tt)*)])*
         $(#[execution_output($($exec_output_args:tt)*)])*
         fn $fn_name:ident(
-            $($(#[$param_attr:ident$(($($param_attr_content:tt)*))? ])+ $param_name:ident: $param_type:ident $(= $default:expr)?),*
+            $($(#[$param_attr:ident$(($($param_attr_content:tt)*))? ])+ $param_name:ident: $param_type:ty $(= $default:expr)?),*
         ) -> (
-            $(#[$output_attr:ident$(($($output_attr_content:tt)*))? ])+ $output_type:ident
+            $(#[$output_attr:ident$(($($output_attr_content:tt)*))? ])+ $output_type:ty
         )
         $body:block
     ) => {
@@ -85,19 +114,16 @@ macro_rules! brick {
 
         paste::paste! {
             // Define the actual function
-            fn $fn_name($($param_name: $param_type),*) -> ($output_type,) $body
+            brick!(@define_fn [$($($mode)*)?] $fn_name($($param_name: $param_type),*) -> ($output_type,) $body);
 
             // Define the execution wrapper
-            #[allow(unused_variables)]
-            pub fn [<$fn_name _execution>](
-                args: Vec<crate::bricks::types::BrickArgumentValue>,
-                inputs: Vec<crate::bricks::types::BrickInputValue>
-            ) -> Vec<crate::bricks::types::BrickOutputValue> {
+            brick!(@define_execution [$($($mode)*)?] $fn_name, context, args, inputs, {
                 // Extract parameters based on their attributes
                 $(
                     let $param_name = brick!(@get_param_value_with_attrs
                         [$(#[$param_attr$(($($param_attr_content)*))? ])+],
                         $param_type,
+                        context,
                         &args,
                         &inputs,
                         stringify!($param_name),
@@ -106,17 +132,20 @@ macro_rules! brick {
                 )*
 
                 // Call the function
-                let result = $fn_name($($param_name),*);
+                let result = brick!(@call [$($($mode)*)?] $fn_name($($param_name),*));
 
                 // Return outputs from single tuple element
                 let mut outputs = Vec::new();
 
                 brick!(@add_tuple_execution_outputs outputs, result, [([$(#[$output_attr$(($($output_attr_content)*))? ])+], $output_type)]);
 
-                outputs
-            }
+                outputs.extend(brick!(@error_message_outputs [$($($mode)*)?]));
+
+                Ok(outputs)
+            });
 
             // Generate the brick structure function
+            #[allow(unused_mut, clippy::vec_init_then_push)]
             pub fn [<$fn_name _brick>]() -> crate::bricks::types::Brick {
                 let mut arguments = Vec::new();
                 let mut inputs = Vec::new();
@@ -150,12 +179,17 @@ macro_rules! brick {
                 // Add output from single tuple return type
                 brick!(@add_tuple_outputs outputs, dummy_result, [([$(#[$output_attr$(($($output_attr_content)*))? ])+], $output_type)]);
 
+                // Add the error branch of fallible bricks
+                brick!(@add_error_outputs [$($($mode)*)?] execution_inputs, execution_outputs, outputs);
+
                 crate::bricks::types::Brick {
                     id: $id.to_string(),
                     label: brick!(@get_label_or_default $($label)?),
                     description: brick!(@get_description_or_default $($description)?),
                     keywords: brick!(@get_keywords_or_default $($keywords)?),
                     category: brick!(@get_category_or_default $category),
+                    version: brick!(@get_version_or_default $($version)?),
+                    migrations: vec![$(($migration_version, $migration as crate::bricks::types::BrickMigrationFn)),*],
                     arguments,
                     inputs,
                     outputs,
@@ -163,6 +197,7 @@ macro_rules! brick {
                     execution_outputs,
                     emission_type: brick!(@get_emission_type $($($emission_type_args)*)?),
                     execution: [<$fn_name _execution>],
+                    async_execution: brick!(@async_execution [$($($mode)*)?] $fn_name),
                 }
             }
         }
@@ -170,18 +205,21 @@ macro_rules! brick {
 
     // Pattern with execution inputs and outputs - multiple tuple return
     (
+        $([$($mode:ident)*])?
         #[id($id:expr)]
         $(#[label($label:expr)])?
         $(#[description($description:expr)])?
         $(#[keywords($keywords:expr)])?
         #[category($category:expr)]
+        $(#[version($version:expr)])?
+        $(#[migration($migration_version:expr, $migration:expr)])*
         $(#[emission_type($($emission_type_args:tt)*)])?
         $(#[execution_input($($exec_input_args:tt)*)])*
         $(#[execution_output($($exec_output_args:tt)*)])*
         fn $fn_name:ident(
-            $($(#[$param_attr:ident$(($($param_attr_content:tt)*))? ])+ $param_name:ident: $param_type:ident $(= $default:expr)?),*
+            $($(#[$param_attr:ident$(($($param_attr_content:tt)*))? ])+ $param_name:ident: $param_type:ty $(= $default:expr)?),*
         ) -> (
-            $($(#[$output_attr:ident$(($($output_attr_content:tt)*))? ])+ $output_type:ident),+
+            $($(#[$output_attr:ident$(($($output_attr_content:tt)*))? ])+ $output_type:ty),+
         )
         $body:block
     ) => {
@@ -192,19 +230,16 @@ macro_rules! brick {
 
         paste::paste! {
             // Define the actual function
-            fn $fn_name($($param_name: $param_type),*) -> ($($output_type),+) $body
+            brick!(@define_fn [$($($mode)*)?] $fn_name($($param_name: $param_type),*) -> ($($output_type),+) $body);
 
             // Define the execution wrapper
-            #[allow(unused_variables)]
-            pub fn [<$fn_name _execution>](
-                args: Vec<crate::bricks::types::BrickArgumentValue>,
-                inputs: Vec<crate::bricks::types::BrickInputValue>
-            ) -> Vec<crate::bricks::types::BrickOutputValue> {
+            brick!(@define_execution [$($($mode)*)?] $fn_name, context, args, inputs, {
                 // Extract parameters based on their attributes
                 $(
                     let $param_name = brick!(@get_param_value_with_attrs
                         [$(#[$param_attr$(($($param_attr_content)*))? ])+],
                         $param_type,
+                        context,
                         &args,
                         &inputs,
                         stringify!($param_name),
@@ -213,17 +248,20 @@ macro_rules! brick {
                 )*
 
                 // Call the function
-                let result = $fn_name($($param_name),*);
+                let result = brick!(@call [$($($mode)*)?] $fn_name($($param_name),*));
 
                 // Return outputs from tuple elements
                 let mut outputs = Vec::new();
 
                 brick!(@add_tuple_execution_outputs outputs, result, [$(([$(#[$output_attr$(($($output_attr_content)*))? ])+], $output_type)),+]);
 
-                outputs
-            }
+                outputs.extend(brick!(@error_message_outputs [$($($mode)*)?]));
+
+                Ok(outputs)
+            });
 
             // Generate the brick structure function
+            #[allow(unused_mut, clippy::vec_init_then_push)]
             pub fn [<$fn_name _brick>]() -> crate::bricks::types::Brick {
                 let mut arguments = Vec::new();
                 let mut inputs = Vec::new();
@@ -257,12 +295,17 @@ macro_rules! brick {
                 // Add outputs from tuple return type
                 brick!(@add_tuple_outputs outputs, dummy_result, [$(([$(#[$output_attr$(($($output_attr_content)*))? ])+], $output_type)),+]);
 
+                // Add the error branch of fallible bricks
+                brick!(@add_error_outputs [$($($mode)*)?] execution_inputs, execution_outputs, outputs);
+
                 crate::bricks::types::Brick {
                     id: $id.to_string(),
                     label: brick!(@get_label_or_default $($label)?),
                     description: brick!(@get_description_or_default $($description)?),
                     keywords: brick!(@get_keywords_or_default $($keywords)?),
                     category: brick!(@get_category_or_default $category),
+                    version: brick!(@get_version_or_default $($version)?),
+                    migrations: vec![$(($migration_version, $migration as crate::bricks::types::BrickMigrationFn)),*],
                     arguments,
                     inputs,
                     outputs,
@@ -270,6 +313,7 @@ macro_rules! brick {
                     execution_outputs,
                     emission_type: brick!(@get_emission_type $($($emission_type_args)*)?),
                     execution: [<$fn_name _execution>],
+                    async_execution: brick!(@async_execution [$($($mode)*)?] $fn_name),
                 }
             }
         }
@@ -277,15 +321,18 @@ macro_rules! brick {
 
     // Mixed parameter types with single tuple return (1-tuple)
     (
+        $([$($mode:ident)*])?
         #[id($id:expr)]
         $(#[label($label:expr)])?
         $(#[description($description:expr)])?
         $(#[keywords($keywords:expr)])?
         #[category($category:expr)]
+        $(#[version($version:expr)])?
+        $(#[migration($migration_version:expr, $migration:expr)])*
         fn $fn_name:ident(
-            $($(#[$param_attr:ident$(($($param_attr_content:tt)*))? ])+ $param_name:ident: $param_type:ident $(= $default:expr)?),*
+            $($(#[$param_attr:ident$(($($param_attr_content:tt)*))? ])+ $param_name:ident: $param_type:ty $(= $default:expr)?),*
         ) -> (
-            $(#[$output_attr:ident$(($($output_attr_content:tt)*))? ])+ $output_type:ident
+            $(#[$output_attr:ident$(($($output_attr_content:tt)*))? ])+ $output_type:ty
         )
         $body:block
     ) => {
@@ -296,19 +343,16 @@ macro_rules! brick {
 
         paste::paste! {
             // Define the actual function
-            fn $fn_name($($param_name: $param_type),*) -> ($output_type,) $body
+            brick!(@define_fn [$($($mode)*)?] $fn_name($($param_name: $param_type),*) -> ($output_type,) $body);
 
             // Define the execution wrapper
-            #[allow(unused_variables)]
-            pub fn [<$fn_name _execution>](
-                args: Vec<crate::bricks::types::BrickArgumentValue>,
-                inputs: Vec<crate::bricks::types::BrickInputValue>
-            ) -> Vec<crate::bricks::types::BrickOutputValue> {
+            brick!(@define_execution [$($($mode)*)?] $fn_name, context, args, inputs, {
                 // Extract parameters based on their attributes
                 $(
                     let $param_name = brick!(@get_param_value_with_attrs
                         [$(#[$param_attr$(($($param_attr_content)*))? ])+],
                         $param_type,
+                        context,
                         &args,
                         &inputs,
                         stringify!($param_name),
@@ -317,17 +361,18 @@ macro_rules! brick {
                 )*
 
                 // Call the function
-                let result = $fn_name($($param_name),*);
+                let result = brick!(@call [$($($mode)*)?] $fn_name($($param_name),*));
 
                 // Return outputs from single tuple element
                 let mut outputs = Vec::new();
 
                 brick!(@add_tuple_execution_outputs outputs, result, [([$(#[$output_attr$(($($output_attr_content)*))? ])+], $output_type)]);
 
-                outputs
-            }
+                Ok(outputs)
+            });
 
             // Generate the brick structure function
+            #[allow(unused_mut, clippy::vec_init_then_push)]
             pub fn [<$fn_name _brick>]() -> crate::bricks::types::Brick {
                 let mut arguments = Vec::new();
                 let mut inputs = Vec::new();
@@ -355,12 +400,15 @@ macro_rules! brick {
                     description: brick!(@get_description_or_default $($description)?),
                     keywords: brick!(@get_keywords_or_default $($keywords)?),
                     category: brick!(@get_category_or_default $category),
+                    version: brick!(@get_version_or_default $($version)?),
+                    migrations: vec![$(($migration_version, $migration as crate::bricks::types::BrickMigrationFn)),*],
                     arguments,
                     inputs,
                     outputs,
                     execution_inputs: Vec::new(),
                     execution_outputs: Vec::new(),
                     execution: [<$fn_name _execution>],
+                    async_execution: brick!(@async_execution [$($($mode)*)?] $fn_name),
                 }
             }
         }
@@ -368,15 +416,18 @@ macro_rules! brick {
 
     // Mixed parameter types with tuple return (multiple outputs)
     (
+        $([$($mode:ident)*])?
         #[id($id:expr)]
         $(#[label($label:expr)])?
         $(#[description($description:expr)])?
         $(#[keywords($keywords:expr)])?
         #[category($category:expr)]
+        $(#[version($version:expr)])?
+        $(#[migration($migration_version:expr, $migration:expr)])*
         fn $fn_name:ident(
-            $($(#[$param_attr:ident$(($($param_attr_content:tt)*))? ])+ $param_name:ident: $param_type:ident $(= $default:expr)?),*
+            $($(#[$param_attr:ident$(($($param_attr_content:tt)*))? ])+ $param_name:ident: $param_type:ty $(= $default:expr)?),*
         ) -> (
-            $($(#[$output_attr:ident$(($($output_attr_content:tt)*))? ])+ $output_type:ident),+
+            $($(#[$output_attr:ident$(($($output_attr_content:tt)*))? ])+ $output_type:ty),+
         )
         $body:block
     ) => {
@@ -387,19 +438,16 @@ macro_rules! brick {
 
         paste::paste! {
             // Define the actual function
-            fn $fn_name($($param_name: $param_type),*) -> ($($output_type),+) $body
+            brick!(@define_fn [$($($mode)*)?] $fn_name($($param_name: $param_type),*) -> ($($output_type),+) $body);
 
             // Define the execution wrapper
-            #[allow(unused_variables)]
-            pub fn [<$fn_name _execution>](
-                args: Vec<crate::bricks::types::BrickArgumentValue>,
-                inputs: Vec<crate::bricks::types::BrickInputValue>
-            ) -> Vec<crate::bricks::types::BrickOutputValue> {
+            brick!(@define_execution [$($($mode)*)?] $fn_name, context, args, inputs, {
                 // Extract parameters based on their attributes
                 $(
                     let $param_name = brick!(@get_param_value_with_attrs
                         [$(#[$param_attr$(($($param_attr_content)*))? ])+],
                         $param_type,
+                        context,
                         &args,
                         &inputs,
                         stringify!($param_name),
@@ -408,17 +456,18 @@ macro_rules! brick {
                 )*
 
                 // Call the function
-                let result = $fn_name($($param_name),*);
+                let result = brick!(@call [$($($mode)*)?] $fn_name($($param_name),*));
 
                 // Return outputs from tuple elements
                 let mut outputs = Vec::new();
 
                 brick!(@add_tuple_execution_outputs outputs, result, [$(([$(#[$output_attr$(($($output_attr_content)*))? ])+], $output_type)),+]);
 
-                outputs
-            }
+                Ok(outputs)
+            });
 
             // Generate the brick structure function
+            #[allow(unused_mut, clippy::vec_init_then_push)]
             pub fn [<$fn_name _brick>]() -> crate::bricks::types::Brick {
                 let mut arguments = Vec::new();
                 let mut inputs = Vec::new();
@@ -446,12 +495,15 @@ macro_rules! brick {
                     description: brick!(@get_description_or_default $($description)?),
                     keywords: brick!(@get_keywords_or_default $($keywords)?),
                     category: brick!(@get_category_or_default $category),
+                    version: brick!(@get_version_or_default $($version)?),
+                    migrations: vec![$(($migration_version, $migration as crate::bricks::types::BrickMigrationFn)),*],
                     arguments,
                     inputs,
                     outputs,
                     execution_inputs: Vec::new(),
                     execution_outputs: Vec::new(),
                     execution: [<$fn_name _execution>],
+                    async_execution: brick!(@async_execution [$($($mode)*)?] $fn_name),
                 }
             }
         }
@@ -459,16 +511,19 @@ macro_rules! brick {
 
     // Mixed parameter types with unit return (no output)
     (
+        $([$($mode:ident)*])?
         #[id($id:expr)]
         $(#[label($label:expr)])?
         $(#[description($description:expr)])?
         $(#[keywords($keywords:expr)])?
         #[category($category:expr)]
+        $(#[version($version:expr)])?
+        $(#[migration($migration_version:expr, $migration:expr)])*
         $(#[emission_type($($emission_type_args:tt)*)])?
         $(#[execution_input($($exec_input_args:tt)*)])*
         $(#[execution_output($($exec_output_args:tt)*)])*
         fn $fn_name:ident(
-            $($(#[$param_attr:ident$(($($param_attr_content:tt)*))? ])+ $param_name:ident: $param_type:ident $(= $default:expr)?),*
+            $($(#[$param_attr:ident$(($($param_attr_content:tt)*))? ])+ $param_name:ident: $param_type:ty $(= $default:expr)?),*
         ) -> ()
         $body:block
     ) => {
@@ -479,19 +534,16 @@ macro_rules! brick {
 
         paste::paste! {
             // Define the actual function
-            fn $fn_name($($param_name: $param_type),*) -> () $body
+            brick!(@define_fn [$($($mode)*)?] $fn_name($($param_name: $param_type),*) -> () $body);
 
             // Define the execution wrapper
-            #[allow(unused_variables)]
-            pub fn [<$fn_name _execution>](
-                args: Vec<crate::bricks::types::BrickArgumentValue>,
-                inputs: Vec<crate::bricks::types::BrickInputValue>
-            ) -> Vec<crate::bricks::types::BrickOutputValue> {
+            brick!(@define_execution [$($($mode)*)?] $fn_name, context, args, inputs, {
                 // Extract parameters based on their attributes
                 $(
                     let $param_name = brick!(@get_param_value_with_attrs
                         [$(#[$param_attr$(($($param_attr_content)*))? ])+],
                         $param_type,
+                        context,
                         &args,
                         &inputs,
                         stringify!($param_name),
@@ -500,13 +552,14 @@ macro_rules! brick {
                 )*
 
                 // Call the function
-                $fn_name($($param_name),*);
+                brick!(@call [$($($mode)*)?] $fn_name($($param_name),*));
 
                 // Return empty outputs vector since function returns ()
-                Vec::new()
-            }
+                Ok(brick!(@error_message_outputs [$($($mode)*)?]))
+            });
 
             // Generate the brick structure function
+            #[allow(unused_mut, clippy::vec_init_then_push)]
             pub fn [<$fn_name _brick>]() -> crate::bricks::types::Brick {
                 let mut arguments = Vec::new();
                 let mut inputs = Vec::new();
@@ -539,12 +592,17 @@ macro_rules! brick {
 
                 // No outputs since function returns ()
 
+                // Add the error branch of fallible bricks
+                brick!(@add_error_outputs [$($($mode)*)?] execution_inputs, execution_outputs, outputs);
+
                 crate::bricks::types::Brick {
                     id: $id.to_string(),
                     label: brick!(@get_label_or_default $($label)?),
                     description: brick!(@get_description_or_default $($description)?),
                     keywords: brick!(@get_keywords_or_default $($keywords)?),
                     category: brick!(@get_category_or_default $category),
+                    version: brick!(@get_version_or_default $($version)?),
+                    migrations: vec![$(($migration_version, $migration as crate::bricks::types::BrickMigrationFn)),*],
                     arguments,
                     inputs,
                     outputs,
@@ -552,6 +610,7 @@ macro_rules! brick {
                     execution_outputs,
                     emission_type: brick!(@get_emission_type $($($emission_type_args)*)?),
                     execution: [<$fn_name _execution>],
+                    async_execution: brick!(@async_execution [$($($mode)*)?] $fn_name),
                 }
             }
         }
@@ -559,13 +618,16 @@ macro_rules! brick {
 
     // Mixed parameter types with single return (single output)
     (
+        $([$($mode:ident)*])?
         #[id($id:expr)]
         $(#[label($label:expr)])?
         $(#[description($description:expr)])?
         $(#[keywords($keywords:expr)])?
         #[category($category:expr)]
+        $(#[version($version:expr)])?
+        $(#[migration($migration_version:expr, $migration:expr)])*
         fn $fn_name:ident(
-            $($(#[$param_attr:ident$(($($param_attr_content:tt)*))? ])+ $param_name:ident: $param_type:ident $(= $default:expr)?),*
+            $($(#[$param_attr:ident$(($($param_attr_content:tt)*))? ])+ $param_name:ident: $param_type:ty $(= $default:expr)?),*
         ) -> $return_type:ident
         $body:block
     ) => {
@@ -576,19 +638,16 @@ macro_rules! brick {
 
         paste::paste! {
             // Define the actual function
-            fn $fn_name($($param_name: $param_type),*) -> $return_type $body
+            brick!(@define_fn [$($($mode)*)?] $fn_name($($param_name: $param_type),*) -> $return_type $body);
 
             // Define the execution wrapper
-            #[allow(unused_variables)]
-            pub fn [<$fn_name _execution>](
-                args: Vec<crate::bricks::types::BrickArgumentValue>,
-                inputs: Vec<crate::bricks::types::BrickInputValue>
-            ) -> Vec<crate::bricks::types::BrickOutputValue> {
+            brick!(@define_execution [$($($mode)*)?] $fn_name, context, args, inputs, {
                 // Extract parameters based on their attributes
                 $(
                     let $param_name = brick!(@get_param_value_with_attrs
                         [$(#[$param_attr$(($($param_attr_content)*))? ])+],
                         $param_type,
+                        context,
                         &args,
                         &inputs,
                         stringify!($param_name),
@@ -597,24 +656,24 @@ macro_rules! brick {
                 )*
 
                 // Call the function
-                let result = $fn_name($($param_name),*);
+                let result = brick!(@call [$($($mode)*)?] $fn_name($($param_name),*));
 
                 // Return outputs (including function return value and any output parameters)
                 let mut outputs = Vec::new();
 
-                // Add the function return value (JSON encoded)
-                let json_value = serde_json::to_string(&result).unwrap_or_else(|_| "null".to_string());
+                // Add the function return value
                 outputs.push(crate::bricks::types::BrickOutputValue {
                     id: "result".to_string(),
-                    value: json_value,
+                    value: crate::bricks::value::BrickValue::into_value(result),
                 });
 
                 // Outputs only come from function return value, not from parameters
 
-                outputs
-            }
+                Ok(outputs)
+            });
 
             // Generate the brick structure function
+            #[allow(unused_mut, clippy::vec_init_then_push)]
             pub fn [<$fn_name _brick>]() -> crate::bricks::types::Brick {
                 let mut arguments = Vec::new();
                 let mut inputs = Vec::new();
@@ -646,17 +705,131 @@ macro_rules! brick {
                     description: brick!(@get_description_or_default $($description)?),
                     keywords: brick!(@get_keywords_or_default $($keywords)?),
                     category: brick!(@get_category_or_default $category),
+                    version: brick!(@get_version_or_default $($version)?),
+                    migrations: vec![$(($migration_version, $migration as crate::bricks::types::BrickMigrationFn)),*],
                     arguments,
                     inputs,
                     outputs,
                     execution_inputs: Vec::new(),
                     execution_outputs: Vec::new(),
                     execution: [<$fn_name _execution>],
+                    async_execution: brick!(@async_execution [$($($mode)*)?] $fn_name),
                 }
             }
         }
     };
 
+    // Helper: Define the brick function. Modes are listed in order: `async`, then `fallible`.
+    // Arguments only read by the engine, like the settings of event bricks, go unused
+    (@define_fn [$($mode:ident)*] $fn_name:ident($($param_name:ident: $param_type:ty),*) -> $ret:ty $body:block) => {
+        brick!(@define_fn_with [$($mode)*] $fn_name($($param_name: $param_type),*) -> brick!(@return_type [$($mode)*] $ret) $body);
+    };
+    (@define_fn_with [async $($mode:ident)*] $fn_name:ident($($param_name:ident: $param_type:ty),*) -> $ret:ty $body:block) => {
+        #[allow(unused_variables)]
+        async fn $fn_name($($param_name: $param_type),*) -> $ret $body
+    };
+    (@define_fn_with [$($mode:ident)*] $fn_name:ident($($param_name:ident: $param_type:ty),*) -> $ret:ty $body:block) => {
+        #[allow(unused_variables)]
+        fn $fn_name($($param_name: $param_type),*) -> $ret $body
+    };
+
+    // Helper: Return type of the brick function, wrapped in a Result when fallible
+    (@return_type [$(async)? fallible] $ret:ty) => {
+        Result<$ret, crate::bricks::error::BrickError>
+    };
+    (@return_type [$($mode:ident)*] $ret:ty) => { $ret };
+
+    // Helper: Call the brick function, awaiting it when async and propagating its error when fallible
+    (@call [] $fn_name:ident($($param_name:ident),*)) => {
+        $fn_name($($param_name),*)
+    };
+    (@call [async] $fn_name:ident($($param_name:ident),*)) => {
+        $fn_name($($param_name),*).await
+    };
+    (@call [fallible] $fn_name:ident($($param_name:ident),*)) => {
+        $fn_name($($param_name),*)?
+    };
+    (@call [async fallible] $fn_name:ident($($param_name:ident),*)) => {
+        $fn_name($($param_name),*).await?
+    };
+
+    // Helper: Define the execution wrapper around the brick function. Async bricks get an
+    // `_execution_async` wrapper returning a future, and an `_execution` one that blocks on it
+    (@define_execution [async $($mode:ident)*] $fn_name:ident, $context:ident, $args:ident, $inputs:ident, $body:block) => {
+        paste::paste! {
+            #[allow(unused_variables)]
+            pub fn [<$fn_name _execution_async>]<'a>(
+                $context: &'a crate::bricks::context::BrickContext,
+                $args: Vec<crate::bricks::types::BrickArgumentValue>,
+                $inputs: Vec<crate::bricks::types::BrickInputValue>
+            ) -> crate::bricks::types::BrickFuture<'a> {
+                Box::pin(async move {
+                    let outputs: Result<
+                        Vec<crate::bricks::types::BrickOutputValue>,
+                        crate::bricks::error::BrickError,
+                    > = async move $body.await;
+                    outputs
+                })
+            }
+
+            pub fn [<$fn_name _execution>](
+                $context: &crate::bricks::context::BrickContext,
+                $args: Vec<crate::bricks::types::BrickArgumentValue>,
+                $inputs: Vec<crate::bricks::types::BrickInputValue>
+            ) -> Result<Vec<crate::bricks::types::BrickOutputValue>, crate::bricks::error::BrickError> {
+                crate::bricks::types::block_on([<$fn_name _execution_async>]($context, $args, $inputs))
+            }
+        }
+    };
+    (@define_execution [$($mode:ident)*] $fn_name:ident, $context:ident, $args:ident, $inputs:ident, $body:block) => {
+        paste::paste! {
+            #[allow(unused_variables)]
+            pub fn [<$fn_name _execution>](
+                $context: &crate::bricks::context::BrickContext,
+                $args: Vec<crate::bricks::types::BrickArgumentValue>,
+                $inputs: Vec<crate::bricks::types::BrickInputValue>
+            ) -> Result<Vec<crate::bricks::types::BrickOutputValue>, crate::bricks::error::BrickError> {
+                $body
+            }
+        }
+    };
+
+    // Helper: Async execution function of the brick, if it has one
+    (@async_execution [async $($mode:ident)*] $fn_name:ident) => {
+        paste::paste! {
+            Some([<$fn_name _execution_async>] as crate::bricks::types::BrickAsyncExecutionFn)
+        }
+    };
+    (@async_execution [$($mode:ident)*] $fn_name:ident) => { None };
+
+    // Helper: Fallible flow bricks get an optional `error` execution output, fired instead of
+    // failing the run when it is connected, and an output with the error message
+    (@add_error_outputs [$(async)? fallible] $exec_input_vec:ident, $exec_output_vec:ident, $output_vec:ident) => {
+        if !$exec_input_vec.is_empty() || !$exec_output_vec.is_empty() {
+            $exec_output_vec.push(crate::bricks::types::BrickExecutionOutput {
+                id: crate::bricks::types::ERROR_EXECUTION_OUTPUT.to_string(),
+                label: "Error".to_string(),
+            });
+            $output_vec.push(crate::bricks::types::BrickOutput {
+                id: crate::bricks::types::ERROR_MESSAGE_OUTPUT.to_string(),
+                label: "Error Message".to_string(),
+                r#type: <String as crate::bricks::value::BrickValue>::connection_type(),
+            });
+        }
+    };
+    (@add_error_outputs [$($mode:ident)*] $exec_input_vec:ident, $exec_output_vec:ident, $output_vec:ident) => {};
+
+    // Helper: Value of the error message output after a successful run
+    (@error_message_outputs [$(async)? fallible]) => {
+        vec![crate::bricks::types::BrickOutputValue {
+            id: crate::bricks::types::ERROR_MESSAGE_OUTPUT.to_string(),
+            value: crate::bricks::value::Value::from(""),
+        }]
+    };
+    (@error_message_outputs [$($mode:ident)*]) => {
+        Vec::<crate::bricks::types::BrickOutputValue>::new()
+    };
+
     // Helper: Get label or default
     (@get_label_or_default $label:expr) => { $label.to_string() };
     (@get_label_or_default) => { "Default Label".to_string() };
@@ -671,6 +844,10 @@ macro_rules! brick {
     };
     (@get_keywords_or_default) => { Vec::<String>::new() };
 
+    // Helper: Get version or default to the first one
+    (@get_version_or_default $version:expr) => { $version };
+    (@get_version_or_default) => { crate::bricks::types::FIRST_VERSION };
+
     // Helper: Get category (required, no default)
     (@get_category_or_default $category:expr) => { $category.to_string() };
 
@@ -699,10 +876,10 @@ macro_rules! brick {
     };
 
     // Helper: Get custom default or type default
-    (@get_custom_default_or_type_default $param_type:ident, $default:expr) => {
+    (@get_custom_default_or_type_default $param_type:ty, $default:expr) => {
         brick!(@convert_default_to_string $default)
     };
-    (@get_custom_default_or_type_default $param_type:ident,) => {
+    (@get_custom_default_or_type_default $param_type:ty,) => {
         brick!(@get_default_value $param_type)
     };
 
@@ -712,22 +889,14 @@ macro_rules! brick {
     };
 
     // Helper: Get default value as string
-    (@get_default_value String) => { "".to_string() };
-    (@get_default_value i32) => { "0".to_string() };
-    (@get_default_value f32) => { "0".to_string() };
-    (@get_default_value bool) => { "false".to_string() };
-
-    // Helper: Get default typed value
-    (@get_default_typed_value String) => { String::new() };
-    (@get_default_typed_value i32) => { 0i32 };
-    (@get_default_typed_value f32) => { 0f32 };
-    (@get_default_typed_value bool) => { false };
+    (@get_default_value $param_type:ty) => {
+        <$param_type as crate::bricks::value::BrickValue>::default_raw()
+    };
 
     // Helper: Get return type enum
-    (@get_return_type String) => { crate::bricks::types::ConnectionType::String };
-    (@get_return_type i32) => { crate::bricks::types::ConnectionType::Number };
-    (@get_return_type f32) => { crate::bricks::types::ConnectionType::Number };
-    (@get_return_type bool) => { crate::bricks::types::ConnectionType::Boolean };
+    (@get_return_type $param_type:ty) => {
+        <$param_type as crate::bricks::value::BrickValue>::connection_type()
+    };
 
     // Helper: Check if attribute list contains a specific attribute
     (@has_attr argument, [#[argument] $($rest:tt)*]) => { true };
@@ -748,33 +917,42 @@ macro_rules! brick {
     (@get_attr_label []) => { "".to_string() };
 
     // Helper: Get parameter value based on attributes
-    (@get_param_value_with_attrs $attrs:tt, $param_type:ident, $args:expr, $inputs:expr, $param_name:expr, $custom_default:expr) => {
-        if brick!(@has_attr argument, $attrs) {
-            // Look in arguments - values are JSON encoded
-            let json_value = $args.iter().find(|arg| arg.id == $param_name)
-                .map(|arg| arg.value.clone())
-                .unwrap_or_else(|| {
-                    // Use the custom default directly as JSON value
-                    $custom_default
-                });
-            brick!(@parse_json_to_type $param_type, json_value)
-        } else if brick!(@has_attr input, $attrs) {
-            // Look in inputs - values are JSON encoded
-            let json_value = $inputs.iter().find(|input| input.id == $param_name)
-                .map(|input| input.value.clone())
-                .unwrap_or_else(|| {
-                    // Use the custom default directly as JSON value
-                    $custom_default
-                });
-            brick!(@parse_json_to_type $param_type, json_value)
+    (@get_param_value_with_attrs [#[context]], $param_type:ty, $context:expr, $args:expr, $inputs:expr, $param_name:expr, $custom_default:expr) => {
+        $context
+    };
+    (@get_param_value_with_attrs $attrs:tt, $param_type:ty, $context:expr, $args:expr, $inputs:expr, $param_name:expr, $custom_default:expr) => {
+        if brick!(@has_attr argument, $attrs) || brick!(@has_attr input, $attrs) {
+            let value = if brick!(@has_attr argument, $attrs) {
+                $args.iter().find(|arg| arg.id == $param_name).map(|arg| arg.value.clone())
+            } else {
+                $inputs.iter().find(|input| input.id == $param_name).map(|input| input.value.clone())
+            };
+
+            // Fall back to the declared default when nothing was provided
+            let value = match value {
+                Some(value) => value,
+                None => crate::bricks::value::Value::parse(
+                    &$custom_default,
+                    &brick!(@get_return_type $param_type),
+                )
+                .map_err(crate::bricks::error::BrickError::InvalidInput)?,
+            };
+
+            <$param_type as crate::bricks::value::BrickValue>::from_value(value)
+                .map_err(|e| crate::bricks::error::BrickError::InvalidInput(
+                    format!("Invalid value for '{}': {}", $param_name, e)
+                ))?
         } else {
             // For outputs, use default value (outputs are set by function execution)
-            brick!(@get_default_typed_value $param_type)
+            <$param_type as Default>::default()
         }
     };
 
     // Helper: Process parameter with attributes for brick creation
-    (@process_param_with_attrs $arg_vec:ident, $input_vec:ident, $output_vec:ident, $attrs:tt, $param_name:ident, $param_type:ident, $default_value:expr) => {
+    (@process_param_with_attrs $arg_vec:ident, $input_vec:ident, $output_vec:ident, [#[context]], $($rest:tt)*) => {
+        // The execution context is not part of the brick's interface
+    };
+    (@process_param_with_attrs $arg_vec:ident, $input_vec:ident, $output_vec:ident, $attrs:tt, $param_name:ident, $param_type:ty, $default_value:expr) => {
         let label = {
             let attr_label = brick!(@get_attr_label $attrs);
             if attr_label.is_empty() {
@@ -803,7 +981,7 @@ macro_rules! brick {
     };
 
     // Helper: Add tuple outputs to outputs vector - handle 1-tuple
-    (@add_tuple_outputs $output_vec:ident, $result:ident, [($attrs0:tt, $type0:ident)]) => {
+    (@add_tuple_outputs $output_vec:ident, $result:ident, [($attrs0:tt, $type0:ty)]) => {
         // Single output
         let id0 = {
             let attr_id = brick!(@get_attr_id $attrs0);
@@ -829,7 +1007,7 @@ macro_rules! brick {
     };
 
     // Helper: Add tuple outputs to outputs vector - handle 2-tuple
-    (@add_tuple_outputs $output_vec:ident, $result:ident, [($attrs0:tt, $type0:ident), ($attrs1:tt, $type1:ident)]) => {
+    (@add_tuple_outputs $output_vec:ident, $result:ident, [($attrs0:tt, $type0:ty), ($attrs1:tt, $type1:ty)]) => {
         // First output
         let id0 = {
             let attr_id = brick!(@get_attr_id $attrs0);
@@ -879,6 +1057,90 @@ macro_rules! brick {
 
 
 
+    // Helper: Add tuple outputs to outputs vector - handle 3-tuple
+    (@add_tuple_outputs $output_vec:ident, $result:ident, [($attrs0:tt, $type0:ty), ($attrs1:tt, $type1:ty), ($attrs2:tt, $type2:ty)]) => {
+        brick!(@add_tuple_outputs $output_vec, $result, [($attrs0, $type0), ($attrs1, $type1)]);
+
+        // Third output
+        let id2 = {
+            let attr_id = brick!(@get_attr_id $attrs2);
+            if attr_id.is_empty() {
+                "output_2".to_string()
+            } else {
+                attr_id
+            }
+        };
+        let label2 = {
+            let attr_label = brick!(@get_attr_label $attrs2);
+            if attr_label.is_empty() {
+                "Output 3".to_string()
+            } else {
+                attr_label
+            }
+        };
+        $output_vec.push(crate::bricks::types::BrickOutput {
+            id: id2,
+            label: label2,
+            r#type: brick!(@get_return_type $type2),
+        });
+    };
+
+    // Helper: Add tuple outputs to outputs vector - handle 4-tuple
+    (@add_tuple_outputs $output_vec:ident, $result:ident, [($attrs0:tt, $type0:ty), ($attrs1:tt, $type1:ty), ($attrs2:tt, $type2:ty), ($attrs3:tt, $type3:ty)]) => {
+        brick!(@add_tuple_outputs $output_vec, $result, [($attrs0, $type0), ($attrs1, $type1), ($attrs2, $type2)]);
+
+        // Fourth output
+        let id3 = {
+            let attr_id = brick!(@get_attr_id $attrs3);
+            if attr_id.is_empty() {
+                "output_3".to_string()
+            } else {
+                attr_id
+            }
+        };
+        let label3 = {
+            let attr_label = brick!(@get_attr_label $attrs3);
+            if attr_label.is_empty() {
+                "Output 4".to_string()
+            } else {
+                attr_label
+            }
+        };
+        $output_vec.push(crate::bricks::types::BrickOutput {
+            id: id3,
+            label: label3,
+            r#type: brick!(@get_return_type $type3),
+        });
+    };
+
+    // Helper: Add tuple outputs to outputs vector - handle 5-tuple
+    (@add_tuple_outputs $output_vec:ident, $result:ident, [($attrs0:tt, $type0:ty), ($attrs1:tt, $type1:ty), ($attrs2:tt, $type2:ty), ($attrs3:tt, $type3:ty), ($attrs4:tt, $type4:ty)]) => {
+        brick!(@add_tuple_outputs $output_vec, $result, [($attrs0, $type0), ($attrs1, $type1), ($attrs2, $type2), ($attrs3, $type3)]);
+
+        // Fifth output
+        let id4 = {
+            let attr_id = brick!(@get_attr_id $attrs4);
+            if attr_id.is_empty() {
+                "output_4".to_string()
+            } else {
+                attr_id
+            }
+        };
+        let label4 = {
+            let attr_label = brick!(@get_attr_label $attrs4);
+            if attr_label.is_empty() {
+                "Output 5".to_string()
+            } else {
+                attr_label
+            }
+        };
+        $output_vec.push(crate::bricks::types::BrickOutput {
+            id: id4,
+            label: label4,
+            r#type: brick!(@get_return_type $type4),
+        });
+    };
+
     // Helper: Extract id from attribute list
     (@get_attr_id [#[id($id:expr)] $($rest:tt)*]) => { $id.to_string() };
     (@get_attr_id [#[$other:ident$($other_content:tt)*] $($rest:tt)*]) => { brick!(@get_attr_id [$($rest)*]) };
@@ -889,31 +1151,10 @@ macro_rules! brick {
     (@get_attr_description [#[$other:ident$($other_content:tt)*] $($rest:tt)*]) => { brick!(@get_attr_description [$($rest)*]) };
     (@get_attr_description []) => { "".to_string() };
 
-    // Helper: Parse value from string
-    (@parse_to_type String, $value:expr) => { $value };
-    (@parse_to_type i32, $value:expr) => { $value.parse::<i32>().unwrap_or(0) };
-    (@parse_to_type f32, $value:expr) => { $value.parse::<f32>().unwrap_or(0.0) };
-    (@parse_to_type bool, $value:expr) => { $value.parse::<bool>().unwrap_or(false) };
-
-    // Helper: Parse value from JSON string
-    (@parse_json_to_type String, $json_value:expr) => {
-        serde_json::from_str::<String>(&$json_value).unwrap_or_else(|_| $json_value)
-    };
-    (@parse_json_to_type i32, $json_value:expr) => {
-        serde_json::from_str::<i32>(&$json_value).unwrap_or(0)
-    };
-    (@parse_json_to_type f32, $json_value:expr) => {
-        serde_json::from_str::<f32>(&$json_value).unwrap_or(0.0)
-    };
-    (@parse_json_to_type bool, $json_value:expr) => {
-        serde_json::from_str::<bool>(&$json_value).unwrap_or(false)
-    };
-
     // Helper: Get argument type enum
-    (@get_argument_type String) => { crate::bricks::types::ArgumentType::String };
-    (@get_argument_type i32) => { crate::bricks::types::ArgumentType::Number };
-    (@get_argument_type f32) => { crate::bricks::types::ArgumentType::Number };
-    (@get_argument_type bool) => { crate::bricks::types::ArgumentType::Boolean };
+    (@get_argument_type $param_type:ty) => {
+        <$param_type as crate::bricks::value::BrickValue>::argument_type()
+    };
 
     // Helper: Ensure parameter has valid attributes
     (@ensure_valid_attrs [$($attrs:tt)*] -> $param_name:ident) => {
@@ -927,6 +1168,9 @@ macro_rules! brick {
     (@has_any_required_attr [#[argument] $($rest:tt)*] $param_name:ident) => {
         // Found argument - valid
     };
+    (@has_any_required_attr [#[context]] $param_name:ident) => {
+        // Found context - valid
+    };
     (@has_any_required_attr [#[input($($content:tt)*)] $($rest:tt)*] $param_name:ident) => {
         // Found input with content - valid
     };
@@ -943,7 +1187,7 @@ macro_rules! brick {
     };
     (@has_any_required_attr [] $param_name:ident) => {
         // No required attributes found - compile error
-        compile_error!(concat!("Parameter '", stringify!($param_name), "' must have one of: #[input] or #[argument]. Outputs come from the function return value."));
+        compile_error!(concat!("Parameter '", stringify!($param_name), "' must have one of: #[input], #[argument] or #[context]. Outputs come from the function return value."));
     };
 
     // Helper: Add single execution input with optional label
@@ -974,8 +1218,8 @@ macro_rules! brick {
         });
     };
 
-    // Helper: Add tuple execution outputs (with JSON values) - handle 1-tuple
-    (@add_tuple_execution_outputs $output_vec:ident, $result:ident, [($attrs0:tt, $type0:ident)]) => {
+    // Helper: Add tuple execution outputs (as typed values) - handle 1-tuple
+    (@add_tuple_execution_outputs $output_vec:ident, $result:ident, [($attrs0:tt, $type0:ty)]) => {
         // Single output
         let id0 = {
             let attr_id = brick!(@get_attr_id $attrs0);
@@ -985,15 +1229,14 @@ macro_rules! brick {
                 attr_id
             }
         };
-        let json_value0 = serde_json::to_string(&$result.0).unwrap_or_else(|_| "null".to_string());
         $output_vec.push(crate::bricks::types::BrickOutputValue {
             id: id0,
-            value: json_value0,
+            value: crate::bricks::value::BrickValue::into_value($result.0),
         });
     };
 
-    // Helper: Add tuple execution outputs (with JSON values) - handle 2-tuple
-    (@add_tuple_execution_outputs $output_vec:ident, $result:ident, [($attrs0:tt, $type0:ident), ($attrs1:tt, $type1:ident)]) => {
+    // Helper: Add tuple execution outputs (as typed values) - handle 2-tuple
+    (@add_tuple_execution_outputs $output_vec:ident, $result:ident, [($attrs0:tt, $type0:ty), ($attrs1:tt, $type1:ty)]) => {
         // First output
         let id0 = {
             let attr_id = brick!(@get_attr_id $attrs0);
@@ -1003,10 +1246,9 @@ macro_rules! brick {
                 attr_id
             }
         };
-        let json_value0 = serde_json::to_string(&$result.0).unwrap_or_else(|_| "null".to_string());
         $output_vec.push(crate::bricks::types::BrickOutputValue {
             id: id0,
-            value: json_value0,
+            value: crate::bricks::value::BrickValue::into_value($result.0),
         });
 
         // Second output
@@ -1018,15 +1260,14 @@ macro_rules! brick {
                 attr_id
             }
         };
-        let json_value1 = serde_json::to_string(&$result.1).unwrap_or_else(|_| "null".to_string());
         $output_vec.push(crate::bricks::types::BrickOutputValue {
             id: id1,
-            value: json_value1,
+            value: crate::bricks::value::BrickValue::into_value($result.1),
         });
     };
 
-    // Helper: Add tuple execution outputs (with JSON values) - handle 3-tuple
-    (@add_tuple_execution_outputs $output_vec:ident, $result:ident, [($attrs0:tt, $type0:ident), ($attrs1:tt, $type1:ident), ($attrs2:tt, $type2:ident)]) => {
+    // Helper: Add tuple execution outputs (as typed values) - handle 3-tuple
+    (@add_tuple_execution_outputs $output_vec:ident, $result:ident, [($attrs0:tt, $type0:ty), ($attrs1:tt, $type1:ty), ($attrs2:tt, $type2:ty)]) => {
         brick!(@add_tuple_execution_outputs $output_vec, $result, [($attrs0, $type0), ($attrs1, $type1)]);
 
         // Third output
@@ -1038,15 +1279,14 @@ macro_rules! brick {
                 attr_id
             }
         };
-        let json_value2 = serde_json::to_string(&$result.2).unwrap_or_else(|_| "null".to_string());
         $output_vec.push(crate::bricks::types::BrickOutputValue {
             id: id2,
-            value: json_value2,
+            value: crate::bricks::value::BrickValue::into_value($result.2),
         });
     };
 
-    // Helper: Add tuple execution outputs (with JSON values) - handle 4-tuple (add more as needed)
-    (@add_tuple_execution_outputs $output_vec:ident, $result:ident, [($attrs0:tt, $type0:ident), ($attrs1:tt, $type1:ident), ($attrs2:tt, $type2:ident), ($attrs3:tt, $type3:ident)]) => {
+    // Helper: Add tuple execution outputs (as typed values) - handle 4-tuple (add more as needed)
+    (@add_tuple_execution_outputs $output_vec:ident, $result:ident, [($attrs0:tt, $type0:ty), ($attrs1:tt, $type1:ty), ($attrs2:tt, $type2:ty), ($attrs3:tt, $type3:ty)]) => {
         brick!(@add_tuple_execution_outputs $output_vec, $result, [($attrs0, $type0), ($attrs1, $type1), ($attrs2, $type2)]);
 
         // Fourth output
@@ -1058,10 +1298,28 @@ macro_rules! brick {
                 attr_id
             }
         };
-        let json_value3 = serde_json::to_string(&$result.3).unwrap_or_else(|_| "null".to_string());
         $output_vec.push(crate::bricks::types::BrickOutputValue {
             id: id3,
-            value: json_value3,
+            value: crate::bricks::value::BrickValue::into_value($result.3),
+        });
+    };
+
+    // Helper: Add tuple execution outputs (as typed values) - handle 5-tuple
+    (@add_tuple_execution_outputs $output_vec:ident, $result:ident, [($attrs0:tt, $type0:ty), ($attrs1:tt, $type1:ty), ($attrs2:tt, $type2:ty), ($attrs3:tt, $type3:ty), ($attrs4:tt, $type4:ty)]) => {
+        brick!(@add_tuple_execution_outputs $output_vec, $result, [($attrs0, $type0), ($attrs1, $type1), ($attrs2, $type2), ($attrs3, $type3)]);
+
+        // Fifth output
+        let id4 = {
+            let attr_id = brick!(@get_attr_id $attrs4);
+            if attr_id.is_empty() {
+                "output_4".to_string()
+            } else {
+                attr_id
+            }
+        };
+        $output_vec.push(crate::bricks::types::BrickOutputValue {
+            id: id4,
+            value: crate::bricks::value::BrickValue::into_value($result.4),
         });
     };
 }
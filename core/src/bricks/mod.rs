@@ -1,13 +1,20 @@
 pub mod arithmetics;
 pub mod boolean_logic;
+pub mod collections;
 pub mod constants;
+pub mod context;
 pub mod control_flow;
+pub mod conversions;
 pub mod debug;
+pub mod error;
 pub mod events;
 pub mod macros;
+pub mod ports;
+pub mod registry;
 #[cfg(test)]
 mod tests;
 pub mod types;
+pub mod value;
 
 use crate::prelude::*;
 
@@ -15,9 +22,12 @@ pub fn all_bricks() -> Vec<Brick> {
     let mut bricks = vec![];
     bricks.extend(arithmetics::all_bricks());
     bricks.extend(boolean_logic::all_bricks());
+    bricks.extend(collections::all_bricks());
     bricks.extend(constants::all_bricks());
     bricks.extend(control_flow::all_bricks());
+    bricks.extend(conversions::all_bricks());
     bricks.extend(debug::all_bricks());
     bricks.extend(events::all_bricks());
+    bricks.extend(ports::all_bricks());
     bricks
 }
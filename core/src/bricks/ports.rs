@@ -0,0 +1,86 @@
+use crate::bricks::macros::brick;
+use crate::prelude::*;
+
+/// Brick of the nodes that become the inputs of a subgraph
+pub const INPUT: &str = "subgraph_input";
+/// Brick of the nodes that become the outputs of a subgraph
+pub const OUTPUT: &str = "subgraph_output";
+/// Brick of the nodes that become the execution inputs of a subgraph
+pub const ENTRY: &str = "subgraph_entry";
+/// Brick of the nodes that become the execution outputs of a subgraph
+pub const EXIT: &str = "subgraph_exit";
+
+pub fn all_bricks() -> Vec<Brick> {
+    vec![
+        subgraph_input_brick(),
+        subgraph_output_brick(),
+        subgraph_entry_brick(),
+        subgraph_exit_brick(),
+    ]
+}
+
+brick! {
+    #[id("subgraph_input")]
+    #[label("Subgraph Input")]
+    #[description("Passes on a value given to the subgraph, its name is the input's name on the subgraph brick")]
+    #[keywords(&["subgraph", "port", "input", "parameter"])]
+    #[category("Subgraph")]
+    fn subgraph_input(
+        #[argument] #[label("Name")] name: String,
+        #[input] #[label("Value")] value: Value
+    ) -> (
+        #[id("value")] #[label("Value")] Value
+    )
+    {
+        (value,)
+    }
+}
+
+brick! {
+    #[id("subgraph_output")]
+    #[label("Subgraph Output")]
+    #[description("Passes a value out of the subgraph, its name is the output's name on the subgraph brick")]
+    #[keywords(&["subgraph", "port", "output", "result"])]
+    #[category("Subgraph")]
+    fn subgraph_output(
+        #[argument] #[label("Name")] name: String,
+        #[input] #[label("Value")] value: Value
+    ) -> (
+        #[id("value")] #[label("Value")] Value
+    )
+    {
+        (value,)
+    }
+}
+
+brick! {
+    #[id("subgraph_entry")]
+    #[label("Subgraph Entry")]
+    #[description("Where execution enters the subgraph, its name is the execution input's name on the subgraph brick")]
+    #[keywords(&["subgraph", "port", "entry", "start"])]
+    #[category("Subgraph")]
+    #[execution_input("execute", "Execute")]
+    #[execution_output("next", "Next")]
+    fn subgraph_entry(
+        #[context] ctx: &BrickContext,
+        #[argument] #[label("Name")] name: String
+    ) -> () {
+        ctx.trigger("next");
+    }
+}
+
+brick! {
+    #[id("subgraph_exit")]
+    #[label("Subgraph Exit")]
+    #[description("Where execution leaves the subgraph, its name is the execution output's name on the subgraph brick")]
+    #[keywords(&["subgraph", "port", "exit", "end"])]
+    #[category("Subgraph")]
+    #[execution_input("execute", "Execute")]
+    #[execution_output("next", "Next")]
+    fn subgraph_exit(
+        #[context] ctx: &BrickContext,
+        #[argument] #[label("Name")] name: String
+    ) -> () {
+        ctx.trigger("next");
+    }
+}
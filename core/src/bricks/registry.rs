@@ -0,0 +1,160 @@
+use crate::bricks;
+use crate::prelude::*;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{OnceLock, RwLock};
+
+static GLOBAL: OnceLock<BrickRegistry> = OnceLock::new();
+
+/// Bricks available to graphs, by id
+///
+/// The global registry starts out with the built-in bricks, the editor adds the subgraphs of
+/// the graph it opens and plugins add theirs with `register`.
+#[derive(Default)]
+pub struct BrickRegistry {
+    bricks: RwLock<BTreeMap<String, Brick>>,
+    /// Incremented on every change, so callers can tell when to fetch the bricks again
+    version: AtomicU64,
+}
+
+impl BrickRegistry {
+    /// An empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A registry holding the built-in bricks
+    pub fn with_builtins() -> Self {
+        let registry = Self::new();
+        for brick in bricks::all_bricks() {
+            registry.register(brick);
+        }
+        registry
+    }
+
+    /// The registry used by the API, graph loading and the engine
+    pub fn global() -> &'static BrickRegistry {
+        GLOBAL.get_or_init(Self::with_builtins)
+    }
+
+    /// Add a brick, returning the brick it replaced if one with the same id was registered
+    pub fn register(&self, brick: Brick) -> Option<Brick> {
+        let replaced = self.bricks.write().unwrap().insert(brick.id.clone(), brick);
+        self.version.fetch_add(1, Ordering::SeqCst);
+        replaced
+    }
+
+    /// Remove a brick, nodes using it can't be executed anymore
+    pub fn unregister(&self, brick_id: &str) -> Option<Brick> {
+        let removed = self.bricks.write().unwrap().remove(brick_id);
+        if removed.is_some() {
+            self.version.fetch_add(1, Ordering::SeqCst);
+        }
+        removed
+    }
+
+    pub fn get(&self, brick_id: &str) -> Option<Brick> {
+        self.bricks.read().unwrap().get(brick_id).cloned()
+    }
+
+    pub fn contains(&self, brick_id: &str) -> bool {
+        self.bricks.read().unwrap().contains_key(brick_id)
+    }
+
+    /// Every brick, ordered by id
+    pub fn all(&self) -> Vec<Brick> {
+        self.bricks.read().unwrap().values().cloned().collect()
+    }
+
+    /// Categories of the registered bricks, in alphabetical order
+    pub fn categories(&self) -> Vec<String> {
+        let mut categories: Vec<String> = self
+            .bricks
+            .read()
+            .unwrap()
+            .values()
+            .map(|brick| brick.category.clone())
+            .collect();
+        categories.sort();
+        categories.dedup();
+        categories
+    }
+
+    pub fn in_category(&self, category: &str) -> Vec<Brick> {
+        self.filter(|brick| brick.category == category)
+    }
+
+    /// Bricks matching every word of the query in their id, label, keywords or category
+    pub fn search(&self, query: &str) -> Vec<Brick> {
+        let words: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+        self.filter(|brick| {
+            let text = [&brick.id, &brick.label, &brick.category]
+                .into_iter()
+                .chain(&brick.keywords)
+                .map(|text| text.to_lowercase())
+                .collect::<Vec<_>>();
+            words
+                .iter()
+                .all(|word| text.iter().any(|text| text.contains(word)))
+        })
+    }
+
+    /// Number of changes made to the registry so far
+    pub fn version(&self) -> u64 {
+        self.version.load(Ordering::SeqCst)
+    }
+
+    fn filter(&self, predicate: impl Fn(&Brick) -> bool) -> Vec<Brick> {
+        self.bricks
+            .read()
+            .unwrap()
+            .values()
+            .filter(|brick| predicate(brick))
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bricks::arithmetics::add_brick;
+
+    #[test]
+    fn test_register_and_unregister() {
+        let registry = BrickRegistry::new();
+        assert!(registry.get("add").is_none());
+
+        assert!(registry.register(add_brick()).is_none());
+        assert!(registry.contains("add"));
+        let mut renamed = add_brick();
+        renamed.label = "Plus".to_string();
+        assert_eq!(registry.register(renamed).unwrap().label, "Addition");
+        assert_eq!(registry.get("add").unwrap().label, "Plus");
+        assert_eq!(registry.version(), 2);
+
+        assert!(registry.unregister("add").is_some());
+        assert!(registry.unregister("add").is_none());
+        assert!(registry.all().is_empty());
+        assert_eq!(registry.version(), 3);
+    }
+
+    #[test]
+    fn test_builtins_by_category_and_search() {
+        let registry = BrickRegistry::with_builtins();
+        assert_eq!(registry.all().len(), bricks::all_bricks().len());
+
+        assert!(registry.categories().contains(&"Arithmetic".to_string()));
+        assert!(registry
+            .in_category("Arithmetic")
+            .iter()
+            .any(|brick| brick.id == "add"));
+
+        let found: Vec<String> = registry
+            .search("LIST get")
+            .into_iter()
+            .map(|brick| brick.id)
+            .collect();
+        assert_eq!(found, vec!["list_get"]);
+    }
+}
@@ -1,6 +1,7 @@
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 mod tests {
-    use crate::bricks::{all_bricks, macros::brick, types::ConnectionType};
+    use crate::bricks::{all_bricks, macros::brick};
 
     brick! {
         #[id("math")]
@@ -125,7 +126,7 @@ mod tests {
             default_bool.is_ok(),
             "Boolean default value should be parseable"
         );
-        assert_eq!(default_bool.unwrap(), true);
+        assert!(default_bool.unwrap());
 
         let first_input = &brick.inputs[0];
         assert!(first_input.default_value.is_some());
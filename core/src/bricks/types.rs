@@ -1,8 +1,17 @@
+use crate::bricks::context::BrickContext;
+use crate::bricks::error::BrickError;
+use crate::bricks::value::Value;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::pin::Pin;
+
 /// Defines how a brick can be triggered for execution
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, specta::Type)]
+#[derive(Debug, Default, Clone, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "tauri", derive(specta::Type))]
 #[serde(tag = "type", rename_all = "camelCase")]
 pub enum BrickEmissionType {
     /// Traditional flow-based execution (triggered by other nodes)
+    #[default]
     FlowTriggered,
 
     /// Self-emitting: HTTP webhook listener
@@ -24,19 +33,21 @@ pub enum BrickEmissionType {
     ManualTrigger,
 }
 
-impl Default for BrickEmissionType {
-    fn default() -> Self {
-        Self::FlowTriggered
-    }
-}
-
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, specta::Type)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "tauri", derive(specta::Type))]
 pub struct Brick {
     pub id: String,
     pub label: String,
     pub description: String,
     pub keywords: Vec<String>,
     pub category: String,
+    /// Bumped whenever inputs, outputs or arguments change in a way saved nodes have to be
+    /// migrated for
+    #[serde(default = "first_version")]
+    pub version: u32,
+    /// Migrations by the version they migrate nodes to, applied when loading a graph
+    #[serde(skip)]
+    pub migrations: Vec<(u32, BrickMigrationFn)>,
     pub arguments: Vec<BrickArgument>,
     pub inputs: Vec<BrickInput>,
     pub outputs: Vec<BrickOutput>,
@@ -45,22 +56,147 @@ pub struct Brick {
     #[serde(rename = "emissionType")]
     pub emission_type: BrickEmissionType,
     #[serde(skip, default = "default_execution_fn")]
-    pub execution: fn(Vec<BrickArgumentValue>, Vec<BrickInputValue>) -> Vec<BrickOutputValue>,
+    pub execution: BrickExecutionFn,
+    /// Set for `async fn` bricks, the engine awaits these on its tokio runtime instead
+    /// of blocking a worker with `execution`
+    #[serde(skip)]
+    pub async_execution: Option<BrickAsyncExecutionFn>,
+}
+
+/// Version of bricks (and of nodes saved before bricks had versions) that don't declare one
+pub const FIRST_VERSION: u32 = 1;
+
+pub(crate) fn first_version() -> u32 {
+    FIRST_VERSION
+}
+
+/// Rewrites a node saved with the previous version of its brick
+pub type BrickMigrationFn = fn(&mut NodeMigration);
+
+/// The parts of a node a migration can change
+///
+/// Renamed handles are applied to the edges connected to the node once all migrations ran.
+#[derive(Debug, Default)]
+pub struct NodeMigration {
+    pub arguments: BTreeMap<String, String>,
+    pub defaults: BTreeMap<String, String>,
+    /// New names of (execution) inputs, by the name they were saved with
+    pub(crate) inputs: BTreeMap<String, String>,
+    /// New names of (execution) outputs, by the name they were saved with
+    pub(crate) outputs: BTreeMap<String, String>,
+}
+
+impl NodeMigration {
+    pub fn new(arguments: BTreeMap<String, String>, defaults: BTreeMap<String, String>) -> Self {
+        Self {
+            arguments,
+            defaults,
+            ..Self::default()
+        }
+    }
+
+    pub fn rename_argument(&mut self, from: &str, to: &str) {
+        if let Some(value) = self.arguments.remove(from) {
+            self.arguments.insert(to.to_string(), value);
+        }
+    }
+
+    /// Rename an input or execution input, along with its default and connected edges
+    pub fn rename_input(&mut self, from: &str, to: &str) {
+        if let Some(value) = self.defaults.remove(from) {
+            self.defaults.insert(to.to_string(), value);
+        }
+        Self::rename_handle(&mut self.inputs, from, to);
+    }
+
+    /// Rename an output or execution output, along with its connected edges
+    pub fn rename_output(&mut self, from: &str, to: &str) {
+        Self::rename_handle(&mut self.outputs, from, to);
+    }
+
+    /// Handle a saved edge connects to, after the renames so far
+    pub fn input(&self, saved: &str) -> String {
+        self.inputs
+            .get(saved)
+            .cloned()
+            .unwrap_or_else(|| saved.to_string())
+    }
+
+    pub fn output(&self, saved: &str) -> String {
+        self.outputs
+            .get(saved)
+            .cloned()
+            .unwrap_or_else(|| saved.to_string())
+    }
+
+    fn rename_handle(renames: &mut BTreeMap<String, String>, from: &str, to: &str) {
+        // Handles renamed by an earlier migration are saved under their original name
+        match renames.values_mut().find(|current| *current == from) {
+            Some(current) => *current = to.to_string(),
+            None => {
+                renames.insert(from.to_string(), to.to_string());
+            }
+        }
+    }
+}
+
+/// Execution output fallible flow bricks fire when they fail
+pub const ERROR_EXECUTION_OUTPUT: &str = "error";
+/// Output holding the error of a fallible flow brick (empty when it succeeded)
+pub const ERROR_MESSAGE_OUTPUT: &str = "error_message";
+
+/// Executes a brick. Fails when an argument or input can't be converted to the brick's type,
+/// or when a fallible brick returns an error.
+pub type BrickExecutionFn = fn(
+    &BrickContext,
+    Vec<BrickArgumentValue>,
+    Vec<BrickInputValue>,
+) -> Result<Vec<BrickOutputValue>, BrickError>;
+
+/// Future returned by an async brick, borrowing the brick's context
+pub type BrickFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<Vec<BrickOutputValue>, BrickError>> + Send + 'a>>;
+
+/// Executes an `async fn` brick
+pub type BrickAsyncExecutionFn =
+    for<'a> fn(&'a BrickContext, Vec<BrickArgumentValue>, Vec<BrickInputValue>) -> BrickFuture<'a>;
+
+/// Run an async brick to completion on the calling thread
+///
+/// This backs the `execution` function of async bricks, so they can still be called
+/// like any other brick (e.g. as data nodes or in tests). The future is driven on a
+/// separate thread, which keeps this safe to call from inside a tokio runtime.
+pub fn block_on(future: BrickFuture<'_>) -> Result<Vec<BrickOutputValue>, BrickError> {
+    std::thread::scope(|scope| {
+        scope
+            .spawn(move || {
+                tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                    .map_err(|e| {
+                        BrickError::failed(format!("Failed to start async runtime: {}", e))
+                    })?
+                    .block_on(future)
+            })
+            .join()
+            .map_err(|_| BrickError::Panicked)?
+    })
 }
 
 fn default_execution(
+    _context: &BrickContext,
     _args: Vec<BrickArgumentValue>,
     _inputs: Vec<BrickInputValue>,
-) -> Vec<BrickOutputValue> {
-    vec![]
+) -> Result<Vec<BrickOutputValue>, BrickError> {
+    Ok(vec![])
 }
 
-fn default_execution_fn(
-) -> fn(Vec<BrickArgumentValue>, Vec<BrickInputValue>) -> Vec<BrickOutputValue> {
+fn default_execution_fn() -> BrickExecutionFn {
     default_execution
 }
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, specta::Type)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "tauri", derive(specta::Type))]
 pub struct BrickInput {
     pub id: String,
     pub label: String,
@@ -69,14 +205,16 @@ pub struct BrickInput {
     pub default_value: Option<String>,
 }
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, specta::Type)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "tauri", derive(specta::Type))]
 pub struct BrickOutput {
     pub id: String,
     pub label: String,
     pub r#type: ConnectionType,
 }
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, specta::Type)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "tauri", derive(specta::Type))]
 pub struct BrickArgument {
     pub id: String,
     pub label: String,
@@ -87,7 +225,8 @@ pub struct BrickArgument {
     pub default_value: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, specta::Type)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "tauri", derive(specta::Type))]
 pub enum ArgumentType {
     #[serde(rename = "string")]
     String,
@@ -99,7 +238,20 @@ pub enum ArgumentType {
     Enum,
 }
 
-#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, specta::Type)]
+impl ArgumentType {
+    /// The connection type whose values this argument holds
+    pub fn connection_type(&self) -> ConnectionType {
+        match self {
+            ArgumentType::String => ConnectionType::String,
+            ArgumentType::Number => ConnectionType::Number,
+            ArgumentType::Boolean => ConnectionType::Boolean,
+            ArgumentType::Enum => ConnectionType::Enum,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "tauri", derive(specta::Type))]
 pub enum ConnectionType {
     #[serde(rename = "flow")]
     Flow,
@@ -111,34 +263,71 @@ pub enum ConnectionType {
     Boolean,
     #[serde(rename = "enum")]
     Enum,
+    /// Accepts any value
+    #[serde(rename = "any")]
+    Any,
+    /// A list whose items are of the given type
+    #[serde(rename = "list")]
+    List(Box<ConnectionType>),
+    /// A map from string keys to values of the given type
+    #[serde(rename = "map")]
+    Map(Box<ConnectionType>),
+}
+
+impl ConnectionType {
+    /// Check whether an output of the `source` type can be connected to an input of this type
+    pub fn accepts(&self, source: &ConnectionType) -> bool {
+        match (self, source) {
+            (ConnectionType::Flow, ConnectionType::Flow) => true,
+            (ConnectionType::Flow, _) | (_, ConnectionType::Flow) => false,
+            // Checked when the value arrives
+            (ConnectionType::Any, _) | (_, ConnectionType::Any) => true,
+            // Scalars render losslessly as text
+            (
+                ConnectionType::String | ConnectionType::Enum,
+                ConnectionType::String
+                | ConnectionType::Enum
+                | ConnectionType::Number
+                | ConnectionType::Boolean,
+            ) => true,
+            (ConnectionType::List(input), ConnectionType::List(output))
+            | (ConnectionType::Map(input), ConnectionType::Map(output)) => input.accepts(output),
+            (input, output) => input == output,
+        }
+    }
 }
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, specta::Type)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "tauri", derive(specta::Type))]
 pub struct BrickExecutionInput {
     pub id: String,
     pub label: String,
 }
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, specta::Type)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "tauri", derive(specta::Type))]
 pub struct BrickExecutionOutput {
     pub id: String,
     pub label: String,
 }
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, specta::Type)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "tauri", derive(specta::Type))]
 pub struct BrickArgumentValue {
     pub id: String,
-    pub value: String,
+    pub value: Value,
 }
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, specta::Type)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "tauri", derive(specta::Type))]
 pub struct BrickInputValue {
     pub id: String,
-    pub value: String,
+    pub value: Value,
 }
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, specta::Type)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "tauri", derive(specta::Type))]
 pub struct BrickOutputValue {
     pub id: String,
-    pub value: String,
+    pub value: Value,
 }
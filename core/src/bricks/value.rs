@@ -0,0 +1,592 @@
+use crate::bricks::types::{ArgumentType, ConnectionType};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// A typed value flowing between bricks
+///
+/// Serialized as `{ "type": "...", "value": ... }` so the frontend can tell integers
+/// from numbers and bytes from lists when sending values back.
+#[derive(Debug, Default, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "tauri", derive(specta::Type))]
+#[serde(tag = "type", content = "value", rename_all = "camelCase")]
+pub enum Value {
+    #[default]
+    Null,
+    Bool(bool),
+    Integer(#[cfg_attr(feature = "tauri", specta(type = f64))] i64),
+    Number(f64),
+    String(String),
+    List(Vec<Value>),
+    Map(BTreeMap<String, Value>),
+    Bytes(Vec<u8>),
+}
+
+impl Value {
+    /// Name of the value's type, used in error messages
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Null => "null",
+            Value::Bool(_) => "boolean",
+            Value::Integer(_) => "integer",
+            Value::Number(_) => "number",
+            Value::String(_) => "string",
+            Value::List(_) => "list",
+            Value::Map(_) => "map",
+            Value::Bytes(_) => "bytes",
+        }
+    }
+
+    /// Parse a raw value (node argument, default or brick default) for the given connection type.
+    /// Raw values may or may not be JSON encoded, so `"hello"` and `hello` are both accepted
+    /// for strings and `5` and `"5"` for numbers.
+    pub fn parse(raw: &str, r#type: &ConnectionType) -> Result<Value, String> {
+        let trimmed = raw.trim();
+        let unquoted = trimmed.trim_matches('"');
+
+        match r#type {
+            ConnectionType::Flow => Ok(Value::Null),
+            ConnectionType::String | ConnectionType::Enum => Ok(Value::String(
+                serde_json::from_str::<String>(trimmed).unwrap_or_else(|_| raw.to_string()),
+            )),
+            ConnectionType::Number => {
+                if let Ok(integer) = unquoted.parse::<i64>() {
+                    Ok(Value::Integer(integer))
+                } else {
+                    match unquoted.parse::<f64>() {
+                        Ok(number) if number.is_finite() => Ok(Value::Number(number)),
+                        _ => Err(format!("Expected a number, got '{}'", raw)),
+                    }
+                }
+            }
+            ConnectionType::Boolean => match unquoted {
+                "true" => Ok(Value::Bool(true)),
+                "false" => Ok(Value::Bool(false)),
+                _ => Err(format!("Expected a boolean, got '{}'", raw)),
+            },
+            ConnectionType::List(_) => match serde_json::from_str(trimmed) {
+                Ok(json @ serde_json::Value::Array(_)) => Ok(Value::from_json(json)),
+                _ => Err(format!("Expected a JSON list, got '{}'", raw)),
+            },
+            ConnectionType::Map(_) => match serde_json::from_str(trimmed) {
+                Ok(json @ serde_json::Value::Object(_)) => Ok(Value::from_json(json)),
+                _ => Err(format!("Expected a JSON object, got '{}'", raw)),
+            },
+            ConnectionType::Any => Ok(serde_json::from_str(trimmed)
+                .map(Value::from_json)
+                .unwrap_or_else(|_| Value::String(raw.to_string()))),
+        }
+    }
+
+    /// Convert a plain JSON value (whole numbers become integers)
+    pub fn from_json(json: serde_json::Value) -> Value {
+        match json {
+            serde_json::Value::Null => Value::Null,
+            serde_json::Value::Bool(b) => Value::Bool(b),
+            serde_json::Value::Number(n) => match n.as_i64() {
+                Some(i) => Value::Integer(i),
+                None => Value::Number(n.as_f64().unwrap_or(f64::NAN)),
+            },
+            serde_json::Value::String(s) => Value::String(s),
+            serde_json::Value::Array(items) => {
+                Value::List(items.into_iter().map(Value::from_json).collect())
+            }
+            serde_json::Value::Object(map) => Value::Map(
+                map.into_iter()
+                    .map(|(key, value)| (key, Value::from_json(value)))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Total ordering used for sorting and comparing values.
+    /// Integers and numbers compare by value; different types order by type
+    /// (null < boolean < number < string < list < map < bytes).
+    pub fn compare(&self, other: &Value) -> Ordering {
+        fn rank(value: &Value) -> u8 {
+            match value {
+                Value::Null => 0,
+                Value::Bool(_) => 1,
+                Value::Integer(_) | Value::Number(_) => 2,
+                Value::String(_) => 3,
+                Value::List(_) => 4,
+                Value::Map(_) => 5,
+                Value::Bytes(_) => 6,
+            }
+        }
+
+        match (self, other) {
+            (Value::Integer(a), Value::Integer(b)) => a.cmp(b),
+            (Value::Integer(_) | Value::Number(_), Value::Integer(_) | Value::Number(_)) => {
+                let a = f64::try_from(self.clone()).unwrap_or(f64::NAN);
+                let b = f64::try_from(other.clone()).unwrap_or(f64::NAN);
+                a.total_cmp(&b)
+            }
+            (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
+            (Value::String(a), Value::String(b)) => a.cmp(b),
+            (Value::List(a), Value::List(b)) => a
+                .iter()
+                .zip(b)
+                .map(|(a, b)| a.compare(b))
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or_else(|| a.len().cmp(&b.len())),
+            (Value::Map(a), Value::Map(b)) => a
+                .iter()
+                .zip(b)
+                .map(|((key_a, a), (key_b, b))| key_a.cmp(key_b).then_with(|| a.compare(b)))
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or_else(|| a.len().cmp(&b.len())),
+            (Value::Bytes(a), Value::Bytes(b)) => a.cmp(b),
+            _ => rank(self).cmp(&rank(other)),
+        }
+    }
+
+    /// Convert to a plain JSON value (type information beyond JSON is lost)
+    pub fn to_json(&self) -> serde_json::Value {
+        match self {
+            Value::Null => serde_json::Value::Null,
+            Value::Bool(b) => serde_json::Value::Bool(*b),
+            Value::Integer(i) => serde_json::Value::from(*i),
+            Value::Number(n) => serde_json::Value::from(*n),
+            Value::String(s) => serde_json::Value::String(s.clone()),
+            Value::List(items) => {
+                serde_json::Value::Array(items.iter().map(Value::to_json).collect())
+            }
+            Value::Map(map) => serde_json::Value::Object(
+                map.iter()
+                    .map(|(key, value)| (key.clone(), value.to_json()))
+                    .collect(),
+            ),
+            Value::Bytes(bytes) => serde_json::Value::from(bytes.clone()),
+        }
+    }
+
+    fn mismatch(&self, expected: &str) -> String {
+        format!("Expected {}, got {} ({})", expected, self.type_name(), self)
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Null => write!(f, "null"),
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::Integer(i) => write!(f, "{}", i),
+            Value::Number(n) => write!(f, "{}", n),
+            Value::String(s) => write!(f, "{}", s),
+            Value::List(_) | Value::Map(_) | Value::Bytes(_) => write!(f, "{}", self.to_json()),
+        }
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Value::Bool(value)
+    }
+}
+
+impl From<i32> for Value {
+    fn from(value: i32) -> Self {
+        Value::Integer(value as i64)
+    }
+}
+
+impl From<i64> for Value {
+    fn from(value: i64) -> Self {
+        Value::Integer(value)
+    }
+}
+
+impl From<f32> for Value {
+    fn from(value: f32) -> Self {
+        Value::Number(value as f64)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Self {
+        Value::Number(value)
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::String(value)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::String(value.to_string())
+    }
+}
+
+impl From<Vec<Value>> for Value {
+    fn from(value: Vec<Value>) -> Self {
+        Value::List(value)
+    }
+}
+
+impl From<BTreeMap<String, Value>> for Value {
+    fn from(value: BTreeMap<String, Value>) -> Self {
+        Value::Map(value)
+    }
+}
+
+impl From<Vec<u8>> for Value {
+    fn from(value: Vec<u8>) -> Self {
+        Value::Bytes(value)
+    }
+}
+
+impl TryFrom<Value> for bool {
+    type Error = String;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Bool(b) => Ok(b),
+            other => Err(other.mismatch("a boolean")),
+        }
+    }
+}
+
+impl TryFrom<Value> for i64 {
+    type Error = String;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Integer(i) => Ok(i),
+            // Whole numbers are accepted, fractions are not silently truncated
+            Value::Number(n) if n.fract() == 0.0 && n.abs() <= i64::MAX as f64 => Ok(n as i64),
+            other => Err(other.mismatch("an integer")),
+        }
+    }
+}
+
+impl TryFrom<Value> for i32 {
+    type Error = String;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        let integer = i64::try_from(value)?;
+        i32::try_from(integer).map_err(|_| format!("Integer {} is out of range", integer))
+    }
+}
+
+impl TryFrom<Value> for f64 {
+    type Error = String;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Number(n) => Ok(n),
+            Value::Integer(i) => Ok(i as f64),
+            other => Err(other.mismatch("a number")),
+        }
+    }
+}
+
+impl TryFrom<Value> for f32 {
+    type Error = String;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        f64::try_from(value).map(|n| n as f32)
+    }
+}
+
+impl TryFrom<Value> for String {
+    type Error = String;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::String(s) => Ok(s),
+            // Scalars render losslessly as text
+            Value::Bool(_) | Value::Integer(_) | Value::Number(_) => Ok(value.to_string()),
+            other => Err(other.mismatch("a string")),
+        }
+    }
+}
+
+impl TryFrom<Value> for Vec<Value> {
+    type Error = String;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::List(items) => Ok(items),
+            other => Err(other.mismatch("a list")),
+        }
+    }
+}
+
+impl TryFrom<Value> for BTreeMap<String, Value> {
+    type Error = String;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Map(map) => Ok(map),
+            other => Err(other.mismatch("a map")),
+        }
+    }
+}
+
+impl TryFrom<Value> for Vec<u8> {
+    type Error = String;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Bytes(bytes) => Ok(bytes),
+            other => Err(other.mismatch("bytes")),
+        }
+    }
+}
+
+/// Rust types that can be used as brick parameters and outputs
+pub trait BrickValue: Sized {
+    /// Connection type shown on the brick's handles
+    fn connection_type() -> ConnectionType;
+
+    /// Argument type used when the parameter is an argument
+    fn argument_type() -> ArgumentType {
+        ArgumentType::String
+    }
+
+    /// Raw default used when a parameter doesn't declare one
+    fn default_raw() -> String;
+
+    fn from_value(value: Value) -> Result<Self, String>;
+
+    fn into_value(self) -> Value;
+}
+
+macro_rules! impl_scalar_brick_value {
+    ($type:ty, $connection_type:ident, $argument_type:ident, $default:expr) => {
+        impl BrickValue for $type {
+            fn connection_type() -> ConnectionType {
+                ConnectionType::$connection_type
+            }
+
+            fn argument_type() -> ArgumentType {
+                ArgumentType::$argument_type
+            }
+
+            fn default_raw() -> String {
+                $default.to_string()
+            }
+
+            fn from_value(value: Value) -> Result<Self, String> {
+                <$type>::try_from(value)
+            }
+
+            fn into_value(self) -> Value {
+                Value::from(self)
+            }
+        }
+    };
+}
+
+impl_scalar_brick_value!(String, String, String, "");
+impl_scalar_brick_value!(i32, Number, Number, "0");
+impl_scalar_brick_value!(i64, Number, Number, "0");
+impl_scalar_brick_value!(f32, Number, Number, "0");
+impl_scalar_brick_value!(f64, Number, Number, "0");
+impl_scalar_brick_value!(bool, Boolean, Boolean, "false");
+
+/// Any value, passed through without conversion
+impl BrickValue for Value {
+    fn connection_type() -> ConnectionType {
+        ConnectionType::Any
+    }
+
+    fn default_raw() -> String {
+        "null".to_string()
+    }
+
+    fn from_value(value: Value) -> Result<Self, String> {
+        Ok(value)
+    }
+
+    fn into_value(self) -> Value {
+        self
+    }
+}
+
+impl<T: BrickValue> BrickValue for Vec<T> {
+    fn connection_type() -> ConnectionType {
+        ConnectionType::List(Box::new(T::connection_type()))
+    }
+
+    fn default_raw() -> String {
+        "[]".to_string()
+    }
+
+    fn from_value(value: Value) -> Result<Self, String> {
+        match value {
+            Value::List(items) => items
+                .into_iter()
+                .enumerate()
+                .map(|(index, item)| {
+                    T::from_value(item).map_err(|e| format!("Item {}: {}", index, e))
+                })
+                .collect(),
+            other => Err(other.mismatch("a list")),
+        }
+    }
+
+    fn into_value(self) -> Value {
+        Value::List(self.into_iter().map(T::into_value).collect())
+    }
+}
+
+impl<T: BrickValue> BrickValue for BTreeMap<String, T> {
+    fn connection_type() -> ConnectionType {
+        ConnectionType::Map(Box::new(T::connection_type()))
+    }
+
+    fn default_raw() -> String {
+        "{}".to_string()
+    }
+
+    fn from_value(value: Value) -> Result<Self, String> {
+        match value {
+            Value::Map(map) => map
+                .into_iter()
+                .map(|(key, item)| {
+                    let item = T::from_value(item).map_err(|e| format!("Key '{}': {}", key, e))?;
+                    Ok((key, item))
+                })
+                .collect(),
+            other => Err(other.mismatch("a map")),
+        }
+    }
+
+    fn into_value(self) -> Value {
+        Value::Map(
+            self.into_iter()
+                .map(|(key, item)| (key, item.into_value()))
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_raw_values() {
+        let number = ConnectionType::Number;
+        assert_eq!(Value::parse("5", &number).unwrap(), Value::Integer(5));
+        assert_eq!(Value::parse("\"5\"", &number).unwrap(), Value::Integer(5));
+        assert_eq!(Value::parse("2.5", &number).unwrap(), Value::Number(2.5));
+        assert!(Value::parse("abc", &number).is_err());
+        assert!(Value::parse("", &number).is_err());
+
+        let string = ConnectionType::String;
+        assert_eq!(
+            Value::parse("\"hello\"", &string).unwrap(),
+            Value::from("hello")
+        );
+        assert_eq!(
+            Value::parse("hello", &string).unwrap(),
+            Value::from("hello")
+        );
+
+        let boolean = ConnectionType::Boolean;
+        assert_eq!(Value::parse("true", &boolean).unwrap(), Value::Bool(true));
+        assert!(Value::parse("yes", &boolean).is_err());
+    }
+
+    #[test]
+    fn test_conversions_do_not_coerce_bad_data() {
+        assert_eq!(i32::try_from(Value::Integer(7)), Ok(7));
+        assert_eq!(i32::try_from(Value::Number(7.0)), Ok(7));
+        assert!(i32::try_from(Value::Number(7.5)).is_err());
+        assert!(i32::try_from(Value::Integer(i64::MAX)).is_err());
+        assert!(i32::try_from(Value::from("7")).is_err());
+        assert!(f32::try_from(Value::Null).is_err());
+        assert!(bool::try_from(Value::Integer(1)).is_err());
+
+        assert_eq!(f32::try_from(Value::Integer(3)), Ok(3.0));
+        assert_eq!(String::try_from(Value::Integer(3)), Ok("3".to_string()));
+        assert!(String::try_from(Value::List(vec![])).is_err());
+    }
+
+    #[test]
+    fn test_parse_collections() {
+        let numbers = ConnectionType::List(Box::new(ConnectionType::Number));
+        assert_eq!(
+            Value::parse("[1, 2.5]", &numbers).unwrap(),
+            Value::List(vec![Value::Integer(1), Value::Number(2.5)])
+        );
+        assert!(Value::parse("{}", &numbers).is_err());
+
+        let map = ConnectionType::Map(Box::new(ConnectionType::Any));
+        assert!(matches!(
+            Value::parse(r#"{"a": "b"}"#, &map).unwrap(),
+            Value::Map(_)
+        ));
+        assert!(Value::parse("[]", &map).is_err());
+    }
+
+    #[test]
+    fn test_brick_value_collections() {
+        let list = Value::List(vec![Value::Integer(1), Value::Number(2.0)]);
+        assert_eq!(Vec::<i32>::from_value(list.clone()), Ok(vec![1, 2]));
+        assert!(Vec::<String>::from_value(Value::List(vec![Value::Null])).is_err());
+        assert_eq!(
+            vec![1, 2].into_value(),
+            Value::List(vec![1.into(), 2.into()])
+        );
+
+        assert_eq!(
+            Vec::<f32>::connection_type(),
+            ConnectionType::List(Box::new(ConnectionType::Number))
+        );
+        assert_eq!(
+            BTreeMap::<String, Value>::connection_type(),
+            ConnectionType::Map(Box::new(ConnectionType::Any))
+        );
+    }
+
+    #[test]
+    fn test_compare() {
+        assert_eq!(
+            Value::Integer(2).compare(&Value::Number(2.0)),
+            Ordering::Equal
+        );
+        assert_eq!(
+            Value::Integer(1).compare(&Value::Number(1.5)),
+            Ordering::Less
+        );
+        assert_eq!(
+            Value::from("b").compare(&Value::from("a")),
+            Ordering::Greater
+        );
+        assert_eq!(Value::Null.compare(&Value::Bool(false)), Ordering::Less);
+        assert_eq!(
+            Value::List(vec![1.into()]).compare(&Value::List(vec![1.into(), 2.into()])),
+            Ordering::Less
+        );
+    }
+
+    #[test]
+    fn test_serde_round_trip() {
+        let mut map = BTreeMap::new();
+        map.insert("count".to_string(), Value::Integer(3));
+        map.insert("ratio".to_string(), Value::Number(3.0));
+
+        let value = Value::List(vec![
+            Value::Null,
+            Value::Bool(true),
+            Value::from("text"),
+            Value::Map(map),
+            Value::Bytes(vec![1, 2, 3]),
+        ]);
+
+        let json = serde_json::to_string(&value).unwrap();
+        assert_eq!(serde_json::from_str::<Value>(&json).unwrap(), value);
+
+        assert_eq!(
+            serde_json::to_string(&Value::Integer(3)).unwrap(),
+            r#"{"type":"integer","value":3}"#
+        );
+    }
+}
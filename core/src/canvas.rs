@@ -1,43 +1,134 @@
+#[cfg(feature = "tauri")]
 use crate::api::ApiEventTrigger;
 use crate::bricks;
+use crate::bricks::conversions;
+use crate::bricks::registry::BrickRegistry;
+use crate::bricks::types::{first_version, ConnectionType, NodeMigration};
+use crate::engine::debugger::Breakpoint;
+use crate::engine::policy::NodePolicy;
+use crate::engine::validation;
 use crate::prelude::*;
+#[cfg(feature = "tauri")]
+use crate::subgraph;
 use serde_json::Value;
-use std::collections::BTreeMap;
-use std::fs;
-use std::path::Path;
-use tauri::AppHandle;
-use tauri::Runtime;
+use std::collections::{BTreeMap, HashSet};
+#[cfg(feature = "tauri")]
+use std::{fs, path::Path};
+#[cfg(feature = "tauri")]
+use tauri::{AppHandle, Runtime};
 use uuid::Uuid;
 
-#[derive(Clone, serde::Serialize, serde::Deserialize, specta::Type)]
+/// Version of the graph file format written by `Graph::to_json`
+///
+/// Version 2 added brick versions to nodes, files without a version are version 1.
+pub const GRAPH_FORMAT_VERSION: u32 = 2;
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "tauri", derive(specta::Type))]
 pub struct Graph {
     pub nodes: Vec<Node>,
     pub edges: Vec<Edge>,
 }
 impl Graph {
-    fn to_json(&self) -> Result<String, String> {
+    pub fn to_json(&self) -> Result<String, String> {
         let json_value =
             serde_json::to_value(self).map_err(|e| format!("Failed to serialize graph: {}", e))?;
 
-        let json_value = remove_json_fields_from_node(json_value, vec!["brick"]);
+        let mut json_value = remove_json_fields_from_node(json_value, vec!["brick"]);
+        if let Some(object) = json_value.as_object_mut() {
+            object.insert("version".to_string(), GRAPH_FORMAT_VERSION.into());
+        }
 
         serde_json::to_string_pretty(&json_value)
             .map_err(|e| format!("Failed to format JSON: {}", e))
     }
 
     pub fn from_json(json: String) -> Result<Self, String> {
-        let mut graph: Graph =
+        let json_value: Value =
             serde_json::from_str(&json).map_err(|e| format!("Failed to parse graph: {}", e))?;
 
+        // Files written before the format was versioned don't have one
+        let version = json_value
+            .get("version")
+            .and_then(Value::as_u64)
+            .unwrap_or(1);
+        if version > GRAPH_FORMAT_VERSION as u64 {
+            return Err(format!(
+                "Graph format version {} is newer than the supported version {}",
+                version, GRAPH_FORMAT_VERSION
+            ));
+        }
+
+        let mut graph: Graph = serde_json::from_value(json_value)
+            .map_err(|e| format!("Failed to parse graph: {}", e))?;
+
+        graph.migrate(get_brick)?;
         for node in &mut graph.nodes {
             node.data.brick = get_brick(&node.data.brick_id);
         }
 
         Ok(graph)
     }
+
+    /// Bring nodes saved with an older version of their brick up to date
+    ///
+    /// Runs the migrations of every version after the one a node was saved with, in order, and
+    /// renames the handles of the edges connected to migrated nodes. Nodes of unknown bricks
+    /// are left alone, validation reports them.
+    pub fn migrate(&mut self, get_brick: impl Fn(&str) -> Option<Brick>) -> Result<(), String> {
+        let mut migrated = BTreeMap::new();
+
+        for node in &mut self.nodes {
+            let Some(brick) = get_brick(&node.data.brick_id) else {
+                continue;
+            };
+            let saved_version = node.data.brick_version;
+            if saved_version > brick.version {
+                return Err(format!(
+                    "Node '{}' was saved with version {} of brick '{}', which only has version {}",
+                    node.id, saved_version, brick.id, brick.version
+                ));
+            }
+            if saved_version == brick.version {
+                continue;
+            }
+
+            let mut migrations: Vec<_> = brick
+                .migrations
+                .iter()
+                .filter(|(version, _)| *version > saved_version && *version <= brick.version)
+                .collect();
+            migrations.sort_by_key(|(version, _)| *version);
+
+            let mut migration = NodeMigration::new(
+                std::mem::take(&mut node.data.arguments),
+                std::mem::take(&mut node.data.defaults),
+            );
+            for (_, migrate) in migrations {
+                migrate(&mut migration);
+            }
+
+            node.data.arguments = std::mem::take(&mut migration.arguments);
+            node.data.defaults = std::mem::take(&mut migration.defaults);
+            node.data.brick_version = brick.version;
+            migrated.insert(node.id.clone(), migration);
+        }
+
+        for edge in &mut self.edges {
+            if let Some(migration) = migrated.get(&edge.source) {
+                edge.source_handle = migration.output(&edge.source_handle);
+            }
+            if let Some(migration) = migrated.get(&edge.target) {
+                edge.target_handle = migration.input(&edge.target_handle);
+            }
+        }
+
+        Ok(())
+    }
 }
 
-#[derive(Clone, serde::Serialize, serde::Deserialize, specta::Type)]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "tauri", derive(specta::Type))]
 pub struct Node {
     pub id: String,
     pub position: Point,
@@ -45,22 +136,32 @@ pub struct Node {
     pub r#type: String,
 }
 
-#[derive(Clone, serde::Serialize, serde::Deserialize, specta::Type)]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "tauri", derive(specta::Type))]
 pub struct Point {
     pub x: f64,
     pub y: f64,
 }
 
-#[derive(Clone, serde::Serialize, serde::Deserialize, specta::Type)]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "tauri", derive(specta::Type))]
 pub struct NodeData {
     #[serde(rename = "brickId")]
     pub brick_id: String,
     pub brick: Option<Brick>,
     pub arguments: BTreeMap<String, String>,
     pub defaults: BTreeMap<String, String>,
+    #[serde(default)]
+    pub policy: NodePolicy,
+    #[serde(default)]
+    pub breakpoint: Option<Breakpoint>,
+    /// Version of the brick the node was last saved with
+    #[serde(rename = "brickVersion", default = "first_version")]
+    pub brick_version: u32,
 }
 
-#[derive(Clone, serde::Serialize, serde::Deserialize, specta::Type)]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "tauri", derive(specta::Type))]
 pub struct Edge {
     pub id: String,
     pub source: String,
@@ -71,6 +172,7 @@ pub struct Edge {
     pub target_handle: String,
 }
 
+#[cfg(feature = "tauri")]
 pub async fn save_graph<R: Runtime>(
     app_handle: AppHandle<R>,
     graph: &Graph,
@@ -84,12 +186,7 @@ pub async fn save_graph<R: Runtime>(
     if notify_frontend {
         ApiEventTrigger::new(app_handle)
             .graph_updated(graph.clone())
-            .map_err(|e| {
-                format!(
-                    "Failed to notify frontend about graph update: {}",
-                    e.to_string()
-                )
-            })?;
+            .map_err(|e| format!("Failed to notify frontend about graph update: {}", e))?;
     }
 
     Ok(format!("Graph saved to {}", graph_path))
@@ -109,6 +206,7 @@ fn remove_json_fields_from_node(mut json_value: Value, fields: Vec<&str>) -> Val
     json_value
 }
 
+#[cfg(feature = "tauri")]
 pub async fn load_graph<R: Runtime>(
     app_handle: AppHandle<R>,
     graph_path: &str,
@@ -129,10 +227,10 @@ pub async fn load_graph<R: Runtime>(
 }
 
 pub fn get_brick(brick_id: &str) -> Option<bricks::types::Brick> {
-    let bricks = bricks::all_bricks();
-    bricks.into_iter().find(|b| b.id == brick_id)
+    BrickRegistry::global().get(brick_id)
 }
 
+#[cfg(feature = "tauri")]
 pub async fn insert_node<R: Runtime>(
     app_handle: AppHandle<R>,
     graph_path: &str,
@@ -146,15 +244,172 @@ pub async fn insert_node<R: Runtime>(
         return Err(format!("Brick with id '{}' not found", brick_id));
     }
 
-    let brick = brick.unwrap();
+    let node = new_node(brick.unwrap(), position);
+
+    graph.nodes.push(node);
+    save_graph(app_handle, &graph, graph_path, true).await?;
+    Ok(graph)
+}
+
+/// Move nodes of the graph stored at `graph_path` into a new subgraph, see `subgraph::extract`
+#[cfg(feature = "tauri")]
+pub async fn create_subgraph<R: Runtime>(
+    app_handle: AppHandle<R>,
+    graph_path: &str,
+    node_ids: &[String],
+    id: &str,
+    label: &str,
+) -> Result<Graph, String> {
+    let mut graph = load_graph(app_handle.clone(), graph_path).await?;
+    let directory = subgraph::directory_of(Path::new(graph_path));
+    if subgraph::load(&directory, id).is_ok() {
+        return Err(format!("Subgraph '{}' exists already", id));
+    }
+
+    let subgraph = subgraph::extract(&mut graph, node_ids, id, label)?;
+    subgraph::save(&directory, &subgraph)?;
+    BrickRegistry::global().register(subgraph.brick());
+    save_graph(app_handle, &graph, graph_path, true).await?;
+    Ok(graph)
+}
+
+/// Connect two handles in the graph stored at `graph_path`, see `connect_nodes`
+#[cfg(feature = "tauri")]
+pub async fn connect<R: Runtime>(
+    app_handle: AppHandle<R>,
+    graph_path: &str,
+    edge: Edge,
+    convert: bool,
+) -> Result<Graph, String> {
+    let mut graph = load_graph(app_handle.clone(), graph_path).await?;
+    connect_nodes(&mut graph, edge, convert)?;
+    save_graph(app_handle, &graph, graph_path, true).await?;
+    Ok(graph)
+}
+
+/// Add an edge after checking that the output's type fits the input's
+///
+/// With `convert`, a value the input doesn't accept but that can be converted is routed
+/// through a conversion node placed between the two nodes. Data inputs hold a single value,
+/// so an edge already connected to the input is replaced, along with the conversion node it
+/// came from when nothing else uses it.
+pub fn connect_nodes(graph: &mut Graph, edge: Edge, convert: bool) -> Result<(), String> {
+    let brick = |node_id: &str| {
+        let node = graph
+            .nodes
+            .iter()
+            .find(|node| node.id == node_id)
+            .ok_or_else(|| format!("Node '{}' not found", node_id))?;
+        let brick = node.data.brick.as_ref().ok_or_else(|| {
+            format!(
+                "Node '{}' uses unknown brick '{}'",
+                node_id, node.data.brick_id
+            )
+        })?;
+        Ok::<_, String>((brick, &node.position))
+    };
+    let (source_brick, source_position) = brick(&edge.source)?;
+    let (target_brick, target_position) = brick(&edge.target)?;
+
+    let output_type =
+        validation::output_type(source_brick, &edge.source_handle).ok_or_else(|| {
+            format!(
+                "Node '{}' has no output '{}'",
+                edge.source, edge.source_handle
+            )
+        })?;
+    let input_type =
+        validation::input_type(target_brick, &edge.target_handle).ok_or_else(|| {
+            format!(
+                "Node '{}' has no input '{}'",
+                edge.target, edge.target_handle
+            )
+        })?;
+
+    let conversion = if input_type.accepts(&output_type) {
+        None
+    } else {
+        let conversion = convert
+            .then(|| conversions::conversion_brick(&output_type, &input_type))
+            .flatten();
+        if conversion.is_none() {
+            return Err(validation::type_mismatch(&edge, &output_type, &input_type));
+        }
+        conversion
+    };
+    let position = Point {
+        x: (source_position.x + target_position.x) / 2.0,
+        y: (source_position.y + target_position.y) / 2.0,
+    };
+
+    if input_type != ConnectionType::Flow {
+        let mut replaced_sources = Vec::new();
+        graph.edges.retain(|existing| {
+            let replaced =
+                existing.target == edge.target && existing.target_handle == edge.target_handle;
+            if replaced {
+                replaced_sources.push(existing.source.clone());
+            }
+            !replaced
+        });
+        remove_unused_conversions(graph, &replaced_sources);
+    }
+
+    let Some(brick) = conversion else {
+        graph.edges.push(edge);
+        return Ok(());
+    };
+
+    let node = new_node(brick, position);
+    let brick = node
+        .data
+        .brick
+        .as_ref()
+        .expect("new nodes have their brick");
+    graph.edges.push(Edge {
+        id: Uuid::new_v4().to_string(),
+        source: node.id.clone(),
+        target: edge.target.clone(),
+        source_handle: brick.outputs[0].id.clone(),
+        target_handle: edge.target_handle.clone(),
+    });
+    graph.edges.push(Edge {
+        target: node.id.clone(),
+        target_handle: brick.inputs[0].id.clone(),
+        ..edge
+    });
+    graph.nodes.push(node);
+    Ok(())
+}
+
+/// Remove the conversion nodes among `node_ids` whose output no longer goes anywhere,
+/// together with the edge feeding them
+fn remove_unused_conversions(graph: &mut Graph, node_ids: &[String]) {
+    let conversion_ids: Vec<String> = conversions::all_bricks()
+        .into_iter()
+        .map(|brick| brick.id)
+        .collect();
+
+    let unused: HashSet<String> = graph
+        .nodes
+        .iter()
+        .filter(|node| node_ids.contains(&node.id))
+        .filter(|node| conversion_ids.contains(&node.data.brick_id))
+        .filter(|node| !graph.edges.iter().any(|edge| edge.source == node.id))
+        .map(|node| node.id.clone())
+        .collect();
+
+    graph.nodes.retain(|node| !unused.contains(&node.id));
+    graph.edges.retain(|edge| !unused.contains(&edge.target));
+}
 
-    let node = Node {
+/// A node for a brick, with the brick's input defaults filled in
+pub(crate) fn new_node(brick: Brick, position: Point) -> Node {
+    Node {
         id: Uuid::new_v4().to_string(),
         position,
         data: NodeData {
-            brick_id: brick_id.to_string(),
-            brick: Some(brick.clone()),
-            arguments: BTreeMap::new(),
+            brick_id: brick.id.clone(),
             defaults: brick
                 .inputs
                 .iter()
@@ -165,11 +420,212 @@ pub async fn insert_node<R: Runtime>(
                         .map(|v| (input.id.clone(), v.clone()))
                 })
                 .collect(),
+            brick_version: brick.version,
+            brick: Some(brick),
+            arguments: BTreeMap::new(),
+            policy: Default::default(),
+            breakpoint: None,
         },
         r#type: "v1".to_string(),
-    };
+    }
+}
 
-    graph.nodes.push(node);
-    save_graph(app_handle, &graph, graph_path, true).await?;
-    Ok(graph)
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bricks::arithmetics::add_brick;
+    use crate::bricks::control_flow::if_else_brick;
+    use crate::bricks::debug::print_brick;
+    use crate::bricks::macros::brick;
+    use crate::bricks::types::FIRST_VERSION;
+
+    fn graph() -> Graph {
+        Graph {
+            nodes: vec![
+                new_node(add_brick(), Point { x: 0.0, y: 0.0 }),
+                new_node(print_brick(), Point { x: 200.0, y: 100.0 }),
+                new_node(if_else_brick(), Point { x: 400.0, y: 0.0 }),
+            ],
+            edges: vec![],
+        }
+    }
+
+    fn edge(
+        graph: &Graph,
+        source: usize,
+        source_handle: &str,
+        target: usize,
+        target_handle: &str,
+    ) -> Edge {
+        Edge {
+            id: "edge".to_string(),
+            source: graph.nodes[source].id.clone(),
+            target: graph.nodes[target].id.clone(),
+            source_handle: source_handle.to_string(),
+            target_handle: target_handle.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_connect_checks_types() {
+        let mut graph = graph();
+
+        // A number can't decide a condition
+        let number_to_condition = edge(&graph, 0, "output_0", 2, "condition");
+        let error = connect_nodes(&mut graph, number_to_condition, true).unwrap_err();
+        assert!(
+            error.contains("(number) can't be connected to input 'condition'"),
+            "{}",
+            error
+        );
+
+        // Numbers render as text without a conversion
+        let number_to_text = edge(&graph, 0, "output_0", 1, "value");
+        connect_nodes(&mut graph, number_to_text, false).unwrap();
+        assert_eq!(graph.nodes.len(), 3);
+        assert_eq!(graph.edges.len(), 1);
+    }
+
+    #[test]
+    fn test_connect_inserts_conversion() {
+        let mut graph = Graph {
+            nodes: vec![
+                new_node(greet_brick(), Point { x: 0.0, y: 0.0 }),
+                new_node(add_brick(), Point { x: 200.0, y: 100.0 }),
+                new_node(add_brick(), Point { x: 0.0, y: 200.0 }),
+            ],
+            edges: vec![],
+        };
+
+        // Text has to be parsed before it can be added
+        let text_to_number = edge(&graph, 0, "output_0", 1, "a");
+        let error = connect_nodes(&mut graph, text_to_number.clone(), false).unwrap_err();
+        assert!(error.contains("can't be connected"), "{}", error);
+        connect_nodes(&mut graph, text_to_number, true).unwrap();
+
+        let conversion = &graph.nodes[3];
+        assert_eq!(conversion.data.brick_id, "string_to_number");
+        assert_eq!(
+            (conversion.position.x, conversion.position.y),
+            (100.0, 50.0)
+        );
+        assert_eq!(graph.edges.len(), 2);
+        assert_eq!(graph.edges[0].source, conversion.id);
+        assert_eq!(graph.edges[0].target, graph.nodes[1].id);
+        assert_eq!(graph.edges[1].source, graph.nodes[0].id);
+        assert_eq!(graph.edges[1].target, conversion.id);
+        assert!(validation::validate(&graph).iter().all(|d| !d.is_error()));
+
+        // Connecting the input again replaces the conversion along with its edge
+        let direct = edge(&graph, 2, "output_0", 1, "a");
+        connect_nodes(&mut graph, direct, true).unwrap();
+        assert_eq!(graph.nodes.len(), 3);
+        assert_eq!(graph.edges.len(), 1);
+        assert_eq!(graph.edges[0].source, graph.nodes[2].id);
+        assert_eq!(graph.edges[0].target, graph.nodes[1].id);
+    }
+
+    #[test]
+    fn test_connect_keeps_conversion_in_use() {
+        let mut graph = Graph {
+            nodes: vec![
+                new_node(greet_brick(), Point { x: 0.0, y: 0.0 }),
+                new_node(add_brick(), Point { x: 200.0, y: 100.0 }),
+                new_node(add_brick(), Point { x: 0.0, y: 200.0 }),
+            ],
+            edges: vec![],
+        };
+
+        let text_to_number = edge(&graph, 0, "output_0", 1, "a");
+        connect_nodes(&mut graph, text_to_number, true).unwrap();
+        let parsed_to_b = edge(&graph, 3, "output_0", 1, "b");
+        connect_nodes(&mut graph, parsed_to_b, true).unwrap();
+
+        // The conversion still feeds the other input
+        let direct = edge(&graph, 2, "output_0", 1, "a");
+        connect_nodes(&mut graph, direct, true).unwrap();
+        assert_eq!(graph.nodes.len(), 4);
+        assert_eq!(graph.edges.len(), 3);
+        assert!(graph
+            .edges
+            .iter()
+            .any(|edge| edge.source == graph.nodes[3].id && edge.target_handle == "b"));
+    }
+
+    brick! {
+        #[id("greet")]
+        #[label("Greet")]
+        #[description("Greets someone")]
+        #[keywords(&["test"])]
+        #[category("Test")]
+        #[version(3)]
+        #[migration(2, rename_name_and_greeting)]
+        #[migration(3, rename_person)]
+        fn greet(
+            #[input] #[label("Who")] who: String
+        ) -> (
+            #[label("Greeting")] String
+        )
+        {
+            (format!("Hello {}", who),)
+        }
+    }
+
+    fn rename_name_and_greeting(migration: &mut NodeMigration) {
+        migration.rename_input("name", "person");
+        migration.rename_output("greeting", "output_0");
+    }
+
+    fn rename_person(migration: &mut NodeMigration) {
+        migration.rename_input("person", "who");
+    }
+
+    #[test]
+    fn test_migrate_renames_inputs_and_edges() {
+        let mut graph = graph();
+        graph.nodes[1] = new_node(greet_brick(), Point { x: 0.0, y: 0.0 });
+        let greet = &mut graph.nodes[1].data;
+        greet.brick_version = FIRST_VERSION;
+        greet
+            .defaults
+            .insert("name".to_string(), "\"world\"".to_string());
+        graph.edges = vec![
+            edge(&graph, 0, "output_0", 1, "name"),
+            edge(&graph, 1, "greeting", 0, "a"),
+        ];
+
+        graph
+            .migrate(|id| (id == "greet").then(greet_brick))
+            .unwrap();
+
+        let greet = &graph.nodes[1].data;
+        assert_eq!(greet.brick_version, 3);
+        assert_eq!(greet.defaults.get("who").unwrap(), "\"world\"");
+        assert!(!greet.defaults.contains_key("name"));
+        assert_eq!(graph.edges[0].target_handle, "who");
+        assert_eq!(graph.edges[1].source_handle, "output_0");
+        // Handles of other nodes aren't touched
+        assert_eq!(graph.edges[1].target_handle, "a");
+
+        graph.nodes[1].data.brick_version = 4;
+        assert!(graph
+            .migrate(|id| (id == "greet").then(greet_brick))
+            .is_err());
+    }
+
+    #[test]
+    fn test_graph_format_version() {
+        let json = graph().to_json().unwrap();
+        let value: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["version"], GRAPH_FORMAT_VERSION);
+        assert_eq!(value["nodes"][0]["data"]["brickVersion"], FIRST_VERSION);
+
+        // Graphs from before versioning load as version 1
+        let loaded = Graph::from_json(json.replace("\"version\": 2", "\"version\": 1")).unwrap();
+        assert_eq!(loaded.nodes.len(), 3);
+        assert!(loaded.nodes.iter().all(|node| node.data.brick.is_some()));
+
+        let newer = Graph::from_json(json.replace("\"version\": 2", "\"version\": 3"));
+        assert!(newer.is_err());
+    }
 }
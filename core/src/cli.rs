@@ -0,0 +1,288 @@
+/// The `vla` command line: run and check graphs without opening the app
+///
+/// ```text
+/// vla run <graph.json> [--debug]   execute a graph until it finishes, or until Ctrl-C
+///                                  when it has listeners (timers, webhooks, file watchers)
+/// vla validate <graph.json>        print the problems found in a graph
+/// vla list-bricks [query]          print the available bricks
+/// ```
+///
+/// Subgraphs are loaded from the `subgraphs` directory next to the graph file.
+use crate::bricks::registry::BrickRegistry;
+use crate::engine::observer::{self, StdoutObserver};
+use crate::engine::validation::{self, Diagnostic, Severity};
+use crate::engine::{Engine, ExecutionPhase};
+use crate::prelude::*;
+use crate::runtime;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+const USAGE: &str = "Usage:
+  vla run <graph.json> [--debug]   Execute a graph, listeners keep it running until Ctrl-C
+  vla validate <graph.json>        Check a graph for problems
+  vla list-bricks [query]          List the available bricks, optionally matching a query
+  vla                              Open the editor";
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Run { graph: PathBuf, debug: bool },
+    Validate { graph: PathBuf },
+    ListBricks { query: Option<String> },
+    Help,
+}
+
+impl Command {
+    /// Parse the arguments following the program name
+    ///
+    /// `None` when they aren't meant for the command line: no arguments, or anything but a
+    /// subcommand (like a file the OS opens the app with), opens the editor. Missing or extra
+    /// arguments of a subcommand are errors.
+    pub fn parse(args: &[String]) -> Option<Result<Self, String>> {
+        let (subcommand, rest) = args.split_first()?;
+        let rest: Vec<&str> = rest.iter().map(String::as_str).collect();
+
+        let command = match (subcommand.as_str(), rest.as_slice()) {
+            ("help" | "--help" | "-h", _) => Ok(Command::Help),
+            ("run", [graph]) => Ok(Command::Run {
+                graph: PathBuf::from(graph),
+                debug: false,
+            }),
+            ("run", [graph, "--debug"] | ["--debug", graph]) => Ok(Command::Run {
+                graph: PathBuf::from(graph),
+                debug: true,
+            }),
+            ("validate", [graph]) => Ok(Command::Validate {
+                graph: PathBuf::from(graph),
+            }),
+            ("list-bricks", []) => Ok(Command::ListBricks { query: None }),
+            ("list-bricks", words) => Ok(Command::ListBricks {
+                query: Some(words.join(" ")),
+            }),
+            ("run" | "validate", _) => Err(format!("Invalid arguments for '{}'", subcommand)),
+            // Platforms pass their own arguments, or files to open, when launching the app
+            _ => return None,
+        };
+        Some(command)
+    }
+}
+
+/// Run the command line, `None` when the arguments are for the editor instead
+pub fn main(args: &[String]) -> Option<ExitCode> {
+    let command = match Command::parse(args)? {
+        Ok(command) => command,
+        Err(error) => {
+            eprintln!("{}\n\n{}", error, USAGE);
+            return Some(ExitCode::FAILURE);
+        }
+    };
+
+    let result = match command {
+        Command::Run { graph, debug } => run(&graph, debug),
+        Command::Validate { graph } => validate(&graph),
+        Command::ListBricks { query } => {
+            list_bricks(query.as_deref());
+            Ok(true)
+        }
+        Command::Help => {
+            println!("{}", USAGE);
+            Ok(true)
+        }
+    };
+
+    Some(match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(error) => {
+            eprintln!("{}", error);
+            ExitCode::FAILURE
+        }
+    })
+}
+
+/// Load a graph file, with its subgraphs expanded and bricks attached
+pub fn load(path: &Path) -> Result<Graph, String> {
+    runtime::load_graph(path).map_err(|e| e.to_string())
+}
+
+/// Execute a graph, returning whether it ran without errors
+fn run(path: &Path, debug: bool) -> Result<bool, String> {
+    let graph = load(path)?;
+
+    let diagnostics = validation::validate(&graph);
+    print_diagnostics(&graph, &diagnostics);
+    if validation::has_errors(&diagnostics) {
+        return Ok(false);
+    }
+
+    let stop = stop_on_ctrl_c();
+    let node_ids: Vec<String> = graph.nodes.iter().map(|node| node.id.clone()).collect();
+    let mut engine = Engine::with_debug(graph.clone(), debug);
+    engine.add_observer(Arc::new(StdoutObserver::verbose(&graph)));
+    let mut succeeded = true;
+
+    engine.start();
+    while !stop.load(Ordering::SeqCst) {
+        let Some(result) = engine.next() else {
+            break;
+        };
+        if let Err(error) = result {
+            eprintln!("error: {}", error);
+            succeeded = false;
+        }
+    }
+
+    let errored = node_ids.iter().any(|node_id| {
+        engine
+            .node_state(node_id)
+            .is_some_and(|state| state.phase == ExecutionPhase::Errored)
+    });
+    Ok(succeeded && !errored)
+}
+
+/// Print the problems of a graph, returning whether it can be executed
+fn validate(path: &Path) -> Result<bool, String> {
+    let graph = load(path)?;
+    let diagnostics = validation::validate(&graph);
+    print_diagnostics(&graph, &diagnostics);
+    if diagnostics.is_empty() {
+        println!("No problems found");
+    }
+    Ok(!validation::has_errors(&diagnostics))
+}
+
+fn list_bricks(query: Option<&str>) {
+    let registry = BrickRegistry::global();
+    let bricks = match query {
+        Some(query) => registry.search(query),
+        None => registry.all(),
+    };
+
+    let mut categories: Vec<&str> = bricks.iter().map(|b| b.category.as_str()).collect();
+    categories.sort();
+    categories.dedup();
+    for category in categories {
+        println!("{}", category);
+        for brick in bricks.iter().filter(|b| b.category == category) {
+            println!("  {:<24} {}", brick.id, brick.description);
+        }
+    }
+}
+
+fn print_diagnostics(graph: &Graph, diagnostics: &[Diagnostic]) {
+    for diagnostic in diagnostics {
+        let severity = match diagnostic.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        match &diagnostic.node_id {
+            Some(node_id) => eprintln!(
+                "{}: {}: {}",
+                severity,
+                node_name(graph, node_id),
+                diagnostic.message
+            ),
+            None => eprintln!("{}: {}", severity, diagnostic.message),
+        }
+    }
+}
+
+fn node_name(graph: &Graph, node_id: &str) -> String {
+    match graph.nodes.iter().find(|node| node.id == node_id) {
+        Some(node) => observer::node_name(node),
+        None => node_id.to_string(),
+    }
+}
+
+/// Set once Ctrl-C is pressed, a second Ctrl-C exits right away
+fn stop_on_ctrl_c() -> Arc<AtomicBool> {
+    let stop = Arc::new(AtomicBool::new(false));
+    let signalled = Arc::clone(&stop);
+
+    std::thread::spawn(move || {
+        let Ok(runtime) = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+        else {
+            return;
+        };
+        runtime.block_on(async {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                eprintln!("Stopping, press Ctrl-C again to exit immediately");
+                signalled.store(true, Ordering::SeqCst);
+            }
+            if tokio::signal::ctrl_c().await.is_ok() {
+                std::process::exit(130);
+            }
+        });
+    });
+
+    stop
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(Command::parse(&args(&[])), None);
+        assert_eq!(
+            Command::parse(&args(&["run", "graph.json"])),
+            Some(Ok(Command::Run {
+                graph: PathBuf::from("graph.json"),
+                debug: false
+            }))
+        );
+        assert_eq!(
+            Command::parse(&args(&["run", "--debug", "graph.json"])),
+            Some(Ok(Command::Run {
+                graph: PathBuf::from("graph.json"),
+                debug: true
+            }))
+        );
+        assert_eq!(
+            Command::parse(&args(&["list-bricks", "to", "text"])),
+            Some(Ok(Command::ListBricks {
+                query: Some("to text".to_string())
+            }))
+        );
+        assert!(matches!(Command::parse(&args(&["validate"])), Some(Err(_))));
+        assert!(matches!(
+            Command::parse(&args(&["run", "a.json", "b.json"])),
+            Some(Err(_))
+        ));
+        // Arguments the OS passes to the app are left alone
+        assert_eq!(Command::parse(&args(&["-psn_0_12345"])), None);
+        assert_eq!(Command::parse(&args(&["graph.json"])), None);
+    }
+
+    #[test]
+    fn test_run_graph_file() {
+        let directory = std::env::temp_dir().join(format!("vla-cli-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let path = directory.join("graph.json");
+
+        let add = crate::canvas::new_node(
+            crate::bricks::arithmetics::add_brick(),
+            Point { x: 0.0, y: 0.0 },
+        );
+        let graph = Graph {
+            nodes: vec![add],
+            edges: vec![],
+        };
+        fs::write(&path, serde_json::to_string(&graph).unwrap()).unwrap();
+
+        assert_eq!(validate(&path), Ok(true));
+        assert_eq!(run(&path, false), Ok(true));
+        assert!(run(&directory.join("missing.json"), false).is_err());
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
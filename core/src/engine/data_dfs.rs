@@ -1,3 +1,4 @@
+use super::topological::Cycle;
 use crate::prelude::*;
 use std::collections::HashSet;
 
@@ -10,7 +11,7 @@ use std::collections::HashSet;
 /// - Only traverses data edges (non-execution edges)
 /// - Yields dependencies before dependents (post-order traversal)
 /// - Skips already-cached nodes
-/// - Detects and prevents cycles
+/// - Yields an error with the path when the dependencies form a cycle, then stops
 pub struct DataNodeDfsIterator<'a> {
     graph: &'a Graph,
     /// Stack of (node_id, is_visited) for DFS traversal
@@ -58,6 +59,42 @@ impl<'a> DataNodeDfsIterator<'a> {
         self.stack.push((node_id, false));
     }
 
+    /// The cycle closed when the node just visited depends on `dependency`, which is still
+    /// being visited itself
+    ///
+    /// Nodes being visited are on the stack in order, each one a dependency of the one before.
+    fn closed_cycle(&self, dependency: &str) -> Option<Cycle> {
+        let path: Vec<&str> = self
+            .stack
+            .iter()
+            .filter(|(_, visited)| *visited)
+            .map(|(id, _)| id.as_str())
+            .collect();
+        let start = path.iter().position(|id| *id == dependency)?;
+
+        // Data flows from the dependency into the node just visited, then back up the path
+        let mut nodes = vec![dependency];
+        nodes.extend(path[start + 1..].iter().rev());
+        let edges = nodes
+            .iter()
+            .zip(nodes.iter().cycle().skip(1))
+            .filter_map(|(source, target)| {
+                self.graph
+                    .edges
+                    .iter()
+                    .find(|edge| {
+                        edge.source == *source && edge.target == *target && self.is_data_edge(edge)
+                    })
+                    .map(|edge| edge.id.clone())
+            })
+            .collect();
+
+        Some(Cycle {
+            nodes: nodes.into_iter().map(str::to_string).collect(),
+            edges,
+        })
+    }
+
     /// Get all data dependencies for a given node.
     /// Returns node IDs of nodes that provide data to this node.
     fn get_data_dependencies(&self, node_id: &str) -> Vec<String> {
@@ -114,7 +151,7 @@ impl<'a> DataNodeDfsIterator<'a> {
 }
 
 impl<'a> Iterator for DataNodeDfsIterator<'a> {
-    type Item = String;
+    type Item = Result<String, Cycle>;
 
     fn next(&mut self) -> Option<Self::Item> {
         while let Some((node_id, visited)) = self.stack.pop() {
@@ -126,7 +163,7 @@ impl<'a> Iterator for DataNodeDfsIterator<'a> {
                     && self.is_data_node(&node_id)
                 {
                     self.yielded.insert(node_id.clone());
-                    return Some(node_id);
+                    return Some(Ok(node_id));
                 }
             } else {
                 // First time seeing this node - push it back with visited=true
@@ -136,6 +173,11 @@ impl<'a> Iterator for DataNodeDfsIterator<'a> {
                 // Push all dependencies onto the stack
                 let dependencies = self.get_data_dependencies(&node_id);
                 for dep_id in dependencies {
+                    if let Some(cycle) = self.closed_cycle(&dep_id) {
+                        // There is no order to execute these in, so don't yield anything else
+                        self.stack.clear();
+                        return Some(Err(cycle));
+                    }
                     self.push_node(dep_id);
                 }
             }
@@ -148,8 +190,8 @@ impl<'a> Iterator for DataNodeDfsIterator<'a> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::bricks::types::{Brick, BrickInput, BrickOutput, ConnectionType};
-    use crate::prelude::*;
+    use crate::bricks::types::{Brick, BrickInput, BrickOutput, ConnectionType, FIRST_VERSION};
+    use crate::test_utils::edge;
     use std::collections::BTreeMap;
 
     fn create_test_brick(id: &str, has_exec: bool) -> Brick {
@@ -164,6 +206,8 @@ mod tests {
             description: String::new(),
             keywords: vec![],
             category: "test".to_string(),
+            version: FIRST_VERSION,
+            migrations: vec![],
             arguments: vec![],
             inputs: vec![BrickInput {
                 id: "input".to_string(),
@@ -193,12 +237,15 @@ mod tests {
                 vec![]
             },
             emission_type: crate::bricks::types::BrickEmissionType::FlowTriggered,
-            execution: |_args: Vec<BrickArgumentValue>, _inputs: Vec<BrickInputValue>| {
-                vec![BrickOutputValue {
+            execution: |_context: &BrickContext,
+                        _args: Vec<BrickArgumentValue>,
+                        _inputs: Vec<BrickInputValue>| {
+                Ok(vec![BrickOutputValue {
                     id: "output".to_string(),
-                    value: "42".to_string(),
-                }]
+                    value: Value::Integer(42),
+                }])
             },
+            async_execution: None,
         }
     }
 
@@ -211,6 +258,9 @@ mod tests {
                 brick: Some(create_test_brick(brick_id, has_exec)),
                 arguments: BTreeMap::new(),
                 defaults: BTreeMap::new(),
+                policy: Default::default(),
+                breakpoint: None,
+                brick_version: FIRST_VERSION,
             },
             r#type: "v1".to_string(),
         }
@@ -244,9 +294,9 @@ mod tests {
         };
 
         let cached = HashSet::new();
-        let mut iter = DataNodeDfsIterator::new(&graph, "C", &cached);
+        let iter = DataNodeDfsIterator::new(&graph, "C", &cached);
 
-        let result: Vec<String> = iter.collect();
+        let result: Vec<String> = iter.collect::<Result<_, _>>().unwrap();
 
         // Should execute A first (no dependencies), then B, then C
         assert_eq!(result, vec!["A", "B", "C"]);
@@ -299,9 +349,9 @@ mod tests {
         };
 
         let cached = HashSet::new();
-        let mut iter = DataNodeDfsIterator::new(&graph, "D", &cached);
+        let iter = DataNodeDfsIterator::new(&graph, "D", &cached);
 
-        let result: Vec<String> = iter.collect();
+        let result: Vec<String> = iter.collect::<Result<_, _>>().unwrap();
 
         // A should be first, then B and C (order may vary), then D
         assert!(result.len() == 4);
@@ -342,9 +392,9 @@ mod tests {
         let mut cached = HashSet::new();
         cached.insert("A".to_string());
 
-        let mut iter = DataNodeDfsIterator::new(&graph, "C", &cached);
+        let iter = DataNodeDfsIterator::new(&graph, "C", &cached);
 
-        let result: Vec<String> = iter.collect();
+        let result: Vec<String> = iter.collect::<Result<_, _>>().unwrap();
 
         // Should only execute B and C (A is cached)
         assert_eq!(result, vec!["B", "C"]);
@@ -379,11 +429,42 @@ mod tests {
         };
 
         let cached = HashSet::new();
-        let mut iter = DataNodeDfsIterator::new(&graph, "C", &cached);
+        let iter = DataNodeDfsIterator::new(&graph, "C", &cached);
 
-        let result: Vec<String> = iter.collect();
+        let result: Vec<String> = iter.collect::<Result<_, _>>().unwrap();
 
         // Should only return B (data node), not A (flow node)
         assert_eq!(result, vec!["B"]);
     }
+
+    #[test]
+    fn test_cycle() {
+        // Graph: A (data) -> B (data) -> C (data) -> B, with D (data) -> A
+        let graph = Graph {
+            nodes: vec![
+                create_test_node("A", "brick_a", false),
+                create_test_node("B", "brick_b", false),
+                create_test_node("C", "brick_c", false),
+                create_test_node("D", "brick_d", false),
+            ],
+            edges: vec![
+                edge("A", "data_output", "B", "data_input"),
+                edge("B", "data_output", "C", "data_input"),
+                edge("C", "data_output", "B", "data_input"),
+                edge("C", "data_output", "D", "data_input"),
+            ],
+        };
+
+        let cached = HashSet::new();
+        let result: Vec<_> = DataNodeDfsIterator::new(&graph, "D", &cached).collect();
+
+        // Reached from D, the cycle is entered at C
+        assert_eq!(
+            result,
+            vec![Err(Cycle {
+                nodes: vec!["C".to_string(), "B".to_string()],
+                edges: vec!["C-B".to_string(), "B-C".to_string()],
+            })]
+        );
+    }
 }
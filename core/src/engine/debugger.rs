@@ -0,0 +1,369 @@
+/// Stepped execution, breakpoints and inspection of cached values
+///
+/// Every wave asks the debugger before it executes a node. A node that may not run yet
+/// stays with its wave, which reports that it is waiting instead of blocking, so the
+/// engine's iterator (and the stop signal checked around it) keeps going while paused.
+use super::wave::{self, Cache, Wave};
+use super::ExecutionMode;
+use crate::bricks::types::{BrickInputValue, BrickOutputValue};
+use crate::prelude::*;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, Mutex, Weak};
+
+/// Breakpoint on a node, stored with the node in the graph file
+#[derive(Debug, Default, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "tauri", derive(specta::Type))]
+pub struct Breakpoint {
+    /// Only pause when every condition holds for the node's inputs
+    #[serde(default)]
+    pub conditions: Vec<BreakpointCondition>,
+}
+
+impl Breakpoint {
+    /// Check whether the breakpoint pauses a node receiving the given inputs
+    pub fn matches(&self, inputs: &[BrickInputValue]) -> bool {
+        self.conditions.iter().all(|condition| {
+            inputs
+                .iter()
+                .find(|input| input.id == condition.input)
+                .is_some_and(|input| {
+                    condition
+                        .operator
+                        .holds(input.value.compare(&condition.value))
+                })
+        })
+    }
+}
+
+/// Compares an input of a node to a value
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "tauri", derive(specta::Type))]
+pub struct BreakpointCondition {
+    pub input: String,
+    pub operator: ConditionOperator,
+    pub value: Value,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "tauri", derive(specta::Type))]
+#[serde(rename_all = "kebab-case")]
+pub enum ConditionOperator {
+    Equals,
+    NotEquals,
+    LessThan,
+    GreaterThan,
+}
+
+impl ConditionOperator {
+    fn holds(&self, ordering: Ordering) -> bool {
+        match self {
+            ConditionOperator::Equals => ordering.is_eq(),
+            ConditionOperator::NotEquals => ordering.is_ne(),
+            ConditionOperator::LessThan => ordering.is_lt(),
+            ConditionOperator::GreaterThan => ordering.is_gt(),
+        }
+    }
+}
+
+/// Cached outputs of every node in one wave
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "tauri", derive(specta::Type))]
+pub struct WaveCache {
+    #[serde(rename = "waveId")]
+    pub wave_id: String,
+    pub outputs: BTreeMap<String, Vec<BrickOutputValue>>,
+}
+
+/// What a node produced and would receive in one wave
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "tauri", derive(specta::Type))]
+pub struct NodeInspection {
+    #[serde(rename = "waveId")]
+    pub wave_id: String,
+    /// Cached outputs, None when the node hasn't executed in this wave
+    pub outputs: Option<Vec<BrickOutputValue>>,
+    /// Inputs resolved from the wave's cache, None when not all of them are available yet
+    pub inputs: Option<Vec<BrickInputValue>>,
+}
+
+#[derive(Default)]
+struct DebuggerState {
+    paused: bool,
+    /// Nodes that may still run while paused
+    steps: u32,
+    /// Nodes held back since the last pause
+    held: HashSet<String>,
+    /// Nodes held when execution continued, they don't stop on their breakpoint again
+    released: HashSet<String>,
+    breakpoints: HashMap<String, Breakpoint>,
+}
+
+/// Shared by every wave of an engine, see `Engine::debugger`
+pub struct Debugger {
+    graph: Arc<Graph>,
+    state: Mutex<DebuggerState>,
+    /// Caches of the waves started so far, by wave id
+    waves: Mutex<Vec<(u64, Weak<Mutex<Cache>>)>>,
+}
+
+impl Debugger {
+    pub fn new(graph: Arc<Graph>) -> Self {
+        let breakpoints = graph
+            .nodes
+            .iter()
+            .filter_map(|node| Some((node.id.clone(), node.data.breakpoint.clone()?)))
+            .collect();
+
+        Self {
+            graph,
+            state: Mutex::new(DebuggerState {
+                breakpoints,
+                ..DebuggerState::default()
+            }),
+            waves: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Make a wave's cache available for inspection for as long as the wave exists
+    pub(super) fn watch(&self, wave: &Wave) {
+        let mut waves = self.waves.lock().unwrap();
+        waves.retain(|(_, cache)| cache.strong_count() > 0);
+        waves.push((wave.id, Arc::downgrade(wave.cache_handle())));
+    }
+
+    /// Caches of the waves that are still running (or paused)
+    fn live_caches(&self) -> Vec<(u64, Arc<Mutex<Cache>>)> {
+        self.waves
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|(id, cache)| Some((*id, cache.upgrade()?)))
+            .collect()
+    }
+
+    /// Cached outputs of every running wave
+    pub fn caches(&self) -> Vec<WaveCache> {
+        self.live_caches()
+            .into_iter()
+            .map(|(id, cache)| WaveCache {
+                wave_id: id.to_string(),
+                outputs: cache
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .map(|(node_id, outputs)| (node_id.clone(), outputs.clone()))
+                    .collect(),
+            })
+            .collect()
+    }
+
+    /// Outputs and resolved inputs of a node in every running wave
+    pub fn inspect(&self, node_id: &str) -> Result<Vec<NodeInspection>, String> {
+        let node = self
+            .graph
+            .nodes
+            .iter()
+            .find(|node| node.id == node_id)
+            .ok_or_else(|| format!("Node '{}' not found", node_id))?;
+
+        Ok(self
+            .live_caches()
+            .into_iter()
+            .map(|(id, cache)| {
+                let cache = cache.lock().unwrap();
+                NodeInspection {
+                    wave_id: id.to_string(),
+                    outputs: cache.get(node_id).cloned(),
+                    inputs: node.data.brick.as_ref().and_then(|brick| {
+                        wave::build_inputs(&self.graph, &cache, node, brick).ok()
+                    }),
+                }
+            })
+            .collect())
+    }
+
+    /// Replace a cached output value, in one wave or (None) every wave that cached it
+    ///
+    /// Nodes executed after this read the new value; the node itself isn't executed again.
+    pub fn override_output(
+        &self,
+        wave_id: Option<&str>,
+        node_id: &str,
+        output_id: &str,
+        value: Value,
+    ) -> Result<(), String> {
+        let mut overridden = false;
+        for (id, cache) in self.live_caches() {
+            if wave_id.is_some_and(|wave_id| wave_id != id.to_string()) {
+                continue;
+            }
+
+            let mut cache = cache.lock().unwrap();
+            let Some(outputs) = cache.get_mut(node_id) else {
+                continue;
+            };
+            let output = outputs
+                .iter_mut()
+                .find(|output| output.id == output_id)
+                .ok_or_else(|| format!("Node '{}' has no output '{}'", node_id, output_id))?;
+            output.value = value.clone();
+            overridden = true;
+        }
+
+        if !overridden {
+            return Err(format!("Node '{}' has no cached outputs", node_id));
+        }
+        Ok(())
+    }
+
+    /// Pause before every node in stepped mode, only on breakpoints otherwise
+    pub fn set_mode(&self, mode: &ExecutionMode) {
+        match mode {
+            ExecutionMode::Stepped => self.pause(),
+            ExecutionMode::Normal => self.resume(),
+        }
+    }
+
+    /// Hold back every node that hasn't started yet
+    pub fn pause(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.paused = true;
+        state.steps = 0;
+    }
+
+    /// Let a single node run, pausing again afterwards
+    pub fn step(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.paused = true;
+        state.steps = 1;
+    }
+
+    /// Run until the next breakpoint
+    pub fn resume(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.paused = false;
+        state.steps = 0;
+        state.released = std::mem::take(&mut state.held);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.state.lock().unwrap().paused
+    }
+
+    /// Set or remove (None) the breakpoint of a node
+    pub fn set_breakpoint(&self, node_id: &str, breakpoint: Option<Breakpoint>) {
+        let mut state = self.state.lock().unwrap();
+        match breakpoint {
+            Some(breakpoint) => state.breakpoints.insert(node_id.to_string(), breakpoint),
+            None => state.breakpoints.remove(node_id),
+        };
+    }
+
+    /// Whether the node's breakpoint needs its inputs to decide if it pauses
+    pub fn has_conditions(&self, node_id: &str) -> bool {
+        self.state
+            .lock()
+            .unwrap()
+            .breakpoints
+            .get(node_id)
+            .is_some_and(|breakpoint| !breakpoint.conditions.is_empty())
+    }
+
+    /// Decide whether a node may execute now, pausing when it hits a breakpoint
+    pub fn allow(&self, node_id: &str, inputs: &[BrickInputValue]) -> bool {
+        let mut state = self.state.lock().unwrap();
+        if state.released.remove(node_id) {
+            return true;
+        }
+
+        let hit = state
+            .breakpoints
+            .get(node_id)
+            .is_some_and(|breakpoint| breakpoint.matches(inputs));
+        if hit {
+            state.paused = true;
+        }
+        if !state.paused {
+            return true;
+        }
+
+        if state.steps > 0 {
+            state.steps -= 1;
+            state.held.remove(node_id);
+            return true;
+        }
+
+        state.held.insert(node_id.to_string());
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(id: &str, value: impl Into<Value>) -> BrickInputValue {
+        BrickInputValue {
+            id: id.to_string(),
+            value: value.into(),
+        }
+    }
+
+    #[test]
+    fn test_conditional_breakpoint() {
+        let breakpoint = Breakpoint {
+            conditions: vec![BreakpointCondition {
+                input: "value".to_string(),
+                operator: ConditionOperator::GreaterThan,
+                value: Value::Integer(5),
+            }],
+        };
+
+        assert!(breakpoint.matches(&[input("value", 8.0)]));
+        assert!(!breakpoint.matches(&[input("value", 5)]));
+        // Conditions on inputs the node doesn't have never hold
+        assert!(!breakpoint.matches(&[input("other", 8)]));
+        assert!(Breakpoint::default().matches(&[]));
+    }
+
+    #[test]
+    fn test_step_and_resume() {
+        let debugger = Debugger::new(Arc::new(Graph {
+            nodes: vec![],
+            edges: vec![],
+        }));
+        debugger.set_mode(&ExecutionMode::Stepped);
+
+        assert!(!debugger.allow("a", &[]));
+        debugger.step();
+        assert!(debugger.allow("a", &[]));
+        assert!(!debugger.allow("b", &[]));
+
+        debugger.resume();
+        assert!(debugger.allow("b", &[]));
+        assert!(debugger.allow("c", &[]));
+    }
+
+    #[test]
+    fn test_breakpoint_pauses_until_resumed() {
+        let debugger = Debugger::new(Arc::new(Graph {
+            nodes: vec![],
+            edges: vec![],
+        }));
+        debugger.set_breakpoint("b", Some(Breakpoint::default()));
+
+        assert!(debugger.allow("a", &[]));
+        assert!(!debugger.allow("b", &[]));
+        assert!(!debugger.allow("b", &[]));
+        assert!(debugger.is_paused());
+
+        // The node paused on doesn't hit its own breakpoint again
+        debugger.resume();
+        assert!(debugger.allow("b", &[]));
+        assert!(!debugger.is_paused());
+
+        // But it does the next time it comes around
+        assert!(!debugger.allow("b", &[]));
+    }
+}
@@ -1,9 +1,42 @@
 /// Emission contexts - each self-emitting brick type has its own context
 /// that runs independently and emits events when ready
 use super::events::ExecutionEvent;
+#[cfg(feature = "fs")]
+use super::events::FileEventType;
+#[cfg(feature = "fs")]
+use std::collections::HashMap;
+#[cfg(feature = "fs")]
+use std::path::{Path, PathBuf};
 use std::sync::mpsc::Sender;
+#[cfg(feature = "fs")]
+use std::time::{Duration, Instant};
 
 /// Trait for emission contexts - each self-emitting node type implements this
+///
+/// Example: Future speech recognition context (just a skeleton to show extensibility)
+///
+/// ```ignore
+/// pub struct SpeechRecognitionContext {
+///     recognizer: Option<SpeechRecognizer>,
+///     active: bool,
+/// }
+///
+/// impl EmissionContext for SpeechRecognitionContext {
+///     fn start(&mut self, node_id: String, event_sender: Sender<ExecutionEvent>) -> Result<(), String> {
+///         // Initialize speech recognizer
+///         // Set up callback that sends ExecutionEvent::SpeechDetected when speech is recognized
+///         // The recognizer runs asynchronously on its own thread
+///         Ok(())
+///     }
+///
+///     fn stop(&mut self) -> Result<(), String> {
+///         // Stop and cleanup recognizer
+///         Ok(())
+///     }
+///
+///     // ... other trait methods
+/// }
+/// ```
 pub trait EmissionContext: Send {
     /// Start the context (spawn threads, set up listeners, etc.)
     /// The context will send ExecutionEvents through the sender when ready
@@ -198,34 +231,298 @@ impl Default for ManualTriggerContext {
     }
 }
 
-/// Example: Future speech recognition context (just a skeleton to show extensibility)
-///
-/// ```ignore
-/// pub struct SpeechRecognitionContext {
-///     recognizer: Option<SpeechRecognizer>,
-///     active: bool,
-/// }
-///
-/// impl EmissionContext for SpeechRecognitionContext {
-///     fn start(&mut self, node_id: String, event_sender: Sender<ExecutionEvent>) -> Result<(), String> {
-///         // Initialize speech recognizer
-///         // Set up callback that sends ExecutionEvent::SpeechDetected when speech is recognized
-///         // The recognizer runs asynchronously on its own thread
-///         Ok(())
-///     }
-///
-///     fn stop(&mut self) -> Result<(), String> {
-///         // Stop and cleanup recognizer
-///         Ok(())
-///     }
-///
-///     // ... other trait methods
-/// }
-/// ```
+/// HTTP webhook emission context - registers a route on the shared local webhook server
+/// and emits an HttpRequest event for every matching request
+#[cfg(feature = "net")]
+pub struct HttpWebhookContext {
+    port: u16,
+    path: String,
+    method: String,
+    /// Hold requests open for a graph response instead of answering 202 right away
+    response_timeout: Option<std::time::Duration>,
+    /// Port the route is actually registered on (differs from `port` when binding port 0)
+    bound_port: Option<u16>,
+}
+
+#[cfg(feature = "net")]
+impl HttpWebhookContext {
+    pub fn new(port: u16, path: impl Into<String>, method: impl Into<String>) -> Self {
+        Self {
+            port,
+            path: path.into(),
+            method: method.into(),
+            response_timeout: None,
+            bound_port: None,
+        }
+    }
+
+    /// Keep each request open until the graph responds, or answer 504 after `timeout`
+    pub fn with_response_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.response_timeout = Some(timeout);
+        self
+    }
+
+    /// Get the port the webhook is reachable on (only available while active)
+    pub fn local_port(&self) -> Option<u16> {
+        self.bound_port
+    }
+}
+
+#[cfg(feature = "net")]
+impl EmissionContext for HttpWebhookContext {
+    fn start(
+        &mut self,
+        node_id: String,
+        event_sender: Sender<ExecutionEvent>,
+    ) -> Result<(), String> {
+        if self.bound_port.is_some() {
+            return Err("Webhook context already active".to_string());
+        }
+
+        let port = super::webhook::register_route(
+            self.port,
+            &self.method,
+            &self.path,
+            node_id,
+            event_sender,
+            self.response_timeout,
+        )?;
+
+        self.bound_port = Some(port);
+        Ok(())
+    }
+
+    fn stop(&mut self) -> Result<(), String> {
+        if let Some(port) = self.bound_port.take() {
+            super::webhook::unregister_route(port, &self.method, &self.path);
+        }
+        Ok(())
+    }
+
+    fn is_active(&self) -> bool {
+        self.bound_port.is_some()
+    }
+
+    fn context_type(&self) -> &'static str {
+        "HttpWebhook"
+    }
+}
+
+#[cfg(feature = "net")]
+impl Drop for HttpWebhookContext {
+    fn drop(&mut self) {
+        let _ = self.stop();
+    }
+}
+
+/// Default quiet period before a burst of file changes is emitted
+#[cfg(feature = "fs")]
+pub const DEFAULT_FILE_DEBOUNCE_MS: u64 = 200;
+
+/// File watcher emission context - watches a directory and emits FileChanged events
+/// for paths matching a glob pattern. Bursts of changes to the same path are collapsed
+/// into a single event once the path has been quiet for the debounce period.
+#[cfg(feature = "fs")]
+pub struct FileWatcherContext {
+    directory: PathBuf,
+    pattern: String,
+    debounce: Duration,
+    active: std::sync::Arc<std::sync::Mutex<bool>>,
+    watcher: Option<notify::RecommendedWatcher>,
+    thread_handle: Option<std::thread::JoinHandle<()>>,
+}
+
+#[cfg(feature = "fs")]
+impl FileWatcherContext {
+    pub fn new(
+        directory: impl Into<PathBuf>,
+        pattern: impl Into<String>,
+        debounce_ms: u64,
+    ) -> Self {
+        Self {
+            directory: directory.into(),
+            pattern: pattern.into(),
+            debounce: Duration::from_millis(debounce_ms),
+            active: std::sync::Arc::new(std::sync::Mutex::new(false)),
+            watcher: None,
+            thread_handle: None,
+        }
+    }
+
+    /// Map a notify event kind to our file event type (None for events we don't report)
+    fn event_type(kind: &notify::EventKind) -> Option<FileEventType> {
+        use notify::event::ModifyKind;
+        use notify::EventKind;
+
+        match kind {
+            EventKind::Create(_) => Some(FileEventType::Created),
+            EventKind::Modify(ModifyKind::Name(_)) => Some(FileEventType::Renamed),
+            EventKind::Modify(ModifyKind::Metadata(_)) => None,
+            EventKind::Modify(_) => Some(FileEventType::Modified),
+            EventKind::Remove(_) => Some(FileEventType::Deleted),
+            _ => None,
+        }
+    }
+
+    /// Combine a new change with the one already pending for the same path
+    fn merge_event_types(pending: &FileEventType, new: FileEventType) -> FileEventType {
+        match (pending, new) {
+            // Writing to a freshly created file is still a creation
+            (FileEventType::Created, FileEventType::Modified) => FileEventType::Created,
+            (_, new) => new,
+        }
+    }
+
+    /// Check if a path matches the glob pattern (relative to the watched directory)
+    fn matches(pattern: &glob::Pattern, directory: &Path, path: &Path) -> bool {
+        let relative = path.strip_prefix(directory).unwrap_or(path);
+        let options = glob::MatchOptions {
+            require_literal_separator: true,
+            ..Default::default()
+        };
+
+        pattern.matches_path_with(relative, options)
+    }
+}
+
+#[cfg(feature = "fs")]
+impl EmissionContext for FileWatcherContext {
+    fn start(
+        &mut self,
+        node_id: String,
+        event_sender: Sender<ExecutionEvent>,
+    ) -> Result<(), String> {
+        use notify::Watcher;
+
+        if *self.active.lock().unwrap() {
+            return Err("File watcher context already active".to_string());
+        }
+
+        let pattern = glob::Pattern::new(&self.pattern)
+            .map_err(|e| format!("Invalid file pattern '{}': {}", self.pattern, e))?;
+
+        // Watch the canonical path so event paths can be matched against it
+        let directory = self.directory.canonicalize().map_err(|e| {
+            format!(
+                "Cannot watch directory '{}': {}",
+                self.directory.display(),
+                e
+            )
+        })?;
+
+        let (notify_sender, notify_receiver) = std::sync::mpsc::channel();
+        let mut watcher = notify::recommended_watcher(notify_sender)
+            .map_err(|e| format!("Failed to create file watcher: {}", e))?;
+        watcher
+            .watch(&directory, notify::RecursiveMode::Recursive)
+            .map_err(|e| format!("Failed to watch '{}': {}", directory.display(), e))?;
+
+        *self.active.lock().unwrap() = true;
+
+        let debounce = self.debounce;
+        let active = std::sync::Arc::clone(&self.active);
+
+        let handle = std::thread::spawn(move || {
+            // Changes waiting for their path to go quiet (path -> (event type, last seen))
+            let mut pending: HashMap<PathBuf, (FileEventType, Instant)> = HashMap::new();
+
+            while *active.lock().unwrap() {
+                match notify_receiver.recv_timeout(debounce.min(Duration::from_millis(50))) {
+                    Ok(Ok(event)) => {
+                        let Some(event_type) = Self::event_type(&event.kind) else {
+                            continue;
+                        };
+
+                        for path in event.paths {
+                            if !Self::matches(&pattern, &directory, &path) {
+                                continue;
+                            }
+
+                            let event_type = match pending.get(&path) {
+                                Some((existing, _)) => {
+                                    Self::merge_event_types(existing, event_type.clone())
+                                }
+                                None => event_type.clone(),
+                            };
+                            pending.insert(path, (event_type, Instant::now()));
+                        }
+                    }
+                    Ok(Err(_)) | Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {}
+                    // Watcher dropped, stop the context
+                    Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => break,
+                }
+
+                // Emit every path that has been quiet for the debounce period
+                let settled: Vec<PathBuf> = pending
+                    .iter()
+                    .filter(|(_, (_, last_seen))| last_seen.elapsed() >= debounce)
+                    .map(|(path, _)| path.clone())
+                    .collect();
+
+                for path in settled {
+                    if let Some((event_type, _)) = pending.remove(&path) {
+                        let event = ExecutionEvent::FileChanged {
+                            node_id: node_id.clone(),
+                            path: path.to_string_lossy().into_owned(),
+                            event_type,
+                        };
+
+                        if event_sender.send(event).is_err() {
+                            // Receiver dropped, stop watching
+                            return;
+                        }
+                    }
+                }
+            }
+        });
+
+        self.watcher = Some(watcher);
+        self.thread_handle = Some(handle);
+        Ok(())
+    }
+
+    fn stop(&mut self) -> Result<(), String> {
+        if !*self.active.lock().unwrap() {
+            return Ok(());
+        }
+
+        *self.active.lock().unwrap() = false;
+
+        // Dropping the watcher also disconnects the channel the thread listens on
+        self.watcher = None;
+
+        if let Some(handle) = self.thread_handle.take() {
+            handle
+                .join()
+                .map_err(|_| "Failed to join file watcher thread".to_string())?;
+        }
+
+        Ok(())
+    }
+
+    fn is_active(&self) -> bool {
+        *self.active.lock().unwrap()
+    }
+
+    fn context_type(&self) -> &'static str {
+        "FileWatcher"
+    }
+}
+
+#[cfg(feature = "fs")]
+impl Drop for FileWatcherContext {
+    fn drop(&mut self) {
+        let _ = self.stop();
+    }
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    #[cfg(feature = "net")]
+    use crate::engine::events::HttpResponseData;
+    #[cfg(feature = "net")]
+    use crate::engine::webhook;
     use std::sync::mpsc;
     use std::time::Duration;
 
@@ -292,4 +589,256 @@ mod tests {
         context.stop().unwrap();
         assert!(!context.is_active());
     }
+
+    /// Send a raw HTTP request to localhost and return the response status code
+    #[cfg(feature = "net")]
+    fn send_http_request(port: u16, request: &str) -> u16 {
+        use std::io::{Read, Write};
+
+        let mut stream = std::net::TcpStream::connect(("127.0.0.1", port)).unwrap();
+        stream.write_all(request.as_bytes()).unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        response
+            .split_whitespace()
+            .nth(1)
+            .and_then(|status| status.parse().ok())
+            .unwrap()
+    }
+
+    #[cfg(feature = "net")]
+    #[test]
+    fn test_http_webhook_context() {
+        let (sender, receiver) = mpsc::channel();
+        let mut context = HttpWebhookContext::new(0, "/hooks/build", "POST");
+
+        context.start("webhook_node".to_string(), sender).unwrap();
+        assert!(context.is_active());
+        let port = context.local_port().unwrap();
+
+        let body = r#"{"ok":true}"#;
+        let status = send_http_request(
+            port,
+            &format!(
+                "POST /hooks/build?branch=main&tag=v1+rc HTTP/1.1\r\nHost: localhost\r\nX-Source: ci\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            ),
+        );
+        assert_eq!(status, 202);
+
+        match receiver.recv_timeout(Duration::from_secs(1)).unwrap() {
+            ExecutionEvent::HttpRequest { node_id, request } => {
+                assert_eq!(node_id, "webhook_node");
+                assert_eq!(request.method, "POST");
+                assert_eq!(request.path, "/hooks/build");
+                assert_eq!(request.body, body);
+
+                let headers: std::collections::BTreeMap<String, String> =
+                    serde_json::from_str(&request.headers).unwrap();
+                assert_eq!(headers["x-source"], "ci");
+
+                let query: std::collections::BTreeMap<String, String> =
+                    serde_json::from_str(&request.query).unwrap();
+                assert_eq!(query["branch"], "main");
+                assert_eq!(query["tag"], "v1 rc");
+            }
+            _ => panic!("Expected HttpRequest event"),
+        }
+
+        // Unknown paths and methods are not routed to the node
+        let status = send_http_request(
+            port,
+            "GET /hooks/build HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n",
+        );
+        assert_eq!(status, 404);
+        let status = send_http_request(
+            port,
+            "POST /other HTTP/1.1\r\nHost: localhost\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        );
+        assert_eq!(status, 404);
+        assert!(receiver.try_recv().is_err());
+
+        context.stop().unwrap();
+        assert!(!context.is_active());
+    }
+
+    #[cfg(feature = "net")]
+    #[test]
+    fn test_http_webhook_context_waits_for_response() {
+        let (sender, receiver) = mpsc::channel();
+        let mut context =
+            HttpWebhookContext::new(0, "/ask", "GET").with_response_timeout(Duration::from_secs(5));
+
+        context.start("webhook_node".to_string(), sender).unwrap();
+        let port = context.local_port().unwrap();
+
+        let client = std::thread::spawn(move || {
+            use std::io::{Read, Write};
+
+            let mut stream = std::net::TcpStream::connect(("127.0.0.1", port)).unwrap();
+            stream
+                .write_all(b"GET /ask HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+                .unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        });
+
+        let request = match receiver.recv_timeout(Duration::from_secs(1)).unwrap() {
+            ExecutionEvent::HttpRequest { request, .. } => request,
+            _ => panic!("Expected HttpRequest event"),
+        };
+        assert!(!request.request_id.is_empty());
+
+        webhook::respond(
+            &request.request_id,
+            HttpResponseData {
+                status: 201,
+                body: "created".to_string(),
+                headers: r#"{"X-Vla":"yes"}"#.to_string(),
+            },
+        )
+        .unwrap();
+
+        let response = client.join().unwrap();
+        assert!(response.starts_with("HTTP/1.1 201"), "{}", response);
+        assert!(response.contains("X-Vla: yes"), "{}", response);
+        assert!(response.ends_with("created"), "{}", response);
+
+        // A request can only be answered once
+        assert!(webhook::respond(&request.request_id, HttpResponseData::default()).is_err());
+
+        context.stop().unwrap();
+    }
+
+    #[cfg(feature = "net")]
+    #[test]
+    fn test_http_webhook_context_times_out() {
+        let (sender, receiver) = mpsc::channel();
+        let mut context = HttpWebhookContext::new(0, "/slow", "POST")
+            .with_response_timeout(Duration::from_millis(100));
+
+        context.start("webhook_node".to_string(), sender).unwrap();
+        let port = context.local_port().unwrap();
+
+        let status = send_http_request(
+            port,
+            "POST /slow HTTP/1.1\r\nHost: localhost\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        );
+        assert_eq!(status, 504);
+        assert!(matches!(
+            receiver.try_recv(),
+            Ok(ExecutionEvent::HttpRequest { .. })
+        ));
+
+        context.stop().unwrap();
+    }
+
+    #[cfg(feature = "net")]
+    #[test]
+    fn test_http_webhook_context_body_handling() {
+        use std::io::Write;
+
+        let (sender, receiver) = mpsc::channel();
+        let mut context = HttpWebhookContext::new(0, "/upload", "POST");
+
+        context.start("webhook_node".to_string(), sender).unwrap();
+        let port = context.local_port().unwrap();
+
+        // A client stalling in the middle of its body doesn't hold up other requests
+        let mut stalled = std::net::TcpStream::connect(("127.0.0.1", port)).unwrap();
+        stalled
+            .write_all(
+                b"POST /upload HTTP/1.1\r\nHost: localhost\r\nContent-Length: 10\r\n\r\nhalf",
+            )
+            .unwrap();
+
+        let status = send_http_request(
+            port,
+            "POST /upload HTTP/1.1\r\nHost: localhost\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok",
+        );
+        assert_eq!(status, 202);
+        match receiver.recv_timeout(Duration::from_secs(1)).unwrap() {
+            ExecutionEvent::HttpRequest { request, .. } => assert_eq!(request.body, "ok"),
+            _ => panic!("Expected HttpRequest event"),
+        }
+
+        // Bodies over the limit are refused without reaching the node
+        let status = send_http_request(
+            port,
+            &format!(
+                "POST /upload HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                webhook::MAX_BODY_BYTES + 1
+            ),
+        );
+        assert_eq!(status, 413);
+        assert!(receiver.try_recv().is_err());
+
+        drop(stalled);
+        context.stop().unwrap();
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn test_file_watcher_context() {
+        let directory = std::env::temp_dir().join(format!("vla-watch-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&directory).unwrap();
+
+        let (sender, receiver) = mpsc::channel();
+        let mut context = FileWatcherContext::new(&directory, "*.txt", 100);
+
+        context.start("watch_node".to_string(), sender).unwrap();
+        assert!(context.is_active());
+
+        // A burst of writes to one file should produce a single event
+        let file = directory.join("export.txt");
+        std::fs::write(&file, "one").unwrap();
+        std::fs::write(&file, "two").unwrap();
+        std::fs::write(&file, "three").unwrap();
+
+        // Files not matching the pattern are ignored
+        std::fs::write(directory.join("export.log"), "ignored").unwrap();
+
+        match receiver.recv_timeout(Duration::from_secs(2)).unwrap() {
+            ExecutionEvent::FileChanged {
+                node_id,
+                path,
+                event_type,
+            } => {
+                assert_eq!(node_id, "watch_node");
+                assert!(path.ends_with("export.txt"), "{}", path);
+                assert!(matches!(event_type, FileEventType::Created));
+            }
+            _ => panic!("Expected FileChanged event"),
+        }
+
+        std::thread::sleep(Duration::from_millis(300));
+        assert!(receiver.try_recv().is_err(), "Burst should be debounced");
+
+        std::fs::remove_file(&file).unwrap();
+        match receiver.recv_timeout(Duration::from_secs(2)).unwrap() {
+            ExecutionEvent::FileChanged { event_type, .. } => {
+                assert!(matches!(event_type, FileEventType::Deleted));
+            }
+            _ => panic!("Expected FileChanged event"),
+        }
+
+        context.stop().unwrap();
+        assert!(!context.is_active());
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn test_file_watcher_context_invalid_directory() {
+        let (sender, _receiver) = mpsc::channel();
+        let mut context = FileWatcherContext::new("/definitely/not/a/dir", "*", 100);
+
+        assert!(context.start("watch_node".to_string(), sender).is_err());
+        assert!(!context.is_active());
+    }
 }
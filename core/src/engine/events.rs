@@ -1,7 +1,8 @@
 use serde::{Deserialize, Serialize};
 
 /// Represents all types of events that can trigger node execution
-#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "tauri", derive(specta::Type))]
 pub enum ExecutionEvent {
     /// Traditional flow-based execution trigger
     /// Occurs when a node completes and triggers its execution output
@@ -38,6 +39,16 @@ pub enum ExecutionEvent {
 }
 
 impl ExecutionEvent {
+    /// Manual trigger of a node, timestamped now
+    pub fn manual_trigger(node_id: impl Into<String>) -> Self {
+        use std::time::{SystemTime, UNIX_EPOCH};
+        let duration = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        ExecutionEvent::ManualTrigger {
+            node_id: node_id.into(),
+            timestamp: format!("{}.{:03}", duration.as_secs(), duration.subsec_millis()),
+        }
+    }
+
     /// Get the target node ID for this event
     pub fn target_node_id(&self) -> &str {
         match self {
@@ -61,13 +72,17 @@ impl ExecutionEvent {
 }
 
 /// HTTP request data for webhook events
-#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "tauri", derive(specta::Type))]
 pub struct HttpRequestData {
     pub method: String,
     pub path: String,
     pub body: String,
     pub headers: String, // JSON-encoded headers
     pub query: String,   // JSON-encoded query parameters
+    /// Identifies the held-open connection when the graph answers the request itself
+    #[serde(default)]
+    pub request_id: String,
 }
 
 impl Default for HttpRequestData {
@@ -78,12 +93,33 @@ impl Default for HttpRequestData {
             body: String::new(),
             headers: "{}".to_string(),
             query: "{}".to_string(),
+            request_id: String::new(),
+        }
+    }
+}
+
+/// HTTP response sent back to the caller of a webhook
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "tauri", derive(specta::Type))]
+pub struct HttpResponseData {
+    pub status: u16,
+    pub body: String,
+    pub headers: String, // JSON-encoded headers
+}
+
+impl Default for HttpResponseData {
+    fn default() -> Self {
+        Self {
+            status: 200,
+            body: String::new(),
+            headers: "{}".to_string(),
         }
     }
 }
 
 /// File system event types
-#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "tauri", derive(specta::Type))]
 pub enum FileEventType {
     Created,
     Modified,
@@ -91,6 +127,17 @@ pub enum FileEventType {
     Renamed,
 }
 
+impl FileEventType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FileEventType::Created => "created",
+            FileEventType::Modified => "modified",
+            FileEventType::Deleted => "deleted",
+            FileEventType::Renamed => "renamed",
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
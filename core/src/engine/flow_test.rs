@@ -1,7 +1,9 @@
 use crate::bricks::macros::brick;
+use crate::bricks::types::{ERROR_EXECUTION_OUTPUT, ERROR_MESSAGE_OUTPUT, FIRST_VERSION};
+use crate::engine::policy::{ErrorPolicy, NodePolicy};
 use crate::engine::Engine;
 use crate::prelude::*;
-use crate::trigger;
+use crate::test_utils::{edge, node, with_defaults};
 use std::collections::BTreeMap;
 
 // Data node: simple addition
@@ -23,10 +25,10 @@ brick! {
     #[id("start_flow")]
     #[category("Control Flow")]
     #[execution_output("begin")]
-    fn start_flow() -> (
+    fn start_flow(#[context] ctx: &BrickContext) -> (
         #[label("Started")] bool
     ) {
-        trigger!("begin");
+        ctx.trigger("begin");
         (true,)
     }
 }
@@ -38,12 +40,13 @@ brick! {
     #[execution_input("execute")]
     #[execution_output("done")]
     fn print_and_continue(
+        #[context] ctx: &BrickContext,
         #[input] value: f32 = 0.0
     ) -> (
         #[label("Value")] f32
     ) {
         println!("  [Print] Value: {}", value);
-        trigger!("done");
+        ctx.trigger("done");
         (value,)
     }
 }
@@ -89,6 +92,9 @@ fn test_sequential_flow_with_data_dependencies() {
                     brick: Some(start_flow_brick()),
                     arguments: BTreeMap::new(),
                     defaults: BTreeMap::new(),
+                    policy: Default::default(),
+                    breakpoint: None,
+                    brick_version: FIRST_VERSION,
                 },
                 r#type: "v1".to_string(),
             },
@@ -100,6 +106,9 @@ fn test_sequential_flow_with_data_dependencies() {
                     brick: Some(add_numbers_brick()),
                     arguments: BTreeMap::new(),
                     defaults: BTreeMap::new(),
+                    policy: Default::default(),
+                    breakpoint: None,
+                    brick_version: FIRST_VERSION,
                 },
                 r#type: "v1".to_string(),
             },
@@ -111,6 +120,9 @@ fn test_sequential_flow_with_data_dependencies() {
                     brick: Some(print_and_continue_brick()),
                     arguments: BTreeMap::new(),
                     defaults: BTreeMap::new(),
+                    policy: Default::default(),
+                    breakpoint: None,
+                    brick_version: FIRST_VERSION,
                 },
                 r#type: "v1".to_string(),
             },
@@ -122,6 +134,9 @@ fn test_sequential_flow_with_data_dependencies() {
                     brick: Some(end_flow_brick()),
                     arguments: BTreeMap::new(),
                     defaults: BTreeMap::new(),
+                    policy: Default::default(),
+                    breakpoint: None,
+                    brick_version: FIRST_VERSION,
                 },
                 r#type: "v1".to_string(),
             },
@@ -160,7 +175,7 @@ fn test_sequential_flow_with_data_dependencies() {
         ],
     };
 
-    let mut engine = Engine::with_debug_test(graph, true);
+    let mut engine = Engine::with_debug(graph, true);
     engine.start();
 
     println!("Starting execution...\n");
@@ -206,15 +221,16 @@ fn test_conditional_flow() {
         #[execution_output("true_branch")]
         #[execution_output("false_branch")]
         fn if_else(
+            #[context] ctx: &BrickContext,
             #[input] condition: bool = true
         ) -> (
             #[label("Condition")] bool
         ) {
             println!("  [If] Condition: {}", condition);
             if condition {
-                trigger!("true_branch");
+                ctx.trigger("true_branch");
             } else {
-                trigger!("false_branch");
+                ctx.trigger("false_branch");
             }
             (condition,)
         }
@@ -251,6 +267,9 @@ fn test_conditional_flow() {
                     brick: Some(start_flow_brick()),
                     arguments: BTreeMap::new(),
                     defaults: BTreeMap::new(),
+                    policy: Default::default(),
+                    breakpoint: None,
+                    brick_version: FIRST_VERSION,
                 },
                 r#type: "v1".to_string(),
             },
@@ -262,6 +281,9 @@ fn test_conditional_flow() {
                     brick: Some(if_else_brick()),
                     arguments: BTreeMap::new(),
                     defaults: BTreeMap::new(),
+                    policy: Default::default(),
+                    breakpoint: None,
+                    brick_version: FIRST_VERSION,
                 },
                 r#type: "v1".to_string(),
             },
@@ -273,6 +295,9 @@ fn test_conditional_flow() {
                     brick: Some(true_handler_brick()),
                     arguments: BTreeMap::new(),
                     defaults: BTreeMap::new(),
+                    policy: Default::default(),
+                    breakpoint: None,
+                    brick_version: FIRST_VERSION,
                 },
                 r#type: "v1".to_string(),
             },
@@ -284,6 +309,9 @@ fn test_conditional_flow() {
                     brick: Some(false_handler_brick()),
                     arguments: BTreeMap::new(),
                     defaults: BTreeMap::new(),
+                    policy: Default::default(),
+                    breakpoint: None,
+                    brick_version: FIRST_VERSION,
                 },
                 r#type: "v1".to_string(),
             },
@@ -313,7 +341,7 @@ fn test_conditional_flow() {
         ],
     };
 
-    let mut engine = Engine::with_debug_test(graph, true);
+    let mut engine = Engine::with_debug(graph, true);
     engine.start();
 
     println!("Starting conditional execution...\n");
@@ -346,3 +374,739 @@ fn test_conditional_flow() {
         "False branch should not execute"
     );
 }
+
+#[test]
+fn test_for_range_loop_flow() {
+    println!("\n=== For Range Loop Flow Test ===\n");
+
+    // Graph: start → loop (0..3) → body: print(index + 3), completed: end
+    let graph = Graph {
+        nodes: vec![
+            node("start", start_flow_brick()),
+            with_defaults(
+                node("loop", crate::bricks::control_flow::for_range_brick()),
+                &[("end", "3")],
+            ),
+            node("add", add_numbers_brick()),
+            node("print", print_and_continue_brick()),
+            node("end", end_flow_brick()),
+        ],
+        edges: vec![
+            edge("start", "begin", "loop", "execute"),
+            edge("loop", "body", "print", "execute"),
+            edge("loop", "completed", "end", "execute"),
+            edge("loop", "output_0", "add", "a"),
+            edge("add", "output_0", "print", "value"),
+        ],
+    };
+
+    let mut engine = Engine::with_debug(graph, true);
+    engine.start();
+
+    let mut executed_nodes = Vec::new();
+    for result in &mut engine {
+        match result {
+            Ok(node_id) => executed_nodes.push(node_id),
+            Err(e) => panic!("Loop execution failed: {}", e),
+        }
+    }
+
+    println!("  Execution order: {:?}", executed_nodes);
+
+    // The index feeding the body is recomputed on every iteration
+    assert_eq!(
+        executed_nodes,
+        vec![
+            "start", "loop", "add", "print", "loop", "add", "print", "loop", "add", "print",
+            "loop", "end"
+        ]
+    );
+
+    // The last body iteration saw index 2
+    assert_eq!(
+        engine.main_wave.cached("print").unwrap()[0].value,
+        Value::Number(5.0)
+    );
+}
+
+#[test]
+fn test_async_nodes_do_not_block_other_branches() {
+    println!("\n=== Async Flow Test ===\n");
+
+    // Graph: start → delay_a (500ms) → end_a
+    //              → delay_b (500ms) → end_b
+    //              → print
+    let graph = Graph {
+        nodes: vec![
+            node("start", start_flow_brick()),
+            with_defaults(
+                node("delay_a", crate::bricks::control_flow::delay_brick()),
+                &[("duration_ms", "500")],
+            ),
+            with_defaults(
+                node("delay_b", crate::bricks::control_flow::delay_brick()),
+                &[("duration_ms", "500")],
+            ),
+            node("end_a", end_flow_brick()),
+            node("end_b", end_flow_brick()),
+            node("print", print_and_continue_brick()),
+        ],
+        edges: vec![
+            edge("start", "begin", "delay_a", "execute"),
+            edge("start", "begin", "delay_b", "execute"),
+            edge("start", "begin", "print", "execute"),
+            edge("delay_a", "completed", "end_a", "execute"),
+            edge("delay_b", "completed", "end_b", "execute"),
+        ],
+    };
+
+    let mut engine = Engine::new(graph);
+    engine.start();
+
+    let started = std::time::Instant::now();
+    let executed: Vec<String> = (&mut engine)
+        .map(|result| result.expect("Async flow failed"))
+        .filter(|node_id| !node_id.is_empty())
+        .collect();
+    let elapsed = started.elapsed();
+
+    println!("  Execution order: {:?} in {:?}", executed, elapsed);
+
+    // The print branch ran while both delays were still waiting
+    let position = |id: &str| executed.iter().position(|n| n == id).unwrap();
+    assert!(position("print") < position("delay_a"));
+    assert!(position("print") < position("delay_b"));
+    assert!(position("delay_a") < position("end_a"));
+    assert!(position("delay_b") < position("end_b"));
+
+    // Both delays waited at the same time, one after the other would take a second
+    assert!(
+        elapsed < std::time::Duration::from_millis(950),
+        "{:?}",
+        elapsed
+    );
+    assert!(matches!(
+        engine.node_state("end_b").unwrap().phase,
+        crate::engine::ExecutionPhase::Completed
+    ));
+}
+
+// Fallible flow node: fails instead of dividing by zero
+brick! {
+    #[id("checked_divide")]
+    #[category("Math")]
+    #[execution_input("execute")]
+    #[execution_output("done")]
+    fn checked_divide(
+        #[context] ctx: &BrickContext,
+        #[input] a: f32 = 1.0,
+        #[input] b: f32 = 1.0
+    ) -> Result<(#[label("Quotient")] f32), BrickError> {
+        if b == 0.0 {
+            return Err(BrickError::failed("division by zero"));
+        }
+        ctx.trigger("done");
+        Ok((a / b,))
+    }
+}
+
+// Flow node: Handle an error message
+brick! {
+    #[id("report_error")]
+    #[category("Control Flow")]
+    #[execution_input("execute")]
+    fn report_error(
+        #[input] message: String = String::new()
+    ) -> (
+        #[label("Message")] String
+    ) {
+        println!("  [Report] {}", message);
+        (message,)
+    }
+}
+
+fn with_policy(mut node: Node, policy: NodePolicy) -> Node {
+    node.data.policy = policy;
+    node
+}
+
+#[test]
+fn test_fallible_brick_gets_error_outputs() {
+    let brick = checked_divide_brick();
+
+    assert!(brick
+        .execution_outputs
+        .iter()
+        .any(|output| output.id == ERROR_EXECUTION_OUTPUT));
+    assert!(brick
+        .outputs
+        .iter()
+        .any(|output| output.id == ERROR_MESSAGE_OUTPUT));
+
+    // Bricks that can't fail keep their outputs as declared
+    assert!(print_and_continue_brick()
+        .execution_outputs
+        .iter()
+        .all(|output| output.id != ERROR_EXECUTION_OUTPUT));
+}
+
+#[test]
+fn test_error_branch_handles_failure() {
+    println!("\n=== Error Branch Test ===\n");
+
+    // Graph: start → divide (by zero) → done: end
+    //                                 → error: report(error_message)
+    let graph = Graph {
+        nodes: vec![
+            node("start", start_flow_brick()),
+            with_defaults(node("divide", checked_divide_brick()), &[("b", "0")]),
+            node("end", end_flow_brick()),
+            node("report", report_error_brick()),
+        ],
+        edges: vec![
+            edge("start", "begin", "divide", "execute"),
+            edge("divide", "done", "end", "execute"),
+            edge("divide", ERROR_EXECUTION_OUTPUT, "report", "execute"),
+            edge("divide", ERROR_MESSAGE_OUTPUT, "report", "message"),
+        ],
+    };
+
+    let mut engine = Engine::new(graph);
+    engine.start();
+
+    let executed: Vec<String> = (&mut engine)
+        .map(|result| result.expect("The error branch should handle the failure"))
+        .collect();
+
+    assert_eq!(executed, vec!["start", "divide", "report"]);
+    assert_eq!(
+        engine.main_wave.cached("report").unwrap()[0].value,
+        Value::from("division by zero")
+    );
+    assert!(matches!(
+        engine.node_state("divide").unwrap().phase,
+        crate::engine::ExecutionPhase::Errored
+    ));
+}
+
+#[test]
+fn test_stop_run_policy_stops_the_wave() {
+    // Graph: start → divide (by zero) → end, without an error handler
+    let graph = Graph {
+        nodes: vec![
+            node("start", start_flow_brick()),
+            with_defaults(node("divide", checked_divide_brick()), &[("b", "0")]),
+            node("end", end_flow_brick()),
+        ],
+        edges: vec![
+            edge("start", "begin", "divide", "execute"),
+            edge("divide", "done", "end", "execute"),
+        ],
+    };
+
+    let mut engine = Engine::new(graph);
+    engine.start();
+
+    let results: Vec<_> = (&mut engine).collect();
+    assert_eq!(
+        results,
+        vec![
+            Ok("start".to_string()),
+            Err("Node 'divide' failed: division by zero".to_string())
+        ]
+    );
+    assert_eq!(
+        engine
+            .node_state("divide")
+            .unwrap()
+            .error_message
+            .as_deref(),
+        Some("Node 'divide' failed: division by zero")
+    );
+}
+
+#[test]
+fn test_data_cycle_stops_the_wave() {
+    // Graph: start → end, end reads from a, which reads from b, which reads from a
+    let graph = Graph {
+        nodes: vec![
+            node("start", start_flow_brick()),
+            node("a", add_numbers_brick()),
+            node("b", add_numbers_brick()),
+            node("end", end_flow_brick()),
+        ],
+        edges: vec![
+            edge("start", "begin", "end", "execute"),
+            edge("a", "output_0", "end", "final_value"),
+            edge("a", "output_0", "b", "a"),
+            edge("b", "output_0", "a", "a"),
+        ],
+    };
+
+    let mut engine = Engine::new(graph);
+    engine.start();
+
+    let results: Vec<_> = (&mut engine).collect();
+    assert_eq!(
+        results,
+        vec![
+            Ok("start".to_string()),
+            Err("Data dependencies of 'end' form a cycle: a → b → a".to_string())
+        ]
+    );
+}
+
+#[test]
+fn test_continue_policy_skips_failed_node() {
+    // Graph: start → divide (by zero) → end
+    //              → print
+    let graph = Graph {
+        nodes: vec![
+            node("start", start_flow_brick()),
+            with_policy(
+                with_defaults(node("divide", checked_divide_brick()), &[("b", "0")]),
+                NodePolicy {
+                    on_error: ErrorPolicy::Continue,
+                    ..Default::default()
+                },
+            ),
+            node("end", end_flow_brick()),
+            node("print", print_and_continue_brick()),
+        ],
+        edges: vec![
+            edge("start", "begin", "divide", "execute"),
+            edge("start", "begin", "print", "execute"),
+            edge("divide", "done", "end", "execute"),
+        ],
+    };
+
+    let mut engine = Engine::new(graph);
+    engine.start();
+
+    let executed: Vec<String> = (&mut engine)
+        .map(|result| result.expect("Continue should not fail the run"))
+        .collect();
+
+    // The failed node's flow is skipped, the rest of the run carries on
+    assert!(!executed.contains(&"end".to_string()));
+    assert!(executed.contains(&"print".to_string()));
+    assert!(matches!(
+        engine.node_state("divide").unwrap().phase,
+        crate::engine::ExecutionPhase::Errored
+    ));
+}
+
+#[test]
+fn test_continue_policy_skips_consumers_of_failed_data_node() {
+    // Data node: Always fails
+    brick! {
+        #[id("broken_source")]
+        #[category("Math")]
+        fn broken_source() -> Result<(#[label("Value")] f32), BrickError> {
+            Err(BrickError::failed("source unavailable"))
+        }
+    }
+
+    // Graph: start → print (value from broken, defaults to 42 when unconnected)
+    //              → end
+    let graph = Graph {
+        nodes: vec![
+            node("start", start_flow_brick()),
+            with_policy(
+                node("broken", broken_source_brick()),
+                NodePolicy {
+                    on_error: ErrorPolicy::Continue,
+                    ..Default::default()
+                },
+            ),
+            with_defaults(
+                node("print", print_and_continue_brick()),
+                &[("value", "42")],
+            ),
+            node("end", end_flow_brick()),
+        ],
+        edges: vec![
+            edge("start", "begin", "print", "execute"),
+            edge("start", "begin", "end", "execute"),
+            edge("broken", "output_0", "print", "value"),
+        ],
+    };
+
+    let mut engine = Engine::new(graph);
+    engine.start();
+
+    let executed: Vec<String> = (&mut engine)
+        .map(|result| result.expect("Continue should not fail the run"))
+        .collect();
+
+    // The consumer doesn't run with its default in place of the failed value
+    assert!(!executed.contains(&"print".to_string()));
+    assert!(engine.main_wave.cached("print").is_none());
+    assert!(executed.contains(&"end".to_string()));
+
+    let print = engine.node_state("print").unwrap();
+    assert!(matches!(
+        print.phase,
+        crate::engine::ExecutionPhase::Errored
+    ));
+    assert_eq!(
+        print.error_message.as_deref(),
+        Some("Skipped: data dependency 'broken' failed")
+    );
+}
+
+#[test]
+fn test_retry_policy_retries_failed_node() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+    // Flow node: Fails on its first two executions
+    brick! {
+        #[id("flaky")]
+        #[category("Control Flow")]
+        #[execution_input("execute")]
+        #[execution_output("done")]
+        fn flaky(#[context] ctx: &BrickContext) -> Result<(#[label("Calls")] i32), BrickError> {
+            let calls = CALLS.fetch_add(1, Ordering::SeqCst) + 1;
+            if calls < 3 {
+                return Err(BrickError::failed(format!("attempt {} failed", calls)));
+            }
+            ctx.trigger("done");
+            Ok((calls as i32,))
+        }
+    }
+
+    // Graph: start → flaky → end
+    let graph = Graph {
+        nodes: vec![
+            node("start", start_flow_brick()),
+            with_policy(
+                node("flaky", flaky_brick()),
+                NodePolicy {
+                    on_error: ErrorPolicy::Retry,
+                    ..Default::default()
+                },
+            ),
+            node("end", end_flow_brick()),
+        ],
+        edges: vec![
+            edge("start", "begin", "flaky", "execute"),
+            edge("flaky", "done", "end", "execute"),
+        ],
+    };
+
+    let mut engine = Engine::new(graph);
+    engine.start();
+
+    let executed: Vec<String> = (&mut engine)
+        .map(|result| result.expect("The third attempt should succeed"))
+        .collect();
+
+    assert_eq!(executed, vec!["start", "flaky", "end"]);
+    assert_eq!(CALLS.load(Ordering::SeqCst), 3);
+    assert_eq!(
+        engine.main_wave.cached("flaky").unwrap()[0].value,
+        Value::from(3)
+    );
+}
+
+#[test]
+fn test_timeout_fails_slow_nodes() {
+    println!("\n=== Timeout Test ===\n");
+
+    // Flow node: Blocks its thread for a while
+    brick! {
+        #[id("slow")]
+        #[category("Control Flow")]
+        #[execution_input("execute")]
+        #[execution_output("done")]
+        fn slow(#[context] ctx: &BrickContext) -> (#[label("Done")] bool) {
+            std::thread::sleep(std::time::Duration::from_millis(500));
+            ctx.trigger("done");
+            (true,)
+        }
+    }
+
+    let timeout = |attempts| NodePolicy {
+        on_error: ErrorPolicy::Continue,
+        max_attempts: Some(attempts),
+        timeout_ms: Some(50),
+        ..Default::default()
+    };
+
+    // Graph: start → slow (sync) → end_slow
+    //              → delay (async) → end_delay
+    let graph = Graph {
+        nodes: vec![
+            node("start", start_flow_brick()),
+            with_policy(node("slow", slow_brick()), timeout(2)),
+            with_policy(
+                with_defaults(
+                    node("delay", crate::bricks::control_flow::delay_brick()),
+                    &[("duration_ms", "500")],
+                ),
+                timeout(1),
+            ),
+            node("end_slow", end_flow_brick()),
+            node("end_delay", end_flow_brick()),
+        ],
+        edges: vec![
+            edge("start", "begin", "slow", "execute"),
+            edge("start", "begin", "delay", "execute"),
+            edge("slow", "done", "end_slow", "execute"),
+            edge("delay", "completed", "end_delay", "execute"),
+        ],
+    };
+
+    let mut engine = Engine::new(graph);
+    engine.start();
+
+    let executed: Vec<String> = (&mut engine)
+        .map(|result| result.expect("Continue should not fail the run"))
+        .filter(|node_id| !node_id.is_empty())
+        .collect();
+
+    println!("  Execution order: {:?}", executed);
+
+    // Neither node got to fire its execution output
+    assert_eq!(executed, vec!["start", "slow", "delay"]);
+
+    let slow = engine.node_state("slow").unwrap();
+    assert_eq!(slow.attempt, 2);
+    assert!(slow.timed_out);
+    assert_eq!(
+        slow.error_message.as_deref(),
+        Some("Node 'slow' failed: timed out after 50ms")
+    );
+
+    let delay = engine.node_state("delay").unwrap();
+    assert_eq!(delay.attempt, 1);
+    assert!(delay.timed_out);
+    assert_eq!(
+        delay.error_message.as_deref(),
+        Some("Node 'delay' failed: timed out after 50ms")
+    );
+}
+
+#[test]
+fn test_backoff_delays_retries() {
+    use crate::engine::policy::Backoff;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+    // Flow node: Fails on its first execution
+    brick! {
+        #[id("flaky_once")]
+        #[category("Control Flow")]
+        #[execution_input("execute")]
+        #[execution_output("done")]
+        fn flaky_once(#[context] ctx: &BrickContext) -> Result<(#[label("Calls")] i32), BrickError> {
+            let calls = CALLS.fetch_add(1, Ordering::SeqCst) + 1;
+            if calls < 2 {
+                return Err(BrickError::failed("not yet"));
+            }
+            ctx.trigger("done");
+            Ok((calls as i32,))
+        }
+    }
+
+    // Graph: start → flaky_once → end
+    let graph = Graph {
+        nodes: vec![
+            node("start", start_flow_brick()),
+            with_policy(
+                node("flaky", flaky_once_brick()),
+                NodePolicy {
+                    max_attempts: Some(2),
+                    backoff: Backoff::Fixed { delay_ms: 150 },
+                    ..Default::default()
+                },
+            ),
+            node("end", end_flow_brick()),
+        ],
+        edges: vec![
+            edge("start", "begin", "flaky", "execute"),
+            edge("flaky", "done", "end", "execute"),
+        ],
+    };
+
+    let mut engine = Engine::new(graph);
+    engine.start();
+
+    let started = std::time::Instant::now();
+    let executed: Vec<String> = (&mut engine)
+        .map(|result| result.expect("The second attempt should succeed"))
+        .filter(|node_id| !node_id.is_empty())
+        .collect();
+
+    assert_eq!(executed, vec!["start", "flaky", "end"]);
+    assert!(started.elapsed() >= std::time::Duration::from_millis(150));
+    assert_eq!(engine.node_state("flaky").unwrap().attempt, 2);
+}
+
+#[test]
+fn test_stepped_execution() {
+    // Graph: start → print → end
+    let graph = Graph {
+        nodes: vec![
+            node("start", start_flow_brick()),
+            node("print", print_and_continue_brick()),
+            node("end", end_flow_brick()),
+        ],
+        edges: vec![
+            edge("start", "begin", "print", "execute"),
+            edge("print", "done", "end", "execute"),
+        ],
+    };
+
+    let mut engine = Engine::new(graph);
+    engine.set_execution_mode(crate::engine::ExecutionMode::Stepped);
+    engine.start();
+    let debugger = engine.debugger();
+
+    // Nothing runs until stepped
+    assert_eq!(engine.next(), Some(Ok(String::new())));
+    assert_eq!(engine.next(), Some(Ok(String::new())));
+    assert_eq!(
+        engine.node_state("start").unwrap().phase,
+        crate::engine::ExecutionPhase::Paused
+    );
+
+    debugger.step();
+    assert_eq!(engine.next(), Some(Ok("start".to_string())));
+    assert_eq!(engine.next(), Some(Ok(String::new())));
+
+    debugger.step();
+    assert_eq!(engine.next(), Some(Ok("print".to_string())));
+    assert_eq!(engine.next(), Some(Ok(String::new())));
+
+    debugger.resume();
+    assert_eq!(engine.next(), Some(Ok("end".to_string())));
+    assert_eq!(engine.next(), None);
+}
+
+#[test]
+fn test_conditional_breakpoint_pauses_loop() {
+    use crate::engine::debugger::{Breakpoint, BreakpointCondition, ConditionOperator};
+
+    // Graph: start → loop (0..3) → body: print(index + 3), paused once the value is 4
+    let mut print = node("print", print_and_continue_brick());
+    print.data.breakpoint = Some(Breakpoint {
+        conditions: vec![BreakpointCondition {
+            input: "value".to_string(),
+            operator: ConditionOperator::Equals,
+            value: Value::Integer(4),
+        }],
+    });
+
+    let graph = Graph {
+        nodes: vec![
+            node("start", start_flow_brick()),
+            with_defaults(
+                node("loop", crate::bricks::control_flow::for_range_brick()),
+                &[("end", "3")],
+            ),
+            node("add", add_numbers_brick()),
+            print,
+        ],
+        edges: vec![
+            edge("start", "begin", "loop", "execute"),
+            edge("loop", "body", "print", "execute"),
+            edge("loop", "output_0", "add", "a"),
+            edge("add", "output_0", "print", "value"),
+        ],
+    };
+
+    let mut engine = Engine::new(graph);
+    engine.start();
+    let debugger = engine.debugger();
+
+    // Runs freely up to the second iteration of the body
+    let executed: Vec<String> = (&mut engine)
+        .map(|result| result.unwrap())
+        .take_while(|node_id| !node_id.is_empty())
+        .collect();
+    assert_eq!(
+        executed,
+        vec!["start", "loop", "add", "print", "loop", "add"]
+    );
+    assert!(debugger.is_paused());
+    assert_eq!(
+        engine.main_wave.cached("add").unwrap()[0].value,
+        Value::Number(4.0)
+    );
+
+    debugger.resume();
+    let executed: Vec<String> = (&mut engine)
+        .map(|result| result.unwrap())
+        .filter(|node_id| !node_id.is_empty())
+        .collect();
+    assert_eq!(executed, vec!["print", "loop", "add", "print", "loop"]);
+}
+
+#[test]
+fn test_inspect_and_override_while_paused() {
+    // Graph: start → print(add) → end(print)
+    let graph = Graph {
+        nodes: vec![
+            node("start", start_flow_brick()),
+            node("add", add_numbers_brick()),
+            node("print", print_and_continue_brick()),
+            node("end", end_flow_brick()),
+        ],
+        edges: vec![
+            edge("start", "begin", "print", "execute"),
+            edge("print", "done", "end", "execute"),
+            edge("add", "output_0", "print", "value"),
+            edge("print", "output_0", "end", "final_value"),
+        ],
+    };
+
+    let mut engine = Engine::new(graph);
+    engine.set_execution_mode(crate::engine::ExecutionMode::Stepped);
+    engine.start();
+    let debugger = engine.debugger();
+
+    // Step through start and the data node feeding print
+    for expected in ["start", "add"] {
+        debugger.step();
+        let executed = engine.find(|result| !matches!(result, Ok(id) if id.is_empty()));
+        assert_eq!(executed, Some(Ok(expected.to_string())));
+    }
+    assert_eq!(engine.next(), Some(Ok(String::new())));
+
+    let inspection = debugger.inspect("print").unwrap();
+    assert_eq!(inspection.len(), 1);
+    assert!(inspection[0].outputs.is_none());
+    assert_eq!(
+        inspection[0].inputs.as_ref().unwrap()[0].value,
+        Value::Number(8.0)
+    );
+    assert!(debugger.caches()[0].outputs.contains_key("add"));
+
+    // Print receives the overridden value once execution continues
+    debugger
+        .override_output(None, "add", "output_0", Value::Number(10.0))
+        .unwrap();
+    assert!(debugger
+        .override_output(None, "end", "output_0", Value::Number(1.0))
+        .is_err());
+    assert_eq!(
+        debugger.inspect("print").unwrap()[0]
+            .inputs
+            .as_ref()
+            .unwrap()[0]
+            .value,
+        Value::Number(10.0)
+    );
+
+    debugger.resume();
+    let executed: Vec<String> = (&mut engine).map(|result| result.unwrap()).collect();
+    assert_eq!(executed, vec!["print", "end"]);
+    assert_eq!(
+        engine.main_wave.cached("end").unwrap()[0].value,
+        Value::Number(10.0)
+    );
+}
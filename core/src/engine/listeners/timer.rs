@@ -1,6 +1,6 @@
 use super::EventListener;
 use crate::engine::events::ExecutionEvent;
-use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::mpsc::{self, Receiver};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration;
@@ -147,7 +147,7 @@ mod tests {
         }
 
         assert!(
-            event_count >= 3 && event_count <= 4,
+            (3..=4).contains(&event_count),
             "Expected 3-4 ticks, got {}",
             event_count
         );
@@ -174,13 +174,10 @@ mod tests {
         // Wait for exactly 5 ticks
         let mut ticks = 0;
         while ticks < 5 {
-            if let Ok(event) = receiver.recv_timeout(Duration::from_millis(100)) {
-                match event {
-                    ExecutionEvent::TimerTick { .. } => {
-                        ticks += 1;
-                    }
-                    _ => {}
-                }
+            if let Ok(ExecutionEvent::TimerTick { .. }) =
+                receiver.recv_timeout(Duration::from_millis(100))
+            {
+                ticks += 1;
             }
         }
 
@@ -1,18 +1,29 @@
 use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Receiver;
+use std::sync::{Arc, Mutex, RwLock};
 use std::time::Instant;
 
 use crate::{
-    api::ApiEventTrigger,
-    bricks::types::{BrickArgumentValue, BrickInputValue, BrickOutputValue},
+    bricks::registry::BrickRegistry,
+    bricks::types::{BrickOutputValue, ERROR_EXECUTION_OUTPUT},
     prelude::*,
 };
-use tauri::{AppHandle, Runtime};
 pub mod data_dfs;
+pub mod debugger;
 pub mod emission_contexts; // Public for extensibility - users can create custom contexts
 pub mod events;
 pub mod listeners;
+pub mod observer;
+pub mod policy;
+pub mod pool;
 
+use debugger::Debugger;
 use emission_contexts::EmissionContext;
+use observer::ExecutionObserver;
+use policy::NodePolicy;
+use pool::{ConcurrencyPolicy, WaveMessage, WavePool};
+use wave::Wave;
 #[cfg(test)]
 mod flow_test;
 #[cfg(test)]
@@ -21,101 +32,106 @@ mod self_emit_test;
 mod tests;
 pub mod topological;
 pub mod trigger;
+pub mod validation;
+pub mod wave;
+#[cfg(feature = "net")]
+pub mod webhook;
 
-#[derive(Clone, serde::Serialize, serde::Deserialize, specta::Type)]
+#[derive(Debug, Default, Clone, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "tauri", derive(specta::Type))]
 pub enum ExecutionMode {
-    Normal,  // Run until completion
+    #[default]
+    Normal, // Run until completion
     Stepped, // Manual step-by-step
 }
 
-impl Default for ExecutionMode {
-    fn default() -> Self {
-        Self::Normal
-    }
-}
-
-#[derive(Clone, serde::Serialize, serde::Deserialize, specta::Type)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "tauri", derive(specta::Type))]
 pub struct ExecutionStateUpdate {
     pub node_id: String,
     pub state: NodeExecutionState,
     pub execution_mode: ExecutionMode,
 }
 
-pub struct Engine<R: Runtime = tauri::Wry> {
-    graph: Graph,
-    /// Queue of flow nodes waiting to execute
-    queue: VecDeque<String>,
-    /// Cache of data node outputs (node_id -> outputs)
-    cache: HashMap<String, Vec<BrickOutputValue>>,
-    /// Current flow node being processed
-    current_flow_node: Option<String>,
-    /// Pending data dependencies for the current flow node
-    pending_data_deps: VecDeque<String>,
+pub struct Engine {
+    /// Graph and node states, shared with the worker pool
+    shared: Arc<EngineShared>,
+    /// Wave started by `start`, executed on the thread iterating the engine
+    main_wave: Wave,
+    /// Listener registry for self-emitting nodes
+    listener_registry: Option<listeners::ListenerRegistry>,
+    /// Workers running the waves started by events
+    pool: Option<WavePool>,
+    /// Progress reported by the waves on the pool
+    wave_messages: Option<Receiver<WaveMessage>>,
+    /// Event waves currently on the pool
+    running_waves: Vec<RunningWave>,
+    /// Events waiting for a free worker or for their trigger's previous wave
+    pending_events: VecDeque<events::ExecutionEvent>,
+    /// Maximum number of event waves running at the same time
+    max_concurrent_waves: usize,
+    next_wave_id: u64,
+    /// Runtime for async bricks, when the engine wasn't created inside one it can use
+    async_runtime: Option<tokio::runtime::Runtime>,
+    /// Whether observers were told a run started, and not yet that it finished
+    running: bool,
+}
+
+/// An event wave that was handed to the worker pool
+struct RunningWave {
+    id: u64,
+    trigger_node: String,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl RunningWave {
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+/// Everything the waves of one engine read and update, no matter which thread they run on
+pub struct EngineShared {
+    graph: Arc<Graph>,
     /// Fast node lookup (node_id -> node index)
     node_index: HashMap<String, usize>,
     /// Enable debug output
     debug: bool,
-    /// AppHandle for event broadcasting
-    app_handle: Option<AppHandle<R>>,
+    /// Told about node state changes, logs and events
+    observers: RwLock<Vec<Arc<dyn ExecutionObserver>>>,
     /// Execution mode (Normal or Stepped)
-    execution_mode: ExecutionMode,
+    execution_mode: Mutex<ExecutionMode>,
     /// Per-node execution states
-    node_states: HashMap<String, NodeExecutionState>,
+    node_states: Mutex<HashMap<String, NodeExecutionState>>,
     /// Per-node execution start times
-    node_start_times: HashMap<String, Instant>,
-    /// Listener registry for self-emitting nodes
-    listener_registry: Option<listeners::ListenerRegistry>,
+    node_start_times: Mutex<HashMap<String, Instant>>,
+    /// Data nodes that only depend on constants, their results are shared by every wave
+    constant_nodes: HashSet<String>,
+    /// Results of the constant data nodes computed so far
+    constants: Mutex<HashMap<String, Vec<BrickOutputValue>>>,
+    /// Decides when waves pause before a node
+    debugger: Arc<Debugger>,
+    /// Runtime async bricks are spawned on
+    runtime: tokio::runtime::Handle,
 }
 
-// Test-friendly implementation for default runtime
 impl Engine {
-    /// Create a test engine without app handle (for testing only)
-    #[cfg(test)]
-    pub fn new_test(graph: Graph) -> Self {
-        Self::with_debug_test(graph, false)
-    }
-
-    /// Create a test engine with debug (for testing only)
-    #[cfg(test)]
-    pub fn with_debug_test(graph: Graph, debug: bool) -> Self {
-        // Build node index for O(1) lookups
-        let node_index: HashMap<String, usize> = graph
-            .nodes
-            .iter()
-            .enumerate()
-            .map(|(idx, node)| (node.id.clone(), idx))
-            .collect();
-
-        // Initialize node states
-        let node_states: HashMap<String, NodeExecutionState> = graph
-            .nodes
-            .iter()
-            .map(|node| (node.id.clone(), NodeExecutionState::default()))
-            .collect();
-
-        Self {
-            graph,
-            queue: VecDeque::new(),
-            cache: HashMap::new(),
-            current_flow_node: None,
-            pending_data_deps: VecDeque::new(),
-            node_index,
-            debug,
-            app_handle: None,
-            execution_mode: ExecutionMode::Normal,
-            node_states,
-            node_start_times: HashMap::new(),
-            listener_registry: None,
-        }
-    }
-}
-
-impl<R: Runtime> Engine<R> {
     pub fn new(graph: Graph) -> Self {
         Self::with_debug(graph, false)
     }
 
     pub fn with_debug(graph: Graph, debug: bool) -> Self {
+        Self::build(graph, debug)
+    }
+
+    fn build(mut graph: Graph, debug: bool) -> Self {
+        // Graphs that weren't loaded through `Graph::from_json` may not have their bricks yet
+        for node in &mut graph.nodes {
+            if node.data.brick.is_none() {
+                node.data.brick = BrickRegistry::global().get(&node.data.brick_id);
+            }
+        }
+
         // Build node index for O(1) lookups
         let node_index: HashMap<String, usize> = graph
             .nodes
@@ -131,101 +147,95 @@ impl<R: Runtime> Engine<R> {
             .map(|node| (node.id.clone(), NodeExecutionState::default()))
             .collect();
 
+        let constant_nodes = constant_data_nodes(&graph);
+        let graph = Arc::new(graph);
+        let debugger = Arc::new(Debugger::new(Arc::clone(&graph)));
+
+        // Async bricks run on the surrounding runtime (Tauri's), or on one of our own.
+        // A current-thread runtime only makes progress while its thread awaits, which
+        // the engine's threads never do.
+        let (runtime, async_runtime) = match tokio::runtime::Handle::try_current() {
+            Ok(handle)
+                if handle.runtime_flavor() != tokio::runtime::RuntimeFlavor::CurrentThread =>
+            {
+                (handle, None)
+            }
+            _ => {
+                let runtime = tokio::runtime::Builder::new_multi_thread()
+                    .worker_threads(2)
+                    .thread_name("vla-async")
+                    .enable_all()
+                    .build()
+                    .expect("Failed to start the async brick runtime");
+                (runtime.handle().clone(), Some(runtime))
+            }
+        };
+
         Self {
-            graph,
-            queue: VecDeque::new(),
-            cache: HashMap::new(),
-            current_flow_node: None,
-            pending_data_deps: VecDeque::new(),
-            node_index,
-            debug,
-            app_handle: None,
-            execution_mode: ExecutionMode::Normal,
-            node_states,
-            node_start_times: HashMap::new(),
+            shared: Arc::new(EngineShared {
+                graph,
+                node_index,
+                debug,
+                observers: RwLock::new(Vec::new()),
+                execution_mode: Mutex::new(ExecutionMode::Normal),
+                node_states: Mutex::new(node_states),
+                node_start_times: Mutex::new(HashMap::new()),
+                constant_nodes,
+                constants: Mutex::new(HashMap::new()),
+                debugger,
+                runtime,
+            }),
+            main_wave: Wave::new(0, HashMap::new(), trigger::ExecutionContext::default()),
             listener_registry: None,
+            pool: None,
+            wave_messages: None,
+            running_waves: Vec::new(),
+            pending_events: VecDeque::new(),
+            max_concurrent_waves: pool::DEFAULT_MAX_CONCURRENT_WAVES,
+            next_wave_id: 1,
+            async_runtime,
+            running: false,
         }
     }
 
-    /// Create engine with AppHandle for event broadcasting
-    pub fn with_app_handle(graph: Graph, app_handle: AppHandle<R>) -> Self {
-        let mut engine = Self::new(graph);
-        engine.app_handle = Some(app_handle);
-        engine
+    /// Tell an observer about everything this engine does from now on
+    pub fn add_observer(&self, observer: Arc<dyn ExecutionObserver>) {
+        self.shared.observers.write().unwrap().push(observer);
     }
 
     /// Set execution mode
     pub fn set_execution_mode(&mut self, mode: ExecutionMode) {
-        self.execution_mode = mode;
+        self.shared.debugger.set_mode(&mode);
+        *self.shared.execution_mode.lock().unwrap() = mode;
     }
 
-    /// Update node state and broadcast change
-    fn update_node_state(
-        &mut self,
-        node_id: &str,
-        phase: ExecutionPhase,
-        outputs: Option<Vec<BrickOutputValue>>,
-    ) {
-        if let Some(node_state) = self.node_states.get_mut(node_id) {
-            node_state.outputs = outputs;
-
-            match phase {
-                ExecutionPhase::Running => {
-                    // Record start time when execution begins
-                    self.node_start_times
-                        .insert(node_id.to_string(), Instant::now());
-                    node_state.phase = phase.clone();
-                    node_state.elapsed_ms = 0;
-                }
-                ExecutionPhase::Completed | ExecutionPhase::Errored => {
-                    // Calculate elapsed time when execution finishes
-                    if let Some(start_time) = self.node_start_times.get(node_id) {
-                        let elapsed = start_time.elapsed();
-                        node_state.elapsed_ms = elapsed.as_millis() as u32;
-                        self.node_start_times.remove(node_id);
-                    }
-                    node_state.phase = phase.clone();
-                }
-                _ => {
-                    // For other phases (Waiting, Queued), just update phase
-                    node_state.phase = phase.clone();
-                    node_state.elapsed_ms = 0;
-                }
-            }
-
-            let state_copy = node_state.clone();
-            self.broadcast_execution_state_update(node_id, state_copy);
-        }
+    /// Debugger of this engine, to step through or pause a running execution
+    pub fn debugger(&self) -> Arc<Debugger> {
+        Arc::clone(&self.shared.debugger)
     }
 
-    /// Broadcast execution state update event
-    fn broadcast_execution_state_update(&self, node_id: &str, state: NodeExecutionState) {
-        if let Some(app_handle) = &self.app_handle {
-            let update = ExecutionStateUpdate {
-                node_id: node_id.to_string(),
-                state,
-                execution_mode: self.execution_mode.clone(),
-            };
-
-            if let Err(e) = ApiEventTrigger::new(app_handle.clone()).node_execution_updated(update)
-            {
-                eprintln!("Failed to broadcast node execution state update: {}", e);
-            }
-        }
+    /// Limit how many event waves run at the same time (applies from the next `start`)
+    pub fn set_max_concurrent_waves(&mut self, max: usize) {
+        self.max_concurrent_waves = max.max(1);
     }
 
-    /// Get a node by ID (O(1) lookup)
-    fn get_node(&self, node_id: &str) -> Option<&Node> {
-        self.node_index
+    /// Current execution state of a node
+    pub fn node_state(&self, node_id: &str) -> Option<NodeExecutionState> {
+        self.shared
+            .node_states
+            .lock()
+            .unwrap()
             .get(node_id)
-            .and_then(|&idx| self.graph.nodes.get(idx))
+            .cloned()
     }
 
-    /// Debug logging helper
-    fn debug_log(&self, msg: &str) {
-        if self.debug {
-            println!("{}", msg);
-        }
+    /// Read a non-empty string argument from a node, stripping JSON quotes if present
+    fn string_argument(node: &Node, argument_id: &str) -> Option<String> {
+        node.data
+            .arguments
+            .get(argument_id)
+            .map(|v| v.trim().trim_matches('"').to_string())
+            .filter(|v| !v.is_empty())
     }
 
     pub fn start(&mut self) {
@@ -239,29 +249,45 @@ impl<R: Runtime> Engine<R> {
         event_receiver: std::sync::mpsc::Receiver<events::ExecutionEvent>,
         event_sender: std::sync::mpsc::Sender<events::ExecutionEvent>,
     ) {
-        // Clear previous execution state
-        self.cache.clear();
-        self.queue.clear();
-        self.current_flow_node = None;
-        self.pending_data_deps.clear();
-        self.node_start_times.clear();
-
         // Stop any existing listeners
         if let Some(mut registry) = self.listener_registry.take() {
             let _ = registry.stop_all();
         }
 
+        // Stop waves left over from a previous run
+        self.stop_waves();
+        self.finish_run();
+        self.running = true;
+        self.shared.notify(|observer| observer.run_started());
+
+        // Clear previous execution state
+        self.main_wave = Wave::new(0, HashMap::new(), trigger::ExecutionContext::default());
+        self.shared.debugger.watch(&self.main_wave);
+        self.pending_events.clear();
+        self.next_wave_id = 1;
+        self.shared.node_start_times.lock().unwrap().clear();
+        self.shared.constants.lock().unwrap().clear();
+
+        // Start the workers for event waves
+        let (message_sender, message_receiver) = std::sync::mpsc::channel();
+        self.pool = Some(WavePool::new(
+            self.max_concurrent_waves,
+            Arc::clone(&self.shared),
+            message_sender,
+        ));
+        self.wave_messages = Some(message_receiver);
+
         // Reset all node states to Waiting
-        let node_ids: Vec<String> = self.graph.nodes.iter().map(|n| n.id.clone()).collect();
-        for node_id in node_ids {
-            self.update_node_state(&node_id, ExecutionPhase::Waiting, None);
+        for node in &self.shared.graph.nodes {
+            self.shared
+                .update_node_state(&node.id, ExecutionPhase::Waiting, None);
         }
 
         // Create registry with the provided event receiver
         let mut registry = listeners::ListenerRegistry::new_with_receiver(event_receiver);
 
         // Scan for self-emitting nodes and create listeners
-        for node in &self.graph.nodes {
+        for node in &self.shared.graph.nodes {
             if let Some(brick) = &node.data.brick {
                 match &brick.emission_type {
                     crate::bricks::types::BrickEmissionType::Timer {
@@ -279,7 +305,7 @@ impl<R: Runtime> Engine<R> {
                             })
                             .unwrap_or(*default_interval_ms as u64);
 
-                        self.debug_log(&format!(
+                        self.shared.debug_log(&format!(
                             "Creating timer listener for {} ({}ms)",
                             node.id, interval_ms
                         ));
@@ -288,7 +314,84 @@ impl<R: Runtime> Engine<R> {
                         let mut context =
                             Box::new(emission_contexts::TimerContext::new(interval_ms));
                         if let Err(e) = context.start(node.id.clone(), event_sender.clone()) {
-                            self.debug_log(&format!("Failed to start timer context: {}", e));
+                            self.shared
+                                .debug_log(&format!("Failed to start timer context: {}", e));
+                        } else {
+                            // Store context in registry's listeners
+                            registry
+                                .listeners
+                                .push(context as Box<dyn emission_contexts::EmissionContext>);
+                        }
+                    }
+                    #[cfg(feature = "net")]
+                    crate::bricks::types::BrickEmissionType::HttpWebhook {
+                        default_path,
+                        default_method,
+                    } => {
+                        // Get route from node arguments or use defaults
+                        let path = Self::string_argument(node, "path")
+                            .unwrap_or_else(|| default_path.clone());
+                        let method = Self::string_argument(node, "method")
+                            .unwrap_or_else(|| default_method.clone());
+                        let port = Self::string_argument(node, "port")
+                            .and_then(|v| v.parse::<u16>().ok())
+                            .unwrap_or(webhook::DEFAULT_WEBHOOK_PORT);
+
+                        self.shared.debug_log(&format!(
+                            "Creating webhook listener for {} ({} {} on port {})",
+                            node.id, method, path, port
+                        ));
+
+                        // Create webhook context and start it
+                        let mut context =
+                            emission_contexts::HttpWebhookContext::new(port, path, method);
+
+                        // Hold requests open when the flow answers them with respond_http
+                        if self.shared.flow_reaches_brick(&node.id, "respond_http") {
+                            let timeout_ms = Self::string_argument(node, "timeout_ms")
+                                .and_then(|v| v.parse::<u64>().ok())
+                                .unwrap_or(webhook::DEFAULT_RESPONSE_TIMEOUT_MS);
+                            context = context.with_response_timeout(
+                                std::time::Duration::from_millis(timeout_ms),
+                            );
+                        }
+
+                        let mut context = Box::new(context);
+                        if let Err(e) = context.start(node.id.clone(), event_sender.clone()) {
+                            self.shared
+                                .debug_log(&format!("Failed to start webhook context: {}", e));
+                        } else {
+                            // Store context in registry's listeners
+                            registry
+                                .listeners
+                                .push(context as Box<dyn emission_contexts::EmissionContext>);
+                        }
+                    }
+                    #[cfg(feature = "fs")]
+                    crate::bricks::types::BrickEmissionType::FileWatcher { default_pattern } => {
+                        // Get watch target from node arguments or use defaults
+                        let directory = Self::string_argument(node, "directory")
+                            .unwrap_or_else(|| ".".to_string());
+                        let pattern = Self::string_argument(node, "pattern")
+                            .unwrap_or_else(|| default_pattern.clone());
+                        let debounce_ms = Self::string_argument(node, "debounce_ms")
+                            .and_then(|v| v.parse::<u64>().ok())
+                            .unwrap_or(emission_contexts::DEFAULT_FILE_DEBOUNCE_MS);
+
+                        self.shared.debug_log(&format!(
+                            "Creating file watcher for {} ({} in {}, {}ms debounce)",
+                            node.id, pattern, directory, debounce_ms
+                        ));
+
+                        // Create file watcher context and start it
+                        let mut context = Box::new(emission_contexts::FileWatcherContext::new(
+                            directory,
+                            pattern,
+                            debounce_ms,
+                        ));
+                        if let Err(e) = context.start(node.id.clone(), event_sender.clone()) {
+                            self.shared
+                                .debug_log(&format!("Failed to start file watcher context: {}", e));
                         } else {
                             // Store context in registry's listeners
                             registry
@@ -297,12 +400,13 @@ impl<R: Runtime> Engine<R> {
                         }
                     }
                     crate::bricks::types::BrickEmissionType::ManualTrigger => {
-                        self.debug_log(&format!("Creating manual trigger context for {}", node.id));
+                        self.shared
+                            .debug_log(&format!("Creating manual trigger context for {}", node.id));
 
                         // Create manual trigger context and start it
                         let mut context = Box::new(emission_contexts::ManualTriggerContext::new());
                         if let Err(e) = context.start(node.id.clone(), event_sender.clone()) {
-                            self.debug_log(&format!(
+                            self.shared.debug_log(&format!(
                                 "Failed to start manual trigger context: {}",
                                 e
                             ));
@@ -326,6 +430,7 @@ impl<R: Runtime> Engine<R> {
         // IMPORTANT: Exclude self-emitting nodes (ManualTrigger, Timer, etc) as they
         // should only execute when their events fire, not on engine start
         let start_nodes: Vec<String> = self
+            .shared
             .graph
             .nodes
             .iter()
@@ -349,10 +454,16 @@ impl<R: Runtime> Engine<R> {
         // If no flow start nodes found, treat all nodes with no incoming edges as start nodes
         // BUT exclude self-emitting nodes (they should only execute on events)
         if start_nodes.is_empty() {
-            let nodes_with_incoming: std::collections::HashSet<String> =
-                self.graph.edges.iter().map(|e| e.target.clone()).collect();
+            let nodes_with_incoming: std::collections::HashSet<String> = self
+                .shared
+                .graph
+                .edges
+                .iter()
+                .map(|e| e.target.clone())
+                .collect();
 
             let nodes_to_queue: Vec<String> = self
+                .shared
                 .graph
                 .nodes
                 .iter()
@@ -372,184 +483,397 @@ impl<R: Runtime> Engine<R> {
                 .collect();
 
             for node_id in nodes_to_queue {
-                self.update_node_state(&node_id, ExecutionPhase::Queued, None);
-                self.queue.push_back(node_id);
+                self.main_wave.enqueue(&self.shared, node_id);
             }
         } else {
             // Queue all start nodes for execution
             for node_id in start_nodes {
-                self.update_node_state(&node_id, ExecutionPhase::Queued, None);
-                self.queue.push_back(node_id);
+                self.main_wave.enqueue(&self.shared, node_id);
             }
         }
     }
 
     /// Manually enqueue a flow node for execution
     pub fn enqueue(&mut self, node_id: String) {
-        self.update_node_state(&node_id, ExecutionPhase::Queued, None);
-        self.queue.push_back(node_id);
+        self.main_wave.enqueue(&self.shared, node_id);
     }
 
-    /// Get cached node IDs
-    fn get_cached_node_ids(&self) -> HashSet<String> {
-        self.cache.keys().cloned().collect()
+    /// Concurrency policy configured on a trigger node
+    fn concurrency_policy(&self, node_id: &str) -> ConcurrencyPolicy {
+        self.shared
+            .get_node(node_id)
+            .and_then(|node| Self::string_argument(node, "concurrency"))
+            .and_then(|v| v.parse().ok())
+            .unwrap_or_default()
     }
 
-    /// Resolve all data dependencies for a node using DFS
-    fn resolve_data_dependencies(&self, node_id: &str) -> Vec<String> {
-        let cached = self.get_cached_node_ids();
-        let dfs_iter = data_dfs::DataNodeDfsIterator::new(&self.graph, node_id, &cached);
-        dfs_iter.collect()
+    /// Check whether a trigger node has a wave running (or waiting) that wasn't cancelled
+    fn trigger_is_busy(&self, node_id: &str) -> bool {
+        self.running_waves
+            .iter()
+            .any(|wave| wave.trigger_node == node_id && !wave.is_cancelled())
+            || self
+                .pending_events
+                .iter()
+                .any(|event| event.target_node_id() == node_id)
     }
 
-    /// Execute a single node (data or flow) and cache its outputs
-    fn execute_node_internal(&mut self, node_id: &str) -> Result<(), String> {
-        // Mark node as running
-        self.update_node_state(node_id, ExecutionPhase::Running, None);
+    /// Apply the trigger's concurrency policy to a new event and start it if there is room
+    fn schedule_event(&mut self, event: events::ExecutionEvent) {
+        let node_id = event.target_node_id().to_string();
+        self.shared
+            .debug_log(&format!("Event received for node: {}", node_id));
+
+        match self.concurrency_policy(&node_id) {
+            ConcurrencyPolicy::Queue => {}
+            ConcurrencyPolicy::Drop => {
+                if self.trigger_is_busy(&node_id) {
+                    self.shared
+                        .debug_log(&format!("Dropped event for busy node: {}", node_id));
+                    return;
+                }
+            }
+            ConcurrencyPolicy::Replace => {
+                for wave in &self.running_waves {
+                    if wave.trigger_node == node_id {
+                        wave.cancelled.store(true, Ordering::SeqCst);
+                    }
+                }
+                self.pending_events
+                    .retain(|pending| pending.target_node_id() != node_id);
+            }
+        }
 
-        // Artificial delay for debugging; only enabled in debug builds
-        if cfg!(debug_assertions) {
-            // std::thread::sleep(std::time::Duration::from_millis(100));
+        self.pending_events.push_back(event);
+        self.dispatch_pending_events();
+    }
+
+    /// Start waiting events, oldest first, while the pool has room for them
+    fn dispatch_pending_events(&mut self) {
+        let mut index = 0;
+
+        while index < self.pending_events.len()
+            && self.running_waves.len() < self.max_concurrent_waves
+        {
+            let node_id = self.pending_events[index].target_node_id().to_string();
+
+            // Queued triggers wait for their previous wave, other triggers can go ahead
+            let previous_running = self
+                .running_waves
+                .iter()
+                .any(|wave| wave.trigger_node == node_id && !wave.is_cancelled());
+            if previous_running {
+                index += 1;
+                continue;
+            }
+
+            if let Some(event) = self.pending_events.remove(index) {
+                self.start_wave(event);
+            }
         }
+    }
 
-        trigger::set_current_node_id(node_id);
+    /// Hand a new wave for an event to the worker pool
+    fn start_wave(&mut self, event: events::ExecutionEvent) {
+        let Some(pool) = &self.pool else {
+            return;
+        };
 
-        let node = self
-            .get_node(node_id)
-            .ok_or_else(|| format!("Node '{}' not found", node_id))?;
+        let id = self.next_wave_id;
+        self.next_wave_id += 1;
+
+        let node_id = event.target_node_id().to_string();
+        let constants = self.shared.constants.lock().unwrap().clone();
+        let mut wave = Wave::new(id, constants, trigger::ExecutionContext::from_event(&event));
+        wave.enqueue(&self.shared, node_id.clone());
+        self.shared.debugger.watch(&wave);
+
+        self.shared
+            .debug_log(&format!("Wave {} started for node: {}", id, node_id));
+        self.running_waves.push(RunningWave {
+            id,
+            trigger_node: node_id,
+            cancelled: wave.cancellation_token(),
+        });
+        pool.submit(wave);
+    }
 
-        let brick = node
-            .data
-            .brick
-            .as_ref()
-            .ok_or_else(|| format!("Node '{}' has no brick", node_id))?;
-
-        // Build arguments from node data
-        let arguments = self.build_arguments(node, brick);
-
-        // Build inputs from connected edges and cached data
-        let inputs = match self.build_inputs(node, brick) {
-            Ok(inputs) => inputs,
-            Err(e) => {
-                // Mark as errored on input failure and set error message
-                self.update_node_state(node_id, ExecutionPhase::Errored, None);
-                if let Some(node_state) = self.node_states.get_mut(node_id) {
-                    node_state.error_message = Some(e.clone());
+    /// Next result reported by an event wave, starting waiting events as waves finish
+    fn receive_wave_result(&mut self) -> Option<Result<String, String>> {
+        loop {
+            let message = self.wave_messages.as_ref()?.try_recv().ok()?;
+
+            match message {
+                WaveMessage::Step { wave_id, result } => {
+                    // Results of a cancelled wave are no longer interesting
+                    let cancelled = self
+                        .running_waves
+                        .iter()
+                        .any(|wave| wave.id == wave_id && wave.is_cancelled());
+                    if !cancelled {
+                        return Some(result);
+                    }
+                }
+                WaveMessage::Finished { wave_id } => {
+                    self.running_waves.retain(|wave| wave.id != wave_id);
+                    self.dispatch_pending_events();
                 }
-                trigger::clear_current_node_id();
-                return Err(e);
             }
+        }
+    }
+
+    /// Cancel every event wave and wait for the workers to stop
+    fn stop_waves(&mut self) {
+        for wave in &self.running_waves {
+            wave.cancelled.store(true, Ordering::SeqCst);
+        }
+        self.pool.take();
+        self.wave_messages.take();
+        self.running_waves.clear();
+    }
+
+    /// Tell the observers the run is over, once
+    fn finish_run(&mut self) {
+        if !std::mem::take(&mut self.running) {
+            return;
+        }
+        let success = !self
+            .shared
+            .node_states
+            .lock()
+            .unwrap()
+            .values()
+            .any(|state| state.phase == ExecutionPhase::Errored);
+        self.shared
+            .notify(|observer| observer.run_finished(success));
+    }
+}
+
+impl EngineShared {
+    /// Update node state and broadcast change
+    fn update_node_state(
+        &self,
+        node_id: &str,
+        phase: ExecutionPhase,
+        outputs: Option<Vec<BrickOutputValue>>,
+    ) {
+        let mut node_states = self.node_states.lock().unwrap();
+        let Some(node_state) = node_states.get_mut(node_id) else {
+            return;
         };
+        let mut node_start_times = self.node_start_times.lock().unwrap();
 
-        // Execute the brick
-        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-            (brick.execution)(arguments, inputs)
-        }));
+        node_state.outputs = outputs;
 
-        match result {
-            Ok(outputs) => {
-                // Cache outputs and mark as completed
-                self.cache.insert(node_id.to_string(), outputs.clone());
-                self.update_node_state(node_id, ExecutionPhase::Completed, Some(outputs));
+        match phase {
+            ExecutionPhase::Running => {
+                // Record start time when execution begins
+                node_start_times.insert(node_id.to_string(), Instant::now());
+                node_state.phase = phase.clone();
+                node_state.elapsed_ms = 0;
             }
-            Err(_) => {
-                // Mark as errored on execution failure and set error message
-                self.update_node_state(node_id, ExecutionPhase::Errored, None);
-                if let Some(node_state) = self.node_states.get_mut(node_id) {
-                    node_state.error_message =
-                        Some(format!("Node '{}' execution panicked", node_id));
+            ExecutionPhase::Completed | ExecutionPhase::Errored => {
+                // Calculate elapsed time when execution finishes
+                if let Some(start_time) = node_start_times.remove(node_id) {
+                    node_state.elapsed_ms = start_time.elapsed().as_millis() as u32;
                 }
-                trigger::clear_current_node_id();
-                return Err(format!("Node '{}' execution panicked", node_id));
+                node_state.phase = phase.clone();
+            }
+            _ => {
+                // For other phases (Waiting, Queued), just update phase
+                node_state.phase = phase.clone();
+                node_state.elapsed_ms = 0;
             }
         }
 
-        trigger::clear_current_node_id();
+        let state_copy = node_state.clone();
+        drop(node_start_times);
+        drop(node_states);
+        self.broadcast_execution_state_update(node_id, state_copy);
+    }
 
-        self.debug_log(&format!("✓ Executed: {}", node_id));
+    /// Current phase of a node
+    fn node_phase(&self, node_id: &str) -> Option<ExecutionPhase> {
+        let node_states = self.node_states.lock().unwrap();
+        node_states.get(node_id).map(|state| state.phase.clone())
+    }
 
-        Ok(())
+    /// Mark a node as running the given attempt
+    fn start_attempt(&self, node_id: &str, attempt: u32) {
+        if let Some(node_state) = self.node_states.lock().unwrap().get_mut(node_id) {
+            node_state.attempt = attempt;
+            node_state.timed_out = false;
+        }
+        self.update_node_state(node_id, ExecutionPhase::Running, None);
     }
 
-    /// Build brick arguments from node data
-    fn build_arguments(&self, node: &Node, brick: &Brick) -> Vec<BrickArgumentValue> {
-        brick
-            .arguments
-            .iter()
-            .map(|arg_def| {
-                // Get value from node arguments, or use brick default
-                let value = node
-                    .data
-                    .arguments
-                    .get(&arg_def.id)
-                    .or(arg_def.default_value.as_ref())
-                    .cloned()
-                    .unwrap_or_default();
-
-                BrickArgumentValue {
-                    id: arg_def.id.clone(),
-                    value,
-                }
-            })
-            .collect()
+    /// Record that an attempt of a node ran into its timeout
+    fn set_node_timed_out(&self, node_id: &str) {
+        let mut node_states = self.node_states.lock().unwrap();
+        let Some(node_state) = node_states.get_mut(node_id) else {
+            return;
+        };
+        node_state.timed_out = true;
+
+        let state_copy = node_state.clone();
+        drop(node_states);
+        self.broadcast_execution_state_update(node_id, state_copy);
     }
 
-    /// Build brick inputs from connected edges and cached data
-    fn build_inputs(&self, node: &Node, brick: &Brick) -> Result<Vec<BrickInputValue>, String> {
-        brick
-            .inputs
-            .iter()
-            .map(|input_def| {
-                // Find incoming data edge for this input
-                let connected_value = self.find_connected_input_value(&node.id, &input_def.id);
-
-                // Priority: connected edge > node defaults > brick defaults
-                let value = connected_value
-                    .or_else(|| node.data.defaults.get(&input_def.id).cloned())
-                    .or_else(|| input_def.default_value.clone())
-                    .ok_or_else(|| {
-                        format!(
-                            "No value available for input '{}' on node '{}'",
-                            input_def.id, node.id
-                        )
-                    })?;
+    /// Mark a node as errored with the given message
+    fn set_node_error(&self, node_id: &str, message: String) {
+        // Before the update, so observers are told the message with it
+        if let Some(node_state) = self.node_states.lock().unwrap().get_mut(node_id) {
+            node_state.error_message = Some(message);
+        }
+        self.update_node_state(node_id, ExecutionPhase::Errored, None);
+    }
 
-                Ok(BrickInputValue {
-                    id: input_def.id.clone(),
-                    value,
-                })
-            })
-            .collect()
+    /// Tell the observers about a node's new state
+    fn broadcast_execution_state_update(&self, node_id: &str, state: NodeExecutionState) {
+        let update = ExecutionStateUpdate {
+            node_id: node_id.to_string(),
+            state,
+            execution_mode: self.execution_mode.lock().unwrap().clone(),
+        };
+        self.notify(|observer| observer.node_state_changed(&update));
     }
 
-    /// Find the value for an input by traversing data edges
-    fn find_connected_input_value(
-        &self,
-        target_node_id: &str,
-        target_input_id: &str,
-    ) -> Option<String> {
-        for edge in &self.graph.edges {
-            if edge.target == target_node_id && edge.target_handle == target_input_id {
-                if let Some(cached_outputs) = self.cache.get(&edge.source) {
-                    let source_output_id = edge.source_handle.as_str();
-
-                    return cached_outputs
-                        .iter()
-                        .find(|output| output.id == source_output_id)
-                        .map(|output| output.value.clone());
+    fn notify(&self, notify: impl Fn(&dyn ExecutionObserver)) {
+        for observer in self.observers.read().unwrap().iter() {
+            notify(observer.as_ref());
+        }
+    }
+
+    /// Get a node by ID (O(1) lookup)
+    fn get_node(&self, node_id: &str) -> Option<&Node> {
+        self.node_index
+            .get(node_id)
+            .and_then(|&idx| self.graph.nodes.get(idx))
+    }
+
+    /// Error policy of a node, the default one for unknown nodes
+    fn node_policy(&self, node_id: &str) -> NodePolicy {
+        self.get_node(node_id)
+            .map(|node| node.data.policy.clone())
+            .unwrap_or_default()
+    }
+
+    /// Check whether a node has an error execution output with a connected handler
+    fn has_error_branch(&self, node_id: &str) -> bool {
+        let has_output = self
+            .get_node(node_id)
+            .and_then(|node| node.data.brick.as_ref())
+            .is_some_and(|brick| {
+                brick
+                    .execution_outputs
+                    .iter()
+                    .any(|output| output.id == ERROR_EXECUTION_OUTPUT)
+            });
+
+        has_output
+            && self
+                .graph
+                .edges
+                .iter()
+                .any(|edge| edge.source == node_id && edge.source_handle == ERROR_EXECUTION_OUTPUT)
+    }
+
+    /// Debug logging helper
+    fn debug_log(&self, msg: &str) {
+        if self.debug {
+            println!("{}", msg);
+        }
+    }
+
+    /// Check whether the execution flow starting at a node can reach a node of the given brick
+    #[cfg(feature = "net")]
+    fn flow_reaches_brick(&self, node_id: &str, brick_id: &str) -> bool {
+        let mut visited = HashSet::new();
+        let mut stack = vec![node_id.to_string()];
+
+        while let Some(current) = stack.pop() {
+            if !visited.insert(current.clone()) {
+                continue;
+            }
+
+            let Some(brick) = self.get_node(&current).and_then(|n| n.data.brick.as_ref()) else {
+                continue;
+            };
+
+            if current != node_id && brick.id == brick_id {
+                return true;
+            }
+
+            for edge in &self.graph.edges {
+                let is_flow_edge = brick
+                    .execution_outputs
+                    .iter()
+                    .any(|output| output.id == edge.source_handle);
+                if edge.source == current && is_flow_edge {
+                    stack.push(edge.target.clone());
                 }
             }
         }
 
-        None
+        false
     }
 
-    /// Execute a data node (skips if already cached)
-    fn execute_data_node(&mut self, node_id: &str) -> Result<(), String> {
-        if self.cache.contains_key(node_id) {
-            return Ok(());
+    /// Data nodes that depend on a loop node or on the flow nodes in its body,
+    /// which have to be recomputed for every iteration
+    fn loop_scoped_data_nodes(&self, loop_node_id: &str, body_output: &str) -> HashSet<String> {
+        // Flow nodes executed per iteration: the loop node and everything its body reaches
+        let mut flow_nodes = HashSet::new();
+        let mut stack = vec![loop_node_id.to_string()];
+
+        while let Some(current) = stack.pop() {
+            if !flow_nodes.insert(current.clone()) {
+                continue;
+            }
+
+            let Some(brick) = self.get_node(&current).and_then(|n| n.data.brick.as_ref()) else {
+                continue;
+            };
+
+            for edge in self.graph.edges.iter().filter(|e| e.source == current) {
+                // Only the body output of the loop itself, what follows the loop runs once
+                let follows = if current == loop_node_id {
+                    edge.source_handle == body_output
+                } else {
+                    brick
+                        .execution_outputs
+                        .iter()
+                        .any(|output| output.id == edge.source_handle)
+                };
+
+                if follows {
+                    stack.push(edge.target.clone());
+                }
+            }
         }
-        self.execute_node_internal(node_id)
+
+        // Data nodes fed (directly or indirectly) by those flow nodes
+        let mut scope = HashSet::new();
+        let mut stack: Vec<String> = flow_nodes.into_iter().collect();
+
+        while let Some(current) = stack.pop() {
+            for edge in self.graph.edges.iter().filter(|e| e.source == current) {
+                if self.is_data_node(&edge.target) && scope.insert(edge.target.clone()) {
+                    stack.push(edge.target.clone());
+                }
+            }
+        }
+
+        scope
+    }
+
+    /// Check if a node is a data node (has no execution inputs/outputs)
+    fn is_data_node(&self, node_id: &str) -> bool {
+        self.get_node(node_id)
+            .and_then(|node| node.data.brick.as_ref())
+            .is_some_and(|brick| {
+                brick.execution_inputs.is_empty() && brick.execution_outputs.is_empty()
+            })
     }
 
     /// Find nodes triggered by an execution output
@@ -565,98 +889,119 @@ impl<R: Runtime> Engine<R> {
     }
 }
 
-impl<R: Runtime> Iterator for Engine<R> {
-    type Item = Result<String, String>;
+/// Data nodes whose inputs all come from other constant data nodes (or nothing at all).
+/// Their results are the same for every event, so they are computed once per run.
+fn constant_data_nodes(graph: &Graph) -> HashSet<String> {
+    let data_nodes: Vec<&Node> = graph
+        .nodes
+        .iter()
+        .filter(|node| {
+            node.data.brick.as_ref().is_some_and(|brick| {
+                brick.execution_inputs.is_empty() && brick.execution_outputs.is_empty()
+            })
+        })
+        .collect();
 
-    fn next(&mut self) -> Option<Self::Item> {
-        loop {
-            // State 0: Check for events from self-emitting nodes
-            if let Some(registry) = &mut self.listener_registry {
-                if let Some(receiver) = registry.event_receiver() {
-                    if let Ok(event) = receiver.try_recv() {
-                        // Set execution context for the brick
-                        let ctx = trigger::ExecutionContext::from_event(&event);
-                        trigger::set_execution_context(ctx);
-
-                        // Queue the node for execution
-                        let node_id = event.target_node_id().to_string();
-                        self.debug_log(&format!("Event received for node: {}", node_id));
-                        self.update_node_state(&node_id, ExecutionPhase::Queued, None);
-                        self.queue.push_back(node_id.clone());
-
-                        // Don't return yet, let it go through normal flow processing
-                    }
-                }
-            }
+    // Grow the set until it settles; nodes in a cycle never become constant
+    let mut constant = HashSet::new();
+    loop {
+        let mut changed = false;
 
-            // State 1: Process pending data dependencies
-            if let Some(data_node_id) = self.pending_data_deps.pop_front() {
-                return match self.execute_data_node(&data_node_id) {
-                    Ok(_) => Some(Ok(data_node_id)),
-                    Err(e) => Some(Err(e)),
-                };
+        for node in &data_nodes {
+            if constant.contains(&node.id) {
+                continue;
             }
 
-            // State 2: Execute current flow node
-            if let Some(flow_node_id) = self.current_flow_node.take() {
-                if let Err(e) = self.execute_node_internal(&flow_node_id) {
-                    return Some(Err(e));
-                }
-
-                // Collect triggers and queue next flow nodes
-                let triggers = trigger::collect_and_clear_triggers();
-                for trigger in &triggers {
-                    let next_nodes = self.find_triggered_nodes(trigger);
-                    for node_id in &next_nodes {
-                        self.update_node_state(node_id, ExecutionPhase::Queued, None);
-                    }
-                    self.queue.extend(next_nodes);
-                }
+            let inputs_constant = graph
+                .edges
+                .iter()
+                .filter(|edge| edge.target == node.id)
+                .all(|edge| constant.contains(&edge.source));
 
-                return Some(Ok(flow_node_id));
+            if inputs_constant {
+                constant.insert(node.id.clone());
+                changed = true;
             }
+        }
 
-            // State 3: Start new flow node from queue
-            if let Some(next_flow_node) = self.queue.pop_front() {
-                // Resolve data dependencies for this flow node
-                let data_deps = self.resolve_data_dependencies(&next_flow_node);
+        if !changed {
+            return constant;
+        }
+    }
+}
 
-                // Queue data dependencies and set current flow node
-                for dep_node in &data_deps {
-                    self.update_node_state(dep_node, ExecutionPhase::Queued, None);
-                }
-                self.pending_data_deps.extend(data_deps);
-                self.current_flow_node = Some(next_flow_node);
-                continue;
-            }
+impl Iterator for Engine {
+    type Item = Result<String, String>;
 
-            // State 4: If listeners are active, keep polling for events
-            if let Some(registry) = &self.listener_registry {
-                if registry.has_active_listeners() {
-                    // Small sleep to avoid busy-waiting
-                    std::thread::sleep(std::time::Duration::from_millis(10));
-                    // Yield control periodically to allow stop signal check
-                    // Return empty node ID to signal "waiting for events"
-                    return Some(Ok(String::new()));
-                }
+    fn next(&mut self) -> Option<Self::Item> {
+        // State 0: Start a wave for every event from self-emitting nodes
+        let events: Vec<events::ExecutionEvent> = self
+            .listener_registry
+            .as_mut()
+            .and_then(|registry| registry.event_receiver())
+            .map(|receiver| receiver.try_iter().collect())
+            .unwrap_or_default();
+        for event in events {
+            self.shared
+                .notify(|observer| observer.event_received(&event));
+            self.schedule_event(event);
+        }
+
+        // Report progress of the event waves running on the pool
+        if let Some(result) = self.receive_wave_result() {
+            return Some(result);
+        }
+
+        // States 1-3: Execute the wave started by `start`
+        if let Some(result) = self.main_wave.step(&self.shared) {
+            if matches!(&result, Ok(node_id) if node_id.is_empty()) {
+                // Only async nodes are running, wait a little for one of them
+                self.main_wave
+                    .wait_for_async(std::time::Duration::from_millis(10));
             }
+            return Some(result);
+        }
 
-            // No more work to do
-            return None;
+        // State 4: If listeners or event waves are active, keep polling
+        let listening = self
+            .listener_registry
+            .as_ref()
+            .is_some_and(|registry| registry.has_active_listeners());
+        if listening || !self.running_waves.is_empty() || !self.pending_events.is_empty() {
+            // Small sleep to avoid busy-waiting
+            std::thread::sleep(std::time::Duration::from_millis(10));
+            // Yield control periodically to allow stop signal check
+            // Return empty node ID to signal "waiting for events"
+            return Some(Ok(String::new()));
         }
+
+        // No more work to do
+        self.finish_run();
+        None
     }
 }
 
-impl<R: Runtime> Drop for Engine<R> {
+impl Drop for Engine {
     fn drop(&mut self) {
+        // Stopped before the run was done
+        self.finish_run();
+
         // Stop all listeners when engine is dropped
         if let Some(mut registry) = self.listener_registry.take() {
             let _ = registry.stop_all();
         }
+
+        self.stop_waves();
+
+        // Don't wait for async bricks still running
+        if let Some(runtime) = self.async_runtime.take() {
+            runtime.shutdown_background();
+        }
     }
 }
 
-#[derive(Default, Clone, serde::Serialize, serde::Deserialize, specta::Type)]
+#[derive(Debug, Default, Clone, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "tauri", derive(specta::Type))]
 pub struct NodeExecutionState {
     pub phase: ExecutionPhase,
     #[serde(rename = "errorMessage")]
@@ -664,19 +1009,22 @@ pub struct NodeExecutionState {
     #[serde(rename = "elapsedMs")]
     pub elapsed_ms: u32,
     pub outputs: Option<Vec<BrickOutputValue>>,
+    /// Attempt the node is on, counting from 1 (0 before it first ran)
+    pub attempt: u32,
+    /// Whether the latest attempt ran into the node's timeout
+    #[serde(rename = "timedOut")]
+    pub timed_out: bool,
 }
 
-#[derive(Clone, serde::Serialize, serde::Deserialize, specta::Type)]
+#[derive(Debug, Default, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "tauri", derive(specta::Type))]
 pub enum ExecutionPhase {
+    #[default]
     Waiting,
     Queued,
+    /// Held back by the debugger
+    Paused,
     Running,
     Completed,
     Errored,
 }
-
-impl Default for ExecutionPhase {
-    fn default() -> Self {
-        Self::Waiting
-    }
-}
@@ -0,0 +1,314 @@
+/// Observers get told what an engine is doing: node state changes, runs starting and
+/// finishing, messages logged by bricks and events received from listeners
+///
+/// Any number of observers can watch one engine (`Engine::add_observer`). They are called on
+/// whichever thread the change happens, so they should return quickly. The Tauri app
+/// broadcasts to the frontend through one (`api::TauriObserver`), the command line prints
+/// with `StdoutObserver`.
+use super::events::ExecutionEvent;
+use super::{ExecutionPhase, ExecutionStateUpdate};
+use crate::prelude::*;
+use std::collections::HashMap;
+use std::sync::mpsc::Sender;
+use std::sync::Mutex;
+
+pub trait ExecutionObserver: Send + Sync {
+    fn node_state_changed(&self, _update: &ExecutionStateUpdate) {}
+
+    fn run_started(&self) {}
+
+    /// `success` is false when any node errored during the run
+    fn run_finished(&self, _success: bool) {}
+
+    fn log(&self, _node_id: &str, _message: &str) {}
+
+    fn event_received(&self, _event: &ExecutionEvent) {}
+}
+
+/// Everything an observer can be told, as a value
+#[derive(Debug, Clone)]
+pub enum ExecutionNotification {
+    NodeStateChanged(ExecutionStateUpdate),
+    RunStarted,
+    RunFinished { success: bool },
+    Log { node_id: String, message: String },
+    EventReceived(ExecutionEvent),
+}
+
+/// Prints logged messages, and optionally node state changes, to stdout
+#[derive(Default)]
+pub struct StdoutObserver {
+    states: bool,
+    /// How nodes are called in the output, by id
+    names: HashMap<String, String>,
+}
+
+impl StdoutObserver {
+    /// Only print the messages bricks log
+    pub fn logs() -> Self {
+        Self::default()
+    }
+
+    /// Print node state changes as well, naming the nodes of the graph by their brick
+    pub fn verbose(graph: &Graph) -> Self {
+        Self {
+            states: true,
+            names: graph
+                .nodes
+                .iter()
+                .map(|node| (node.id.clone(), node_name(node)))
+                .collect(),
+        }
+    }
+
+    fn name<'a>(&'a self, node_id: &'a str) -> &'a str {
+        self.names.get(node_id).map_or(node_id, String::as_str)
+    }
+}
+
+/// Label of a node's brick with the start of its id, to tell nodes of the same brick apart
+pub fn node_name(node: &Node) -> String {
+    let label = node
+        .data
+        .brick
+        .as_ref()
+        .map_or(node.data.brick_id.as_str(), |brick| brick.label.as_str());
+    let short_id: String = node.id.chars().take(8).collect();
+    format!("{} ({})", label, short_id)
+}
+
+impl ExecutionObserver for StdoutObserver {
+    fn node_state_changed(&self, update: &ExecutionStateUpdate) {
+        if !self.states {
+            return;
+        }
+
+        let state = &update.state;
+        match state.phase {
+            ExecutionPhase::Waiting => {}
+            ExecutionPhase::Completed => {
+                println!(
+                    "completed {} in {} ms",
+                    self.name(&update.node_id),
+                    state.elapsed_ms
+                )
+            }
+            ExecutionPhase::Errored => println!(
+                "errored   {}: {}",
+                self.name(&update.node_id),
+                state.error_message.as_deref().unwrap_or("unknown error")
+            ),
+            ref phase => println!(
+                "{:<9} {}",
+                format!("{:?}", phase).to_lowercase(),
+                self.name(&update.node_id)
+            ),
+        }
+    }
+
+    fn run_finished(&self, success: bool) {
+        if self.states {
+            println!(
+                "finished {}",
+                if success {
+                    "successfully"
+                } else {
+                    "with errors"
+                }
+            );
+        }
+    }
+
+    fn log(&self, _node_id: &str, message: &str) {
+        println!("{}", message);
+    }
+
+    fn event_received(&self, event: &ExecutionEvent) {
+        if self.states {
+            println!("event     {:?}", event);
+        }
+    }
+}
+
+/// Sends every notification over a channel, to handle them on another thread
+pub struct ChannelObserver {
+    sender: Mutex<Sender<ExecutionNotification>>,
+}
+
+impl ChannelObserver {
+    pub fn new(sender: Sender<ExecutionNotification>) -> Self {
+        Self {
+            sender: Mutex::new(sender),
+        }
+    }
+
+    fn send(&self, notification: ExecutionNotification) {
+        // Nobody listening anymore is fine, the engine keeps running
+        let _ = self.sender.lock().unwrap().send(notification);
+    }
+}
+
+impl ExecutionObserver for ChannelObserver {
+    fn node_state_changed(&self, update: &ExecutionStateUpdate) {
+        self.send(ExecutionNotification::NodeStateChanged(update.clone()));
+    }
+
+    fn run_started(&self) {
+        self.send(ExecutionNotification::RunStarted);
+    }
+
+    fn run_finished(&self, success: bool) {
+        self.send(ExecutionNotification::RunFinished { success });
+    }
+
+    fn log(&self, node_id: &str, message: &str) {
+        self.send(ExecutionNotification::Log {
+            node_id: node_id.to_string(),
+            message: message.to_string(),
+        });
+    }
+
+    fn event_received(&self, event: &ExecutionEvent) {
+        self.send(ExecutionNotification::EventReceived(event.clone()));
+    }
+}
+
+/// Keeps every notification, to inspect them once the engine is done
+#[derive(Default)]
+pub struct RecordingObserver {
+    notifications: Mutex<Vec<ExecutionNotification>>,
+}
+
+impl RecordingObserver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Notifications received so far, oldest first
+    pub fn notifications(&self) -> Vec<ExecutionNotification> {
+        self.notifications.lock().unwrap().clone()
+    }
+
+    /// Phases a node went through, in order
+    pub fn phases(&self, node_id: &str) -> Vec<ExecutionPhase> {
+        self.notifications
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|notification| match notification {
+                ExecutionNotification::NodeStateChanged(update) if update.node_id == node_id => {
+                    Some(update.state.phase.clone())
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Messages logged by a node, in order
+    pub fn logs(&self, node_id: &str) -> Vec<String> {
+        self.notifications
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|notification| match notification {
+                ExecutionNotification::Log {
+                    node_id: id,
+                    message,
+                } if id == node_id => Some(message.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn record(&self, notification: ExecutionNotification) {
+        self.notifications.lock().unwrap().push(notification);
+    }
+}
+
+impl ExecutionObserver for RecordingObserver {
+    fn node_state_changed(&self, update: &ExecutionStateUpdate) {
+        self.record(ExecutionNotification::NodeStateChanged(update.clone()));
+    }
+
+    fn run_started(&self) {
+        self.record(ExecutionNotification::RunStarted);
+    }
+
+    fn run_finished(&self, success: bool) {
+        self.record(ExecutionNotification::RunFinished { success });
+    }
+
+    fn log(&self, node_id: &str, message: &str) {
+        self.record(ExecutionNotification::Log {
+            node_id: node_id.to_string(),
+            message: message.to_string(),
+        });
+    }
+
+    fn event_received(&self, event: &ExecutionEvent) {
+        self.record(ExecutionNotification::EventReceived(event.clone()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bricks::debug::print_brick;
+    use crate::canvas::new_node;
+    use crate::engine::Engine;
+    use std::sync::Arc;
+
+    fn print_graph() -> Graph {
+        let mut print = new_node(print_brick(), Point { x: 0.0, y: 0.0 });
+        print
+            .data
+            .defaults
+            .insert("value".to_string(), "\"hello\"".to_string());
+        Graph {
+            nodes: vec![print],
+            edges: vec![],
+        }
+    }
+
+    #[test]
+    fn test_observers_follow_the_run() {
+        let graph = print_graph();
+        let node_id = graph.nodes[0].id.clone();
+        let recording = Arc::new(RecordingObserver::new());
+        let (sender, receiver) = std::sync::mpsc::channel();
+
+        let mut engine = Engine::new(graph);
+        engine.add_observer(recording.clone());
+        engine.add_observer(Arc::new(ChannelObserver::new(sender)));
+        engine.start();
+        for result in &mut engine {
+            result.unwrap();
+        }
+
+        assert_eq!(
+            recording.phases(&node_id),
+            vec![
+                ExecutionPhase::Waiting,
+                ExecutionPhase::Queued,
+                ExecutionPhase::Running,
+                ExecutionPhase::Completed
+            ]
+        );
+        assert_eq!(recording.logs(&node_id), vec!["hello"]);
+
+        let notifications = recording.notifications();
+        assert!(matches!(
+            notifications.first(),
+            Some(ExecutionNotification::RunStarted)
+        ));
+        assert!(matches!(
+            notifications.last(),
+            Some(ExecutionNotification::RunFinished { success: true })
+        ));
+
+        // Every observer is told the same, and the run only finishes once
+        drop(engine);
+        assert_eq!(receiver.try_iter().count(), notifications.len());
+        assert_eq!(recording.notifications().len(), notifications.len());
+    }
+}
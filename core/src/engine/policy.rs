@@ -0,0 +1,176 @@
+use std::time::Duration;
+
+/// How often a node is attempted in total under `ErrorPolicy::Retry`
+pub const DEFAULT_RETRY_ATTEMPTS: u32 = 3;
+
+/// What happens when a node fails
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "tauri", derive(specta::Type))]
+#[serde(rename_all = "kebab-case")]
+pub enum ErrorPolicy {
+    /// Stop the run (wave) the node is part of, other runs carry on
+    #[default]
+    StopRun,
+    /// Mark the node as errored and carry on with the rest of the run
+    Continue,
+    /// Execute the node again, stopping the run once every attempt failed
+    Retry,
+}
+
+impl ErrorPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorPolicy::StopRun => "stop-run",
+            ErrorPolicy::Continue => "continue",
+            ErrorPolicy::Retry => "retry",
+        }
+    }
+}
+
+impl std::str::FromStr for ErrorPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "stop-run" | "stop" => Ok(ErrorPolicy::StopRun),
+            "continue" => Ok(ErrorPolicy::Continue),
+            "retry" => Ok(ErrorPolicy::Retry),
+            other => Err(format!("Unknown error policy '{}'", other)),
+        }
+    }
+}
+
+/// How long to wait before attempting a failed node again
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "tauri", derive(specta::Type))]
+#[serde(tag = "strategy", rename_all = "kebab-case")]
+pub enum Backoff {
+    /// Retry right away
+    #[default]
+    None,
+    /// Wait the same time before every retry
+    Fixed {
+        #[serde(rename = "delayMs")]
+        delay_ms: u32,
+    },
+    /// Double the wait after every retry, up to `max_ms` when set
+    Exponential {
+        #[serde(rename = "initialMs")]
+        initial_ms: u32,
+        #[serde(default, rename = "maxMs")]
+        max_ms: Option<u32>,
+    },
+}
+
+impl Backoff {
+    /// Time to wait before the given retry (1 for the second attempt)
+    pub fn delay(&self, retry: u32) -> Duration {
+        let ms = match *self {
+            Backoff::None => 0,
+            Backoff::Fixed { delay_ms } => delay_ms,
+            Backoff::Exponential { initial_ms, max_ms } => {
+                let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+                let delay = initial_ms.saturating_mul(factor);
+                max_ms.map_or(delay, |max| delay.min(max))
+            }
+        };
+        Duration::from_millis(ms as u64)
+    }
+}
+
+/// Per-node execution policy, stored with the node in the graph file
+///
+/// Decides what the engine does when a node's brick fails or runs too long. A failing
+/// fallible flow brick whose `error` execution output is connected continues on that
+/// branch once the policy has no attempts left.
+#[derive(Debug, Default, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "tauri", derive(specta::Type))]
+pub struct NodePolicy {
+    #[serde(default, rename = "onError")]
+    pub on_error: ErrorPolicy,
+    /// Attempts in total before the failure is final, overrides the default of `on_error`
+    #[serde(default, rename = "maxAttempts")]
+    pub max_attempts: Option<u32>,
+    #[serde(default)]
+    pub backoff: Backoff,
+    /// Fail an attempt that runs longer than this
+    #[serde(default, rename = "timeoutMs")]
+    pub timeout_ms: Option<u32>,
+}
+
+impl NodePolicy {
+    /// How often the node may be attempted before its failure is final
+    pub fn attempt_limit(&self) -> u32 {
+        match (self.max_attempts, self.on_error) {
+            (Some(attempts), _) => attempts.max(1),
+            (None, ErrorPolicy::Retry) => DEFAULT_RETRY_ATTEMPTS,
+            (None, _) => 1,
+        }
+    }
+
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout_ms.map(|ms| Duration::from_millis(ms as u64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_error_policy() {
+        assert_eq!("stop-run".parse(), Ok(ErrorPolicy::StopRun));
+        assert_eq!("Continue".parse(), Ok(ErrorPolicy::Continue));
+        assert!("ignore".parse::<ErrorPolicy>().is_err());
+
+        for policy in [
+            ErrorPolicy::StopRun,
+            ErrorPolicy::Continue,
+            ErrorPolicy::Retry,
+        ] {
+            assert_eq!(policy.as_str().parse(), Ok(policy));
+        }
+    }
+
+    #[test]
+    fn test_policy_serialization() {
+        let policy: NodePolicy = serde_json::from_str(r#"{"onError":"retry"}"#).unwrap();
+        assert_eq!(policy.on_error, ErrorPolicy::Retry);
+        assert_eq!(policy.attempt_limit(), DEFAULT_RETRY_ATTEMPTS);
+
+        // Graph files written before policies existed
+        let policy: NodePolicy = serde_json::from_str("{}").unwrap();
+        assert_eq!(policy, NodePolicy::default());
+        assert_eq!(policy.attempt_limit(), 1);
+
+        let policy: NodePolicy = serde_json::from_str(
+            r#"{
+                "onError": "continue",
+                "maxAttempts": 4,
+                "backoff": { "strategy": "fixed", "delayMs": 250 },
+                "timeoutMs": 1000
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(policy.attempt_limit(), 4);
+        assert_eq!(policy.backoff, Backoff::Fixed { delay_ms: 250 });
+        assert_eq!(policy.timeout(), Some(Duration::from_millis(1000)));
+    }
+
+    #[test]
+    fn test_backoff_delays() {
+        assert_eq!(Backoff::None.delay(3), Duration::ZERO);
+        assert_eq!(
+            Backoff::Fixed { delay_ms: 100 }.delay(3),
+            Duration::from_millis(100)
+        );
+
+        let exponential = Backoff::Exponential {
+            initial_ms: 100,
+            max_ms: Some(300),
+        };
+        assert_eq!(exponential.delay(1), Duration::from_millis(100));
+        assert_eq!(exponential.delay(2), Duration::from_millis(200));
+        assert_eq!(exponential.delay(3), Duration::from_millis(300));
+    }
+}
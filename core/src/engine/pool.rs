@@ -0,0 +1,144 @@
+/// Worker pool that runs event waves concurrently
+///
+/// The engine decides which waves may start (concurrency limit and per-trigger policy),
+/// the pool only runs them. Workers report every executed node back through a channel
+/// so the engine can keep yielding results as an iterator.
+use super::wave::Wave;
+use super::EngineShared;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+
+/// How many event waves may run at the same time by default
+pub const DEFAULT_MAX_CONCURRENT_WAVES: usize = 4;
+
+/// What happens when a trigger fires while a wave it started is still running
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ConcurrencyPolicy {
+    /// Start the new wave once the running one has finished
+    #[default]
+    Queue,
+    /// Ignore the new event
+    Drop,
+    /// Cancel the running wave and start the new one right away
+    Replace,
+}
+
+impl ConcurrencyPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConcurrencyPolicy::Queue => "queue",
+            ConcurrencyPolicy::Drop => "drop",
+            ConcurrencyPolicy::Replace => "replace",
+        }
+    }
+}
+
+impl std::str::FromStr for ConcurrencyPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "queue" => Ok(ConcurrencyPolicy::Queue),
+            "drop" => Ok(ConcurrencyPolicy::Drop),
+            "replace" | "replace-running" => Ok(ConcurrencyPolicy::Replace),
+            other => Err(format!("Unknown concurrency policy '{}'", other)),
+        }
+    }
+}
+
+/// Progress reported by a wave running on the pool
+pub enum WaveMessage {
+    /// A node of the wave executed (or failed)
+    Step {
+        wave_id: u64,
+        result: Result<String, String>,
+    },
+    /// The wave has nothing left to do, or was cancelled
+    Finished { wave_id: u64 },
+}
+
+pub struct WavePool {
+    jobs: Option<Sender<Wave>>,
+    workers: Vec<std::thread::JoinHandle<()>>,
+}
+
+impl WavePool {
+    /// Spawn `size` workers that run submitted waves against the shared graph
+    pub fn new(size: usize, shared: Arc<EngineShared>, messages: Sender<WaveMessage>) -> Self {
+        let (jobs, receiver) = mpsc::channel::<Wave>();
+        let receiver = Arc::new(Mutex::new(receiver));
+
+        let workers = (0..size.max(1))
+            .map(|_| {
+                let receiver = Arc::clone(&receiver);
+                let shared = Arc::clone(&shared);
+                let messages = messages.clone();
+                std::thread::spawn(move || worker_loop(&receiver, &shared, &messages))
+            })
+            .collect();
+
+        Self {
+            jobs: Some(jobs),
+            workers,
+        }
+    }
+
+    /// Hand a wave to the next free worker
+    pub fn submit(&self, wave: Wave) {
+        if let Some(jobs) = &self.jobs {
+            let _ = jobs.send(wave);
+        }
+    }
+}
+
+fn worker_loop(
+    receiver: &Mutex<Receiver<Wave>>,
+    shared: &EngineShared,
+    messages: &Sender<WaveMessage>,
+) {
+    loop {
+        // Only hold the lock while waiting, so other workers can pick up jobs
+        let job = receiver.lock().unwrap().recv();
+        let Ok(wave) = job else {
+            // The pool was dropped
+            return;
+        };
+
+        let wave_id = wave.id;
+        wave.run(shared, |result| {
+            let _ = messages.send(WaveMessage::Step { wave_id, result });
+        });
+        let _ = messages.send(WaveMessage::Finished { wave_id });
+    }
+}
+
+impl Drop for WavePool {
+    fn drop(&mut self) {
+        // Closing the job channel lets idle workers exit, busy ones finish their wave first
+        self.jobs.take();
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_concurrency_policy() {
+        assert_eq!("queue".parse(), Ok(ConcurrencyPolicy::Queue));
+        assert_eq!("Drop".parse(), Ok(ConcurrencyPolicy::Drop));
+        assert_eq!("replace-running".parse(), Ok(ConcurrencyPolicy::Replace));
+        assert!("sometimes".parse::<ConcurrencyPolicy>().is_err());
+
+        for policy in [
+            ConcurrencyPolicy::Queue,
+            ConcurrencyPolicy::Drop,
+            ConcurrencyPolicy::Replace,
+        ] {
+            assert_eq!(policy.as_str().parse(), Ok(policy));
+        }
+    }
+}
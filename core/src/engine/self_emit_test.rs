@@ -1,48 +1,99 @@
 /// Integration tests for self-emitting nodes
 #[cfg(test)]
 mod tests {
+    #[cfg(feature = "fs")]
+    use crate::bricks::events::file_watch_brick;
     use crate::bricks::events::{manual_trigger_brick, timer_brick};
+    #[cfg(feature = "net")]
+    use crate::bricks::events::{respond_http_brick, webhook_brick};
+    use crate::bricks::macros::brick;
     use crate::engine::events::ExecutionEvent;
+    #[cfg(feature = "fs")]
+    use crate::engine::events::FileEventType;
+    #[cfg(feature = "net")]
+    use crate::engine::events::HttpRequestData;
     use crate::engine::listeners::{
         manual::ManualTriggerListener, timer::TimerListener, EventListener,
     };
     use crate::engine::trigger;
+    use crate::engine::Engine;
     use crate::prelude::*;
-    use std::collections::BTreeMap;
-    use std::time::Duration;
-
-    /// Create a test node with a self-emitting brick
-    fn create_timer_node(id: &str, interval_ms: u64) -> Node {
-        let mut arguments = BTreeMap::new();
-        arguments.insert("interval_ms".to_string(), interval_ms.to_string());
-
-        Node {
-            id: id.to_string(),
-            position: Point { x: 0.0, y: 0.0 },
-            data: NodeData {
-                brick_id: "timer".to_string(),
-                brick: Some(timer_brick()),
-                arguments,
-                defaults: BTreeMap::new(),
-            },
-            r#type: "v1".to_string(),
+    use crate::test_utils::{edge, node, with_arguments};
+    use std::time::{Duration, Instant};
+
+    brick! {
+        #[id("slow_step")]
+        #[category("Debug")]
+        #[execution_input("execute")]
+        #[execution_output("done")]
+        fn slow_step(#[context] ctx: &BrickContext) -> (#[label("Done")] bool) {
+            std::thread::sleep(Duration::from_millis(200));
+            ctx.trigger("done");
+            (true,)
+        }
+    }
+
+    brick! {
+        #[id("mark")]
+        #[category("Debug")]
+        #[execution_input("execute")]
+        fn mark() -> (#[label("Done")] bool) {
+            (true,)
+        }
+    }
+
+    /// Graph with a manual trigger per entry, each running `trigger → slow_step → mark`
+    fn slow_trigger_graph(triggers: &[(&str, &str)]) -> Graph {
+        let mut graph = Graph {
+            nodes: vec![],
+            edges: vec![],
+        };
+
+        for (id, policy) in triggers {
+            let slow = format!("{}_slow", id);
+            let after = format!("{}_after", id);
+            graph.nodes.push(with_arguments(
+                node(id, manual_trigger_brick()),
+                &[("concurrency", policy)],
+            ));
+            graph.nodes.push(node(&slow, slow_step_brick()));
+            graph.nodes.push(node(&after, mark_brick()));
+            graph.edges.push(edge(id, "triggered", &slow, "execute"));
+            graph.edges.push(edge(&slow, "done", &after, "execute"));
         }
+
+        graph
     }
 
-    fn create_manual_trigger_node(id: &str) -> Node {
-        Node {
-            id: id.to_string(),
-            position: Point { x: 0.0, y: 0.0 },
-            data: NodeData {
-                brick_id: "manual_trigger".to_string(),
-                brick: Some(manual_trigger_brick()),
-                arguments: BTreeMap::new(),
-                defaults: BTreeMap::new(),
-            },
-            r#type: "v1".to_string(),
+    fn manual_event(node_id: &str) -> ExecutionEvent {
+        ExecutionEvent::ManualTrigger {
+            node_id: node_id.to_string(),
+            timestamp: "0".to_string(),
         }
     }
 
+    /// Iterate the engine until `until` returns true for the executed nodes, or the time is up
+    fn run_engine(
+        engine: &mut Engine,
+        executed: &mut Vec<String>,
+        timeout: Duration,
+        until: impl Fn(&[String]) -> bool,
+    ) {
+        let deadline = Instant::now() + timeout;
+        while Instant::now() < deadline && !until(executed) {
+            match engine.next() {
+                Some(Ok(node_id)) if !node_id.is_empty() => executed.push(node_id),
+                Some(Ok(_)) => {}
+                Some(Err(e)) => panic!("Execution failed: {}", e),
+                None => break,
+            }
+        }
+    }
+
+    fn count(executed: &[String], node_id: &str) -> usize {
+        executed.iter().filter(|n| *n == node_id).count()
+    }
+
     #[test]
     fn test_timer_listener_emits_events() {
         let mut listener = TimerListener::new("timer_node".to_string(), 50);
@@ -69,7 +120,7 @@ mod tests {
         }
 
         assert!(
-            event_count >= 2 && event_count <= 4,
+            (2..=4).contains(&event_count),
             "Expected 2-4 events, got {}",
             event_count
         );
@@ -112,23 +163,20 @@ mod tests {
             timestamp: "1234567890.123".to_string(),
         };
 
-        trigger::set_execution_context(context);
-
         // Execute the timer brick
         let brick = timer_brick();
         let args = vec![];
         let inputs = vec![];
 
-        let outputs = (brick.execution)(args, inputs);
+        let ctx = BrickContext::new("event").with_event(context);
+        let outputs = (brick.execution)(&ctx, args, inputs).unwrap();
 
         // Check outputs
         assert_eq!(outputs.len(), 2);
         assert_eq!(outputs[0].id, "output_0"); // tick_count
-        assert_eq!(outputs[0].value, "\"5\"");
+        assert_eq!(outputs[0].value, Value::from("5"));
         assert_eq!(outputs[1].id, "output_1"); // timestamp
-        assert_eq!(outputs[1].value, "\"1234567890.123\"");
-
-        trigger::clear_execution_context();
+        assert_eq!(outputs[1].value, Value::from("1234567890.123"));
     }
 
     #[test]
@@ -138,28 +186,351 @@ mod tests {
             timestamp: "1234567890.456".to_string(),
         };
 
-        trigger::set_execution_context(context);
-
         // Execute the manual trigger brick
         let brick = manual_trigger_brick();
         let args = vec![];
         let inputs = vec![];
 
-        let outputs = (brick.execution)(args, inputs);
+        let ctx = BrickContext::new("event").with_event(context);
+        let outputs = (brick.execution)(&ctx, args, inputs).unwrap();
 
         // Check outputs
         assert_eq!(outputs.len(), 1);
         assert_eq!(outputs[0].id, "output_0"); // timestamp
-        assert_eq!(outputs[0].value, "\"1234567890.456\"");
+        assert_eq!(outputs[0].value, Value::from("1234567890.456"));
+    }
+
+    #[cfg(feature = "net")]
+    #[test]
+    fn test_webhook_brick_execution_with_context() {
+        // Setup execution context with HTTP request data
+        let context = trigger::ExecutionContext::HttpRequest(HttpRequestData {
+            method: "POST".to_string(),
+            path: "/deploy".to_string(),
+            body: "payload".to_string(),
+            headers: r#"{"x-token":"abc"}"#.to_string(),
+            query: r#"{"env":"prod"}"#.to_string(),
+            request_id: String::new(),
+        });
+
+        // Execute the webhook brick
+        let brick = webhook_brick();
+        let ctx = BrickContext::new("event").with_event(context);
+        let outputs = (brick.execution)(&ctx, vec![], vec![]).unwrap();
+
+        // Check outputs
+        assert_eq!(outputs.len(), 5);
+        assert_eq!(outputs[0].value, Value::from("POST"));
+        assert_eq!(outputs[1].value, Value::from("/deploy"));
+        assert_eq!(outputs[2].value, Value::from("payload"));
+        assert_eq!(outputs[4].id, "output_4"); // query
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn test_file_watch_brick_execution_with_context() {
+        // Setup execution context with file change data
+        let context = trigger::ExecutionContext::FileChanged {
+            path: "/tmp/exports/report.csv".to_string(),
+            event_type: FileEventType::Modified,
+        };
+
+        let brick = file_watch_brick();
+        let ctx = BrickContext::new("event").with_event(context);
+        let outputs = (brick.execution)(&ctx, vec![], vec![]).unwrap();
+
+        assert_eq!(outputs.len(), 2);
+        assert_eq!(outputs[0].value, Value::from("/tmp/exports/report.csv"));
+        assert_eq!(outputs[1].value, Value::from("modified"));
+
+        let triggers = ctx.take_triggers();
+        assert_eq!(triggers.len(), 1);
+        assert_eq!(triggers[0].output_id, "changed");
+    }
+
+    #[cfg(feature = "net")]
+    #[test]
+    fn test_engine_runs_webhook_node_on_request() {
+        use std::io::{Read, Write};
+
+        // Reserve a free port for the webhook server
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+
+        let graph = Graph {
+            nodes: vec![with_arguments(
+                node("hook", webhook_brick()),
+                &[
+                    ("path", "/run"),
+                    ("method", "\"PUT\""),
+                    ("port", &port.to_string()),
+                ],
+            )],
+            edges: vec![],
+        };
+
+        let mut engine = Engine::new(graph);
+        engine.start();
+
+        let mut stream = std::net::TcpStream::connect(("127.0.0.1", port)).unwrap();
+        stream
+            .write_all(b"PUT /run?x=1 HTTP/1.1\r\nHost: localhost\r\nContent-Length: 5\r\nConnection: close\r\n\r\nhello")
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 202"), "{}", response);
+
+        // Webhook nodes only execute when a request arrives
+        let executed = (&mut engine)
+            .take(200)
+            .filter_map(|result| result.ok())
+            .find(|node_id| node_id == "hook");
+        assert_eq!(executed.as_deref(), Some("hook"));
+
+        let outputs = engine.node_state("hook").unwrap().outputs.unwrap();
+        assert_eq!(outputs[0].value, Value::from("PUT"));
+        assert_eq!(outputs[1].value, Value::from("/run"));
+        assert_eq!(outputs[2].value, Value::from("hello"));
+        assert_eq!(outputs[4].value, Value::from(r#"{"x":"1"}"#));
+    }
+
+    #[cfg(feature = "net")]
+    #[test]
+    fn test_engine_answers_webhook_with_respond_http() {
+        use crate::test_utils::with_defaults;
+        use std::io::{Read, Write};
+
+        // Reserve a free port for the webhook server
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+
+        let graph = Graph {
+            nodes: vec![
+                with_arguments(
+                    node("hook", webhook_brick()),
+                    &[
+                        ("path", "/ping"),
+                        ("method", "GET"),
+                        ("port", &port.to_string()),
+                    ],
+                ),
+                with_defaults(
+                    node("respond", respond_http_brick()),
+                    &[("status", "201"), ("body", "\"pong\"")],
+                ),
+            ],
+            edges: vec![edge("hook", "received", "respond", "execute")],
+        };
+
+        let mut engine = Engine::new(graph);
+        engine.start();
+
+        // The connection stays open until the flow responds, so send from another thread
+        let client = std::thread::spawn(move || {
+            let mut stream = std::net::TcpStream::connect(("127.0.0.1", port)).unwrap();
+            stream
+                .write_all(b"GET /ping HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+                .unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        });
+
+        let executed = (&mut engine)
+            .take(500)
+            .filter_map(|result| result.ok())
+            .find(|node_id| node_id == "respond");
+        assert_eq!(executed.as_deref(), Some("respond"));
+        assert_eq!(
+            engine.node_state("respond").unwrap().outputs.unwrap()[0].value,
+            Value::Bool(true)
+        );
+
+        let response = client.join().unwrap();
+        assert!(response.starts_with("HTTP/1.1 201"), "{}", response);
+        assert!(response.ends_with("pong"), "{}", response);
+    }
+
+    #[test]
+    fn test_each_event_starts_a_new_wave() {
+        use crate::bricks::constants::number_constant_brick;
+        use crate::bricks::debug::print_brick;
+
+        brick! {
+            #[id("event_label")]
+            #[category("Debug")]
+            fn event_label(
+                #[input] timestamp: String,
+                #[input] scale: f32
+            ) -> (
+                #[label("Label")] String
+            ) {
+                (format!("{} x{}", timestamp, scale),)
+            }
+        }
+
+        // manual trigger → print(event_label(timestamp, constant))
+        let graph = Graph {
+            nodes: vec![
+                node("trigger1", manual_trigger_brick()),
+                with_arguments(node("scale", number_constant_brick()), &[("value", "2")]),
+                node("label", event_label_brick()),
+                node("print", print_brick()),
+            ],
+            edges: vec![
+                edge("trigger1", "triggered", "print", "execute"),
+                edge("trigger1", "output_0", "label", "timestamp"),
+                edge("scale", "output_0", "label", "scale"),
+                edge("label", "output_0", "print", "value"),
+            ],
+        };
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let mut engine = Engine::new(graph);
+        engine.start_with_event_channel(receiver, sender.clone());
+
+        // The constant has no incoming edges, so the start wave computes it first
+        let mut executed = vec![engine.next().unwrap().unwrap()];
+        assert_eq!(executed, vec!["scale"]);
+
+        for timestamp in ["1", "2"] {
+            sender
+                .send(ExecutionEvent::ManualTrigger {
+                    node_id: "trigger1".to_string(),
+                    timestamp: timestamp.to_string(),
+                })
+                .unwrap();
+        }
+
+        let mut labels = Vec::new();
+        for _ in 0..500 {
+            let node_id = engine.next().unwrap().unwrap();
+            if node_id == "print" {
+                // Every wave sees the label computed from its own event
+                let label = engine.node_state("label").unwrap().outputs.unwrap();
+                labels.push(label[0].value.to_string());
+            }
+            executed.push(node_id);
+            if labels.len() == 2 {
+                break;
+            }
+        }
+
+        assert_eq!(labels, vec!["1 x2", "2 x2"]);
+
+        // The constant is shared, the label is recomputed for every wave
+        let count = |id: &str| executed.iter().filter(|n| *n == id).count();
+        assert_eq!(count("scale"), 1);
+        assert_eq!(count("label"), 2);
+    }
+
+    #[test]
+    fn test_slow_wave_does_not_block_other_triggers() {
+        let graph = slow_trigger_graph(&[("a", "queue"), ("b", "queue")]);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let mut engine = Engine::new(graph);
+        engine.start_with_event_channel(receiver, sender.clone());
+
+        sender.send(manual_event("a")).unwrap();
+        let mut executed = Vec::new();
+        run_engine(&mut engine, &mut executed, Duration::from_secs(5), |done| {
+            done.contains(&"a".to_string())
+        });
+
+        // "a" is busy in its slow step while "b" fires
+        sender.send(manual_event("b")).unwrap();
+        run_engine(&mut engine, &mut executed, Duration::from_secs(5), |done| {
+            count(done, "a_after") == 1 && count(done, "b_after") == 1
+        });
+
+        let position = |id: &str| executed.iter().position(|n| n == id).unwrap();
+        assert!(position("b") < position("a_slow"), "{:?}", executed);
+    }
+
+    #[test]
+    fn test_concurrency_limit_runs_waves_one_by_one() {
+        let graph = slow_trigger_graph(&[("a", "queue"), ("b", "queue")]);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let mut engine = Engine::new(graph);
+        engine.set_max_concurrent_waves(1);
+        engine.start_with_event_channel(receiver, sender.clone());
+
+        sender.send(manual_event("a")).unwrap();
+        sender.send(manual_event("b")).unwrap();
+
+        let mut executed = Vec::new();
+        run_engine(&mut engine, &mut executed, Duration::from_secs(5), |done| {
+            count(done, "b_after") == 1
+        });
+
+        assert_eq!(
+            executed,
+            vec!["a", "a_slow", "a_after", "b", "b_slow", "b_after"]
+        );
+    }
+
+    /// Fire a trigger twice, the second time while its first wave is in its slow step
+    fn fire_twice(policy: &str) -> Vec<String> {
+        let graph = slow_trigger_graph(&[("a", policy)]);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let mut engine = Engine::new(graph);
+        engine.start_with_event_channel(receiver, sender.clone());
+
+        sender.send(manual_event("a")).unwrap();
+        let mut executed = Vec::new();
+        run_engine(&mut engine, &mut executed, Duration::from_secs(5), |done| {
+            done.contains(&"a".to_string())
+        });
+
+        sender.send(manual_event("a")).unwrap();
+        run_engine(
+            &mut engine,
+            &mut executed,
+            Duration::from_millis(1000),
+            |_| false,
+        );
+
+        executed
+    }
+
+    #[test]
+    fn test_queue_policy_runs_events_in_order() {
+        let executed = fire_twice("queue");
+        assert_eq!(
+            executed,
+            vec!["a", "a_slow", "a_after", "a", "a_slow", "a_after"]
+        );
+    }
+
+    #[test]
+    fn test_drop_policy_ignores_events_while_busy() {
+        let executed = fire_twice("drop");
+        assert_eq!(executed, vec!["a", "a_slow", "a_after"]);
+    }
+
+    #[test]
+    fn test_replace_policy_cancels_running_wave() {
+        let executed = fire_twice("replace");
 
-        trigger::clear_execution_context();
+        // The first wave stops after its slow step, only the second one finishes
+        assert_eq!(count(&executed, "a"), 2);
+        assert_eq!(count(&executed, "a_after"), 1);
     }
 
     #[test]
     fn test_graph_with_timer_node() {
         // Create a simple graph with just a timer node
         let graph = Graph {
-            nodes: vec![create_timer_node("timer1", 100)],
+            nodes: vec![with_arguments(
+                node("timer1", timer_brick()),
+                &[("interval_ms", "100")],
+            )],
             edges: vec![],
         };
 
@@ -1,7 +1,7 @@
 use std::collections::BTreeMap;
 
 use crate::bricks::macros::brick;
-use crate::bricks::types::BrickInputValue;
+use crate::bricks::types::{BrickInputValue, FIRST_VERSION};
 use crate::engine::Engine;
 use crate::prelude::*;
 
@@ -33,6 +33,9 @@ fn sample_graph() -> Graph {
                 map.insert("b".to_string(), "false".to_string());
                 map
             }),
+            policy: Default::default(),
+            breakpoint: None,
+            brick_version: FIRST_VERSION,
         },
         r#type: "v1".to_string(),
     };
@@ -47,7 +50,7 @@ fn sample_graph() -> Graph {
 fn test_engine_execution() {
     let graph = sample_graph();
 
-    let mut engine = Engine::new_test(graph);
+    let mut engine = Engine::new(graph);
     engine.start();
 
     for result in engine {
@@ -55,36 +58,71 @@ fn test_engine_execution() {
     }
 }
 
+#[test]
+fn test_engine_rejects_invalid_default() {
+    let mut graph = sample_graph();
+    graph.nodes[0]
+        .data
+        .defaults
+        .insert("b".to_string(), "not a bool".to_string());
+
+    let mut engine = Engine::new(graph);
+    engine.start();
+
+    // Bad data errors out instead of being coerced to false
+    let error = engine
+        .find_map(|result| result.err())
+        .expect("Execution should fail");
+    assert!(error.contains("Invalid default for input 'b'"), "{}", error);
+}
+
+#[test]
+fn test_execution_rejects_mismatched_input() {
+    let inputs = vec![BrickInputValue {
+        id: "a".to_string(),
+        value: Value::from("five"),
+    }];
+
+    let brick = crate::bricks::arithmetics::add_brick();
+    let error = (brick.execution)(&BrickContext::default(), vec![], inputs).unwrap_err();
+    assert!(matches!(error, BrickError::InvalidInput(_)));
+    assert!(
+        error.to_string().contains("Invalid value for 'a'"),
+        "{}",
+        error
+    );
+}
+
 #[test]
 fn test_json_execution() {
-    // Test the add brick execution with JSON values
+    // Test the add brick execution with typed values
     let args = vec![]; // Use inputs instead of arguments for this test
     let inputs = vec![
         BrickInputValue {
             id: "a".to_string(),
-            value: "5.0".to_string(), // JSON encoded f32
+            value: Value::Number(5.0),
         },
         BrickInputValue {
             id: "b".to_string(),
-            value: "3.0".to_string(), // JSON encoded f32
+            value: Value::Number(3.0),
         },
     ];
 
     // Call the execution function directly
     let brick = crate::bricks::arithmetics::add_brick();
-    let outputs = (brick.execution)(args, inputs);
+    let outputs = (brick.execution)(&BrickContext::default(), args, inputs).unwrap();
 
     println!("Outputs: {:?}", outputs);
 
     // Verify we got one output
     assert_eq!(outputs.len(), 1);
 
-    // Check the output value is JSON encoded
+    // Check the output value is a number
     let result_output = &outputs[0];
     assert_eq!(result_output.id, "output_0");
 
-    // Parse the JSON value back to verify it's the expected result
-    let result_value: f32 = serde_json::from_str(&result_output.value).unwrap();
+    // Convert the value back to verify it's the expected result
+    let result_value = f32::try_from(result_output.value.clone()).unwrap();
     assert_eq!(result_value, 8.0); // 5.0 + 3.0 = 8.0
 }
 
@@ -95,7 +133,7 @@ fn test_json_execution_with_default_values() {
     let inputs = vec![]; // No inputs provided
 
     let brick = crate::bricks::arithmetics::add_brick();
-    let outputs = (brick.execution)(args, inputs);
+    let outputs = (brick.execution)(&BrickContext::default(), args, inputs).unwrap();
 
     println!("Outputs with defaults: {:?}", outputs);
 
@@ -104,6 +142,6 @@ fn test_json_execution_with_default_values() {
 
     // Check the output value
     let result_output = &outputs[0];
-    let result_value: f32 = serde_json::from_str(&result_output.value).unwrap();
+    let result_value = f32::try_from(result_output.value.clone()).unwrap();
     assert_eq!(result_value, 2.0); // 1.0 + 1.0 = 2.0 (default values)
 }
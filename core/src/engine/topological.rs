@@ -1,19 +1,77 @@
-use std::collections::HashMap;
+use crate::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 
-pub fn sort(nodes: Vec<String>, connections: Vec<(String, String)>) -> Vec<String> {
+/// A closed path through a graph, the last node connects back to the first
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "tauri", derive(specta::Type))]
+pub struct Cycle {
+    /// Nodes along the cycle, in the direction of its connections
+    pub nodes: Vec<String>,
+    /// Ids of the edges along the cycle, starting with the one leaving the first node
+    ///
+    /// Empty when the cycle was found in plain connections rather than edges.
+    pub edges: Vec<String>,
+}
+
+impl fmt::Display for Cycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for node in &self.nodes {
+            write!(f, "{} → ", node)?;
+        }
+        write!(f, "{}", self.nodes.first().map_or("", String::as_str))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SortError {
+    /// The connections form a cycle, so there is no order
+    Cycle(Cycle),
+    /// A connection references a node that isn't in the list
+    UnknownNode(String),
+    /// The node is in the list more than once
+    DuplicateNode(String),
+}
+
+impl fmt::Display for SortError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SortError::Cycle(cycle) => write!(f, "Connections form a cycle: {}", cycle),
+            SortError::UnknownNode(node) => {
+                write!(f, "Connection references unknown node '{}'", node)
+            }
+            SortError::DuplicateNode(node) => write!(f, "Node '{}' is listed twice", node),
+        }
+    }
+}
+
+pub fn sort(
+    nodes: Vec<String>,
+    connections: Vec<(String, String)>,
+) -> Result<Vec<String>, SortError> {
     let mut graph: HashMap<String, Vec<String>> = HashMap::new();
     let mut in_degree: HashMap<String, usize> = HashMap::new();
 
     for node in &nodes {
-        graph.insert(node.clone(), vec![]);
+        if graph.insert(node.clone(), vec![]).is_some() {
+            return Err(SortError::DuplicateNode(node.clone()));
+        }
         in_degree.insert(node.clone(), 0);
     }
 
-    for (src, dst) in connections {
-        if let Some(neighbors) = graph.get_mut(&src) {
+    for (src, dst) in &connections {
+        for node in [src, dst] {
+            if !graph.contains_key(node) {
+                return Err(SortError::UnknownNode(node.clone()));
+            }
+        }
+    }
+
+    for (src, dst) in &connections {
+        if let Some(neighbors) = graph.get_mut(src) {
             neighbors.push(dst.clone());
         }
-        *in_degree.entry(dst).or_insert(0) += 1;
+        *in_degree.entry(dst.clone()).or_insert(0) += 1;
     }
 
     let mut queue: Vec<String> = in_degree
@@ -38,46 +96,151 @@ pub fn sort(nodes: Vec<String>, connections: Vec<(String, String)>) -> Vec<Strin
     }
 
     if sorted.len() != nodes.len() {
-        panic!("Topological sort failed: the graph may contain a cycle or reference missing nodes");
+        let connections = connections
+            .iter()
+            .map(|(src, dst)| (src.as_str(), dst.as_str(), None));
+        let cycle = find_path(connections).expect("nodes left unsorted are part of a cycle");
+        return Err(SortError::Cycle(cycle));
     }
 
-    sorted
+    Ok(sorted)
+}
+
+/// Find a cycle formed by the given edges, if there is one
+pub fn find_cycle<'a>(edges: impl IntoIterator<Item = &'a Edge>) -> Option<Cycle> {
+    find_path(edges.into_iter().map(|edge| {
+        (
+            edge.source.as_str(),
+            edge.target.as_str(),
+            Some(edge.id.as_str()),
+        )
+    }))
+}
+
+/// Depth-first search for a connection back to a node on the current path
+fn find_path<'a>(
+    connections: impl IntoIterator<Item = (&'a str, &'a str, Option<&'a str>)>,
+) -> Option<Cycle> {
+    let mut order: Vec<&str> = Vec::new();
+    let mut neighbors: HashMap<&str, Vec<(&str, Option<&str>)>> = HashMap::new();
+    for (src, dst, edge) in connections {
+        if !neighbors.contains_key(src) {
+            order.push(src);
+        }
+        neighbors.entry(src).or_default().push((dst, edge));
+    }
+
+    let mut visited = HashSet::new();
+    let mut path = Vec::new();
+    order
+        .into_iter()
+        .find_map(|node| visit(node, None, &neighbors, &mut visited, &mut path))
+}
+
+/// Visit a node reached through an edge, `path` holds the nodes (and the edges leading to them)
+/// currently being visited
+fn visit<'a>(
+    node: &'a str,
+    via: Option<&'a str>,
+    neighbors: &HashMap<&'a str, Vec<(&'a str, Option<&'a str>)>>,
+    visited: &mut HashSet<&'a str>,
+    path: &mut Vec<(&'a str, Option<&'a str>)>,
+) -> Option<Cycle> {
+    if let Some(start) = path.iter().position(|(on_path, _)| *on_path == node) {
+        let cycle = &path[start..];
+        return Some(Cycle {
+            nodes: cycle.iter().map(|(node, _)| node.to_string()).collect(),
+            edges: cycle[1..]
+                .iter()
+                .map(|(_, edge)| *edge)
+                .chain([via])
+                .flatten()
+                .map(str::to_string)
+                .collect(),
+        });
+    }
+    if !visited.insert(node) {
+        return None;
+    }
+
+    path.push((node, via));
+    for (next, edge) in neighbors.get(node).into_iter().flatten() {
+        if let Some(cycle) = visit(next, *edge, neighbors, visited, path) {
+            return Some(cycle);
+        }
+    }
+    path.pop();
+    None
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_utils::edge;
 
     #[test]
     fn test_topological_sort() {
         let nodes = vec!["a".into(), "b".into(), "c".into()];
         let connections = vec![("a".into(), "b".into()), ("b".into(), "c".into())];
 
-        let sorted = sort(nodes, connections);
+        let sorted = sort(nodes, connections).unwrap();
         assert_eq!(sorted, vec!["a", "b", "c"]);
     }
 
     #[test]
-    #[should_panic(
-        expected = "Topological sort failed: the graph may contain a cycle or reference missing nodes"
-    )]
     fn test_cycle_detection() {
-        let nodes = vec!["a".into(), "b".into(), "c".into()];
+        let nodes = vec!["a".into(), "b".into(), "c".into(), "d".into()];
         let connections = vec![
+            ("d".into(), "a".into()),
             ("a".into(), "b".into()),
             ("b".into(), "c".into()),
             ("c".into(), "a".into()),
         ];
-        sort(nodes, connections);
-        // This point should never be reached
-        panic!("Test failed: Cycle was not detected");
+
+        let Err(SortError::Cycle(cycle)) = sort(nodes, connections) else {
+            panic!("Cycle was not detected");
+        };
+        assert_eq!(cycle.nodes, vec!["a", "b", "c"]);
+        assert_eq!(cycle.to_string(), "a → b → c → a");
+    }
+
+    #[test]
+    fn test_unknown_node() {
+        let nodes = vec!["a".into()];
+        let connections = vec![("a".into(), "b".into())];
+
+        assert_eq!(
+            sort(nodes, connections),
+            Err(SortError::UnknownNode("b".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_duplicate_node() {
+        let nodes = vec!["a".into(), "a".into()];
+
+        assert_eq!(
+            sort(nodes, vec![]),
+            Err(SortError::DuplicateNode("a".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_find_cycle_in_edges() {
+        let mut edges = vec![edge("a", "out", "b", "in"), edge("b", "out", "c", "in")];
+        assert_eq!(find_cycle(&edges), None);
+
+        edges.push(edge("c", "out", "b", "in"));
+        let cycle = find_cycle(&edges).unwrap();
+        assert_eq!(cycle.nodes, vec!["b", "c"]);
+        assert_eq!(cycle.edges, vec!["b-c", "c-b"]);
     }
 
     #[test]
     fn test_disconnected_graph() {
         let nodes = vec!["a".into(), "b".into(), "c".into(), "d".into()];
         let connections = vec![("a".into(), "b".into()), ("c".into(), "d".into())];
-        let sorted = sort(nodes, connections);
+        let sorted = sort(nodes, connections).unwrap();
         // Valid outputs could be ["a", "b", "c", "d"] or ["c", "d", "a", "b"] or other variations
         assert_eq!(sorted.len(), 4);
         // make sure a is before b and c is before d
@@ -0,0 +1,367 @@
+/// Static checks on a graph before it is executed
+///
+/// The engine only notices most of these problems once it reaches the node involved (or not
+/// at all, like data cycles), so `execute_graph` refuses graphs with error diagnostics.
+use super::topological::{self, Cycle};
+use crate::bricks::types::ConnectionType;
+use crate::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "tauri", derive(specta::Type))]
+#[serde(rename_all = "kebab-case")]
+pub enum Severity {
+    /// The graph can't be executed
+    Error,
+    /// The graph runs, but probably not as intended
+    Warning,
+}
+
+/// A problem found in a graph, pointing at the node or edge involved
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "tauri", derive(specta::Type))]
+pub struct Diagnostic {
+    pub severity: Severity,
+    #[serde(rename = "nodeId")]
+    pub node_id: Option<String>,
+    #[serde(rename = "edgeId")]
+    pub edge_id: Option<String>,
+    pub message: String,
+    /// The cycle the node is part of, so every edge along it can be highlighted
+    #[serde(default)]
+    pub cycle: Option<Cycle>,
+}
+
+impl Diagnostic {
+    pub fn error(message: String) -> Self {
+        Self {
+            severity: Severity::Error,
+            node_id: None,
+            edge_id: None,
+            message,
+            cycle: None,
+        }
+    }
+
+    fn warning(message: String) -> Self {
+        Self {
+            severity: Severity::Warning,
+            ..Self::error(message)
+        }
+    }
+
+    fn on_node(mut self, node_id: &str) -> Self {
+        self.node_id = Some(node_id.to_string());
+        self
+    }
+
+    fn on_edge(mut self, edge_id: &str) -> Self {
+        self.edge_id = Some(edge_id.to_string());
+        self
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+/// Check a graph for problems, bricks have to be attached to its nodes already
+pub fn validate(graph: &Graph) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut nodes: HashMap<&str, &Node> = HashMap::new();
+
+    for node in &graph.nodes {
+        if nodes.insert(node.id.as_str(), node).is_some() {
+            diagnostics.push(
+                Diagnostic::error(format!("Node id '{}' is used more than once", node.id))
+                    .on_node(&node.id),
+            );
+        }
+        if node.data.brick.is_none() {
+            diagnostics.push(
+                Diagnostic::error(format!(
+                    "Node '{}' uses unknown brick '{}'",
+                    node.id, node.data.brick_id
+                ))
+                .on_node(&node.id),
+            );
+        }
+    }
+
+    let mut connected_inputs: HashSet<(&str, &str)> = HashSet::new();
+    let mut used_outputs: HashSet<&str> = HashSet::new();
+    let mut data_edges = Vec::new();
+
+    for edge in &graph.edges {
+        let (Some(source), Some(target)) = (
+            nodes.get(edge.source.as_str()),
+            nodes.get(edge.target.as_str()),
+        ) else {
+            diagnostics.push(
+                Diagnostic::error(format!(
+                    "Edge '{}' connects '{}' to '{}', which don't both exist",
+                    edge.id, edge.source, edge.target
+                ))
+                .on_edge(&edge.id),
+            );
+            continue;
+        };
+        used_outputs.insert(source.id.as_str());
+
+        // Unknown bricks are reported above already
+        let (Some(source_brick), Some(target_brick)) = (&source.data.brick, &target.data.brick)
+        else {
+            continue;
+        };
+
+        let Some(output_type) = output_type(source_brick, &edge.source_handle) else {
+            diagnostics.push(
+                Diagnostic::error(format!(
+                    "Node '{}' has no output '{}'",
+                    source.id, edge.source_handle
+                ))
+                .on_edge(&edge.id),
+            );
+            continue;
+        };
+        let Some(input_type) = input_type(target_brick, &edge.target_handle) else {
+            diagnostics.push(
+                Diagnostic::error(format!(
+                    "Node '{}' has no input '{}'",
+                    target.id, edge.target_handle
+                ))
+                .on_edge(&edge.id),
+            );
+            continue;
+        };
+
+        if !input_type.accepts(&output_type) {
+            diagnostics.push(
+                Diagnostic::error(type_mismatch(edge, &output_type, &input_type)).on_edge(&edge.id),
+            );
+            continue;
+        }
+
+        // Execution inputs can be triggered from several outputs, data inputs hold one value
+        if input_type != ConnectionType::Flow {
+            if !connected_inputs.insert((target.id.as_str(), edge.target_handle.as_str())) {
+                diagnostics.push(
+                    Diagnostic::error(format!(
+                        "Input '{}' of '{}' is connected more than once",
+                        edge.target_handle, target.id
+                    ))
+                    .on_edge(&edge.id),
+                );
+            }
+            data_edges.push(edge);
+        }
+    }
+
+    for node in &graph.nodes {
+        let Some(brick) = &node.data.brick else {
+            continue;
+        };
+
+        for input in &brick.inputs {
+            let has_value = connected_inputs.contains(&(node.id.as_str(), input.id.as_str()))
+                || node.data.defaults.contains_key(&input.id)
+                || input.default_value.is_some();
+            if !has_value {
+                diagnostics.push(
+                    Diagnostic::error(format!(
+                        "Input '{}' of '{}' is not connected and has no default",
+                        input.id, node.id
+                    ))
+                    .on_node(&node.id),
+                );
+            }
+        }
+
+        let is_data_node = brick.execution_inputs.is_empty() && brick.execution_outputs.is_empty();
+        if is_data_node && !used_outputs.contains(node.id.as_str()) {
+            diagnostics.push(
+                Diagnostic::warning(format!("Outputs of '{}' are never used", node.id))
+                    .on_node(&node.id),
+            );
+        }
+    }
+
+    if let Some(cycle) = topological::find_cycle(data_edges) {
+        let mut diagnostic =
+            Diagnostic::error(format!("Data dependencies form a cycle: {}", cycle))
+                .on_node(&cycle.nodes[0]);
+        diagnostic.cycle = Some(cycle);
+        diagnostics.push(diagnostic);
+    }
+    diagnostics
+}
+
+/// Check whether any of the diagnostics prevents execution
+pub fn has_errors(diagnostics: &[Diagnostic]) -> bool {
+    diagnostics.iter().any(Diagnostic::is_error)
+}
+
+/// Type of the value (or execution) leaving a brick through an output handle
+pub(crate) fn output_type(brick: &Brick, handle: &str) -> Option<ConnectionType> {
+    if brick
+        .execution_outputs
+        .iter()
+        .any(|output| output.id == handle)
+    {
+        return Some(ConnectionType::Flow);
+    }
+    brick
+        .outputs
+        .iter()
+        .find(|output| output.id == handle)
+        .map(|output| output.r#type.clone())
+}
+
+/// Type of the value (or execution) an input handle of a brick takes
+pub(crate) fn input_type(brick: &Brick, handle: &str) -> Option<ConnectionType> {
+    if brick
+        .execution_inputs
+        .iter()
+        .any(|input| input.id == handle)
+    {
+        return Some(ConnectionType::Flow);
+    }
+    brick
+        .inputs
+        .iter()
+        .find(|input| input.id == handle)
+        .map(|input| input.r#type.clone())
+}
+
+/// Explain why an edge's output can't feed its input
+pub(crate) fn type_mismatch(
+    edge: &Edge,
+    output_type: &ConnectionType,
+    input_type: &ConnectionType,
+) -> String {
+    format!(
+        "Output '{}' of '{}' ({}) can't be connected to input '{}' of '{}' ({})",
+        edge.source_handle,
+        edge.source,
+        type_name(output_type),
+        edge.target_handle,
+        edge.target,
+        type_name(input_type)
+    )
+}
+
+fn type_name(r#type: &ConnectionType) -> String {
+    match r#type {
+        ConnectionType::Flow => "execution".to_string(),
+        ConnectionType::String => "string".to_string(),
+        ConnectionType::Number => "number".to_string(),
+        ConnectionType::Boolean => "boolean".to_string(),
+        ConnectionType::Enum => "enum".to_string(),
+        ConnectionType::Any => "any".to_string(),
+        ConnectionType::List(item) => format!("list of {}", type_name(item)),
+        ConnectionType::Map(value) => format!("map of {}", type_name(value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bricks::arithmetics::add_brick;
+    use crate::bricks::debug::{print_brick, trigger_brick};
+    use crate::test_utils::{edge, node};
+
+    fn errors(graph: &Graph) -> Vec<Diagnostic> {
+        validate(graph)
+            .into_iter()
+            .filter(Diagnostic::is_error)
+            .collect()
+    }
+
+    #[test]
+    fn test_valid_graph() {
+        let graph = Graph {
+            nodes: vec![node("a", add_brick()), node("print", print_brick())],
+            edges: vec![edge("a", "output_0", "print", "value")],
+        };
+
+        assert_eq!(validate(&graph), vec![]);
+    }
+
+    #[test]
+    fn test_broken_references() {
+        let mut unknown = node("unknown", print_brick());
+        unknown.data.brick_id = "missing".to_string();
+        unknown.data.brick = None;
+        let graph = Graph {
+            nodes: vec![node("a", add_brick()), unknown],
+            edges: vec![
+                edge("a", "output_0", "gone", "value"),
+                edge("a", "nope", "a", "b"),
+            ],
+        };
+
+        let errors = errors(&graph);
+        assert_eq!(errors.len(), 3, "{:?}", errors);
+        assert_eq!(errors[0].node_id.as_deref(), Some("unknown"));
+        assert_eq!(errors[1].edge_id.as_deref(), Some("a-gone"));
+        assert_eq!(errors[2].message, "Node 'a' has no output 'nope'");
+    }
+
+    #[test]
+    fn test_type_mismatch_and_duplicate_inputs() {
+        let graph = Graph {
+            nodes: vec![
+                node("a", add_brick()),
+                node("b", add_brick()),
+                node("print", print_brick()),
+                node("trigger", trigger_brick()),
+            ],
+            edges: vec![
+                // Numbers render as text, but an execution output carries no value
+                edge("a", "output_0", "print", "value"),
+                edge("trigger", "triggered", "b", "a"),
+                edge("a", "output_0", "b", "b"),
+                Edge {
+                    id: "again".to_string(),
+                    ..edge("a", "output_0", "b", "b")
+                },
+            ],
+        };
+
+        let errors = errors(&graph);
+        let edges: Vec<_> = errors.iter().filter_map(|e| e.edge_id.as_deref()).collect();
+        assert_eq!(edges, vec!["trigger-b", "again"], "{:?}", errors);
+    }
+
+    #[test]
+    fn test_data_cycle() {
+        let graph = Graph {
+            nodes: vec![node("a", add_brick()), node("b", add_brick())],
+            edges: vec![
+                edge("a", "output_0", "b", "a"),
+                edge("b", "output_0", "a", "a"),
+            ],
+        };
+
+        let errors = errors(&graph);
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert_eq!(
+            errors[0].message,
+            "Data dependencies form a cycle: a → b → a"
+        );
+        let cycle = errors[0].cycle.as_ref().unwrap();
+        assert_eq!(cycle.edges, vec!["a-b", "b-a"]);
+    }
+
+    #[test]
+    fn test_connection_types() {
+        use ConnectionType::*;
+
+        assert!(String.accepts(&Number));
+        assert!(Number.accepts(&Any));
+        assert!(List(Box::new(Any)).accepts(&List(Box::new(Number))));
+        assert!(!Number.accepts(&String));
+        assert!(!Flow.accepts(&Any));
+        assert!(!Map(Box::new(Number)).accepts(&List(Box::new(Number))));
+    }
+}
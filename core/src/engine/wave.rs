@@ -0,0 +1,835 @@
+/// A single execution of the graph
+///
+/// Every incoming event starts its own wave with an isolated queue, cache and
+/// `ExecutionContext`, so waves can run side by side on the engine's worker pool.
+/// Only the results of constant data nodes are shared between waves.
+use super::policy::ErrorPolicy;
+use super::topological::Cycle;
+use super::{data_dfs, trigger, EngineShared, ExecutionPhase};
+use crate::bricks::types::{
+    BrickArgumentValue, BrickAsyncExecutionFn, BrickExecutionFn, BrickInputValue, BrickOutputValue,
+    ERROR_EXECUTION_OUTPUT, ERROR_MESSAGE_OUTPUT,
+};
+use crate::prelude::*;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::task::AbortHandle;
+
+pub struct Wave {
+    /// Unique id within the engine (0 is the wave started by `Engine::start`)
+    pub id: u64,
+    /// Queue of flow nodes waiting to execute
+    queue: VecDeque<String>,
+    /// Cache of node outputs, shared with the debugger for inspection
+    cache: Arc<Mutex<Cache>>,
+    /// Current flow node being processed
+    current_flow_node: Option<String>,
+    /// Pending data dependencies for the current flow node
+    pending_data_deps: VecDeque<String>,
+    /// Loops whose body is currently executing (innermost last)
+    loop_stack: Vec<LoopFrame>,
+    /// Data of the event that started this wave
+    context: trigger::ExecutionContext,
+    /// Set to stop the wave before its next node
+    cancelled: Arc<AtomicBool>,
+    /// Async flow nodes running on the engine's runtime
+    in_flight: Vec<(String, AbortHandle)>,
+    /// Async nodes report back through this channel once their future completes
+    completion_sender: Sender<AsyncCompletion>,
+    completions: Receiver<AsyncCompletion>,
+    /// Completions received while waiting, not processed yet
+    completed: VecDeque<AsyncCompletion>,
+    /// Attempts made so far for nodes that are being retried
+    attempts: HashMap<String, u32>,
+    /// Backoff of a retried node, no node of the wave starts before this
+    retry_at: Option<Instant>,
+    /// Whether the debugger is holding back the wave's next node
+    held: bool,
+}
+
+/// Outputs of the nodes a wave executed (node_id -> outputs)
+pub type Cache = HashMap<String, Vec<BrickOutputValue>>;
+
+/// Result of an async flow node, `Err` when the brick panicked
+struct AsyncCompletion {
+    node_id: String,
+    outcome: Result<(BrickContext, Result<Vec<BrickOutputValue>, BrickError>), ()>,
+}
+
+/// How a wave goes on after one of its nodes failed
+enum Recovery {
+    /// Execute the node again
+    Retry,
+    /// Continue on the node's error branch
+    Branch(Vec<trigger::Trigger>),
+    /// Leave the node's flow and go on with the rest of the wave
+    Skip,
+    /// Stop the wave
+    Stop(String),
+}
+
+/// A loop node whose body is currently being executed
+struct LoopFrame {
+    node_id: String,
+    /// Iteration the loop node sees when it executes next
+    iteration: usize,
+    /// Flow nodes queued before the loop started, resumed once it completes
+    outer_queue: VecDeque<String>,
+    /// Data nodes recomputed for every iteration
+    scope: HashSet<String>,
+}
+
+impl Wave {
+    /// Create a wave that starts out with the already computed constant results
+    pub fn new(id: u64, constants: Cache, context: trigger::ExecutionContext) -> Self {
+        let (completion_sender, completions) = mpsc::channel();
+        Self {
+            id,
+            queue: VecDeque::new(),
+            cache: Arc::new(Mutex::new(constants)),
+            current_flow_node: None,
+            pending_data_deps: VecDeque::new(),
+            loop_stack: Vec::new(),
+            context,
+            cancelled: Arc::new(AtomicBool::new(false)),
+            in_flight: Vec::new(),
+            completion_sender,
+            completions,
+            completed: VecDeque::new(),
+            attempts: HashMap::new(),
+            retry_at: None,
+            held: false,
+        }
+    }
+
+    /// Token that cancels this wave when set
+    pub fn cancellation_token(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.cancelled)
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Cached outputs of a node
+    pub fn cached(&self, node_id: &str) -> Option<Vec<BrickOutputValue>> {
+        self.cache.lock().unwrap().get(node_id).cloned()
+    }
+
+    fn is_cached(&self, node_id: &str) -> bool {
+        self.cache.lock().unwrap().contains_key(node_id)
+    }
+
+    /// Shared handle on the wave's cache
+    pub(super) fn cache_handle(&self) -> &Arc<Mutex<Cache>> {
+        &self.cache
+    }
+
+    /// Queue a flow node for execution
+    pub fn enqueue(&mut self, shared: &EngineShared, node_id: String) {
+        shared.update_node_state(&node_id, ExecutionPhase::Queued, None);
+        self.queue.push_back(node_id);
+    }
+
+    /// Run the wave to completion on the current thread, reporting every step
+    pub fn run(mut self, shared: &EngineShared, mut report: impl FnMut(Result<String, String>)) {
+        while !self.is_cancelled() {
+            match self.step(shared) {
+                // Only waiting on async nodes, block until one completes
+                Some(Ok(node_id)) if node_id.is_empty() => {
+                    self.wait_for_async(Duration::from_millis(10))
+                }
+                Some(Ok(node_id)) => report(Ok(node_id)),
+                Some(Err(e)) => {
+                    // An errored node stops the rest of its wave
+                    report(Err(e));
+                    break;
+                }
+                None => break,
+            }
+        }
+
+        if self.is_cancelled() {
+            shared.debug_log(&format!("Wave {} cancelled", self.id));
+        }
+    }
+
+    /// Execute the next node of this wave, or return None once it has nothing left to do
+    ///
+    /// Returns an empty node id while the wave only waits for async nodes to complete.
+    pub fn step(&mut self, shared: &EngineShared) -> Option<Result<String, String>> {
+        loop {
+            // A retried node waits for its backoff, async nodes can complete meanwhile
+            let backing_off = self.retry_at.is_some_and(|at| Instant::now() < at);
+            if !backing_off {
+                self.retry_at = None;
+            }
+
+            // State 1: Process pending data dependencies
+            let data_node = if backing_off {
+                None
+            } else {
+                self.pending_data_deps.pop_front()
+            };
+            if let Some(data_node_id) = data_node {
+                if !self.is_cached(&data_node_id) && self.held_by_debugger(shared, &data_node_id) {
+                    self.pending_data_deps.push_front(data_node_id);
+                    return Some(Ok(String::new()));
+                }
+
+                let Err(error) = self.execute_data_node(shared, &data_node_id) else {
+                    return Some(Ok(data_node_id));
+                };
+
+                // Data nodes have no error branch, the policy decides
+                match self.recover(shared, &data_node_id, error) {
+                    Recovery::Retry => {
+                        self.pending_data_deps.push_front(data_node_id);
+                        continue;
+                    }
+                    Recovery::Stop(message) => {
+                        self.stop();
+                        return Some(Err(message));
+                    }
+                    Recovery::Branch(_) | Recovery::Skip => {
+                        self.skip_flow_node(shared, &data_node_id);
+                        return Some(Ok(data_node_id));
+                    }
+                }
+            }
+
+            // State 2: Execute current flow node
+            let flow_node = if backing_off {
+                None
+            } else {
+                self.current_flow_node.take()
+            };
+            if let Some(flow_node_id) = flow_node {
+                if self.held_by_debugger(shared, &flow_node_id) {
+                    self.current_flow_node = Some(flow_node_id);
+                    return Some(Ok(String::new()));
+                }
+
+                // Let loop nodes know which iteration they are on
+                let iteration = match self.loop_stack.last() {
+                    Some(frame) if frame.node_id == flow_node_id => frame.iteration,
+                    _ => 0,
+                };
+
+                let result = match self.execute_node_internal(shared, &flow_node_id, iteration) {
+                    Ok(Some(triggers)) => Ok(triggers),
+                    // Async node, it is reported once it completes
+                    Ok(None) => continue,
+                    Err(error) => Err(error),
+                };
+
+                match self.continue_flow(shared, flow_node_id, result) {
+                    Some(result) => return Some(result),
+                    None => continue,
+                }
+            }
+
+            // State 2b: An async flow node completed
+            let completion = self
+                .completed
+                .pop_front()
+                .or_else(|| self.completions.try_recv().ok());
+            if let Some(completion) = completion {
+                let node_id = completion.node_id.clone();
+                let result = self.complete_async_node(shared, completion);
+
+                match self.continue_flow(shared, node_id, result) {
+                    Some(result) => return Some(result),
+                    None => continue,
+                }
+            }
+
+            // State 2c: Wait for the backoff of a retried node to elapse
+            if backing_off {
+                return Some(Ok(String::new()));
+            }
+
+            // State 3: Start new flow node from queue
+            if let Some(next_flow_node) = self.queue.pop_front() {
+                // Resolve data dependencies for this flow node
+                let data_deps = match self.resolve_data_dependencies(shared, &next_flow_node) {
+                    Ok(data_deps) => data_deps,
+                    Err(cycle) => {
+                        // None of the nodes on the cycle can ever get their inputs
+                        let message = format!(
+                            "Data dependencies of '{}' form a cycle: {}",
+                            next_flow_node, cycle
+                        );
+                        shared.set_node_error(&next_flow_node, message.clone());
+                        self.stop();
+                        return Some(Err(message));
+                    }
+                };
+
+                // Queue data dependencies and set current flow node
+                for dep_node in &data_deps {
+                    shared.update_node_state(dep_node, ExecutionPhase::Queued, None);
+                }
+                self.pending_data_deps.extend(data_deps);
+                self.current_flow_node = Some(next_flow_node);
+                continue;
+            }
+
+            // State 3a: Wait for async flow nodes before considering the wave (or a loop
+            // iteration) finished
+            if !self.in_flight.is_empty() {
+                return Some(Ok(String::new()));
+            }
+
+            // State 3b: Loop body finished, run the loop node again for its next iteration
+            if let Some(frame) = self.loop_stack.last_mut() {
+                frame.iteration += 1;
+                let node_id = frame.node_id.clone();
+                let scope: Vec<String> = frame.scope.iter().cloned().collect();
+
+                let mut cache = self.cache.lock().unwrap();
+                for scoped_node in &scope {
+                    cache.remove(scoped_node);
+                }
+                drop(cache);
+
+                self.enqueue(shared, node_id);
+                continue;
+            }
+
+            return None;
+        }
+    }
+
+    /// Ask the debugger whether a node may execute now, marking it paused if not
+    fn held_by_debugger(&mut self, shared: &EngineShared, node_id: &str) -> bool {
+        // Conditional breakpoints look at the inputs the node would receive
+        let inputs = match shared.get_node(node_id) {
+            Some(node) if shared.debugger.has_conditions(node_id) => node
+                .data
+                .brick
+                .as_ref()
+                .and_then(|brick| {
+                    build_inputs(&shared.graph, &self.cache.lock().unwrap(), node, brick).ok()
+                })
+                .unwrap_or_default(),
+            _ => Vec::new(),
+        };
+
+        self.held = !shared.debugger.allow(node_id, &inputs);
+        if self.held && shared.node_phase(node_id) != Some(ExecutionPhase::Paused) {
+            shared.update_node_state(node_id, ExecutionPhase::Paused, None);
+        }
+        self.held
+    }
+
+    /// Resolve all data dependencies for a node using DFS, in the order they have to execute
+    fn resolve_data_dependencies(
+        &self,
+        shared: &EngineShared,
+        node_id: &str,
+    ) -> Result<Vec<String>, Cycle> {
+        // The node itself may be cached from an earlier run (e.g. a previous loop iteration)
+        let mut cached: HashSet<String> = self.cache.lock().unwrap().keys().cloned().collect();
+        cached.remove(node_id);
+        let dfs_iter = data_dfs::DataNodeDfsIterator::new(&shared.graph, node_id, &cached);
+        dfs_iter
+            .filter(|dep| dep.as_deref() != Ok(node_id))
+            .collect()
+    }
+
+    /// Execute a single node (data or flow), cache its outputs and return the execution
+    /// outputs it fired
+    ///
+    /// Async flow nodes are spawned on the engine's runtime instead, in which case this
+    /// returns None and the node finishes later on in `step`.
+    fn execute_node_internal(
+        &mut self,
+        shared: &EngineShared,
+        node_id: &str,
+        iteration: usize,
+    ) -> Result<Option<Vec<trigger::Trigger>>, BrickError> {
+        // Mark node as running
+        let attempt = self.attempts.get(node_id).copied().unwrap_or(1);
+        shared.start_attempt(node_id, attempt);
+
+        let node = shared
+            .get_node(node_id)
+            .ok_or_else(|| BrickError::failed(format!("Node '{}' not found", node_id)))?;
+
+        let brick = node
+            .data
+            .brick
+            .as_ref()
+            .ok_or_else(|| BrickError::failed(format!("Node '{}' has no brick", node_id)))?;
+
+        // Build arguments from node data and inputs from connected edges and cached data
+        let (arguments, inputs) = build_arguments(node, brick)
+            .and_then(|arguments| {
+                let cache = self.cache.lock().unwrap();
+                Ok((arguments, build_inputs(&shared.graph, &cache, node, brick)?))
+            })
+            .map_err(BrickError::InvalidInput)?;
+
+        let context = BrickContext::new(node_id)
+            .with_event(self.context.clone())
+            .with_loop_iteration(iteration)
+            .with_cancellation(self.cancellation_token());
+
+        let timeout = node.data.policy.timeout();
+
+        // Let async flow nodes run in the background while other branches continue,
+        // data nodes are needed right away so they always run to completion here
+        if let Some(execution) = brick.async_execution {
+            if !shared.is_data_node(node_id) {
+                self.spawn_async_node(shared, node_id, context, execution, arguments, inputs);
+                return Ok(None);
+            }
+        }
+
+        // Execute the brick
+        let (context, result) = match timeout {
+            Some(timeout) => run_with_timeout(brick.execution, context, arguments, inputs, timeout),
+            None => {
+                let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                    (brick.execution)(&context, arguments, inputs)
+                }))
+                .unwrap_or(Err(BrickError::Panicked));
+                (context, result)
+            }
+        };
+
+        self.finish_node(shared, node_id, &context, result)
+            .map(Some)
+    }
+
+    /// Record the result of a brick execution, returning the execution outputs it fired
+    fn finish_node(
+        &mut self,
+        shared: &EngineShared,
+        node_id: &str,
+        context: &BrickContext,
+        result: Result<Vec<BrickOutputValue>, BrickError>,
+    ) -> Result<Vec<trigger::Trigger>, BrickError> {
+        for message in context.take_logs() {
+            shared.notify(|observer| observer.log(node_id, &message));
+        }
+
+        match result {
+            Ok(outputs) => {
+                // Constant results are shared with waves started later on
+                if shared.constant_nodes.contains(node_id) {
+                    shared
+                        .constants
+                        .lock()
+                        .unwrap()
+                        .insert(node_id.to_string(), outputs.clone());
+                }
+
+                // Cache outputs and mark as completed
+                self.cache
+                    .lock()
+                    .unwrap()
+                    .insert(node_id.to_string(), outputs.clone());
+                shared.update_node_state(node_id, ExecutionPhase::Completed, Some(outputs));
+                self.attempts.remove(node_id);
+            }
+            // What happens next is up to the node's policy (see `recover`)
+            Err(error) => return Err(error),
+        }
+
+        shared.debug_log(&format!("✓ Executed: {} (wave {})", node_id, self.id));
+
+        Ok(context.take_triggers())
+    }
+
+    /// Queue the flow nodes behind the execution outputs a node fired
+    fn follow_triggers(
+        &mut self,
+        shared: &EngineShared,
+        node_id: &str,
+        triggers: &[trigger::Trigger],
+    ) {
+        for trigger in triggers {
+            if trigger.iteration {
+                self.begin_iteration(shared, node_id, &trigger.output_id);
+            } else {
+                self.end_loop(shared, node_id);
+            }
+
+            let next_nodes = shared.find_triggered_nodes(trigger);
+            for node_id in &next_nodes {
+                shared.update_node_state(node_id, ExecutionPhase::Queued, None);
+            }
+            self.queue.extend(next_nodes);
+        }
+    }
+
+    /// Carry on after a flow node finished, returning what `step` reports for it (None when
+    /// the node is retried)
+    fn continue_flow(
+        &mut self,
+        shared: &EngineShared,
+        node_id: String,
+        result: Result<Vec<trigger::Trigger>, BrickError>,
+    ) -> Option<Result<String, String>> {
+        match result {
+            Ok(triggers) => self.follow_triggers(shared, &node_id, &triggers),
+            Err(error) => match self.recover(shared, &node_id, error) {
+                Recovery::Retry => {
+                    self.current_flow_node = Some(node_id);
+                    return None;
+                }
+                Recovery::Branch(triggers) => self.follow_triggers(shared, &node_id, &triggers),
+                Recovery::Skip => {}
+                Recovery::Stop(message) => {
+                    self.stop();
+                    return Some(Err(message));
+                }
+            },
+        }
+
+        Some(Ok(node_id))
+    }
+
+    /// Decide how to go on after a node failed, based on its policy and error branch
+    fn recover(&mut self, shared: &EngineShared, node_id: &str, error: BrickError) -> Recovery {
+        if matches!(error, BrickError::TimedOut(_)) {
+            shared.set_node_timed_out(node_id);
+        }
+
+        let policy = shared.node_policy(node_id);
+        let attempt = self.attempts.entry(node_id.to_string()).or_insert(1);
+        if *attempt < policy.attempt_limit() {
+            let delay = policy.backoff.delay(*attempt);
+            *attempt += 1;
+            shared.debug_log(&format!(
+                "Retrying {} (attempt {}) in {:?} after: {}",
+                node_id, attempt, delay, error
+            ));
+            if !delay.is_zero() {
+                self.retry_at = Some(Instant::now() + delay);
+            }
+            return Recovery::Retry;
+        }
+        self.attempts.remove(node_id);
+
+        let message = format!("Node '{}' failed: {}", node_id, error);
+        shared.set_node_error(node_id, message.clone());
+
+        if shared.has_error_branch(node_id) {
+            // Let the handler branch read what went wrong
+            self.cache.lock().unwrap().insert(
+                node_id.to_string(),
+                vec![BrickOutputValue {
+                    id: ERROR_MESSAGE_OUTPUT.to_string(),
+                    value: Value::from(error.message()),
+                }],
+            );
+            return Recovery::Branch(vec![trigger::Trigger::new(
+                node_id.to_string(),
+                ERROR_EXECUTION_OUTPUT.to_string(),
+            )]);
+        }
+
+        match policy.on_error {
+            ErrorPolicy::Continue => Recovery::Skip,
+            ErrorPolicy::StopRun | ErrorPolicy::Retry => Recovery::Stop(message),
+        }
+    }
+
+    /// Skip the flow node waiting on a failed data node, rather than running it with the
+    /// defaults of the inputs that node should have provided
+    fn skip_flow_node(&mut self, shared: &EngineShared, failed_node_id: &str) {
+        for dep_node in self.pending_data_deps.drain(..) {
+            shared.update_node_state(&dep_node, ExecutionPhase::Waiting, None);
+        }
+        if let Some(flow_node_id) = self.current_flow_node.take() {
+            shared.set_node_error(
+                &flow_node_id,
+                format!("Skipped: data dependency '{}' failed", failed_node_id),
+            );
+        }
+    }
+
+    /// Drop everything this wave still had to do
+    fn stop(&mut self) {
+        self.queue.clear();
+        self.pending_data_deps.clear();
+        self.current_flow_node = None;
+        self.loop_stack.clear();
+        self.retry_at = None;
+        for (_, task) in self.in_flight.drain(..) {
+            task.abort();
+        }
+    }
+
+    /// Run an async flow node on the engine's runtime, reporting back through the wave's channel
+    fn spawn_async_node(
+        &mut self,
+        shared: &EngineShared,
+        node_id: &str,
+        context: BrickContext,
+        execution: BrickAsyncExecutionFn,
+        arguments: Vec<BrickArgumentValue>,
+        inputs: Vec<BrickInputValue>,
+    ) {
+        let timeout = shared.node_policy(node_id).timeout();
+        let task = shared.runtime.spawn(async move {
+            let execution = execution(&context, arguments, inputs);
+            let result = match timeout {
+                Some(timeout) => tokio::time::timeout(timeout, execution)
+                    .await
+                    .unwrap_or(Err(BrickError::TimedOut(timeout))),
+                None => execution.await,
+            };
+            (context, result)
+        });
+        self.in_flight
+            .push((node_id.to_string(), task.abort_handle()));
+
+        // A panicking brick only fails its own task, so report the join result instead
+        let completions = self.completion_sender.clone();
+        let node_id = node_id.to_string();
+        shared.runtime.spawn(async move {
+            let outcome = task.await.map_err(|_| ());
+            let _ = completions.send(AsyncCompletion { node_id, outcome });
+        });
+    }
+
+    /// Finish an async flow node whose future completed
+    fn complete_async_node(
+        &mut self,
+        shared: &EngineShared,
+        completion: AsyncCompletion,
+    ) -> Result<Vec<trigger::Trigger>, BrickError> {
+        let AsyncCompletion { node_id, outcome } = completion;
+        if let Some(index) = self.in_flight.iter().position(|(id, _)| *id == node_id) {
+            self.in_flight.remove(index);
+        }
+
+        let (context, result) = match outcome {
+            Ok((context, result)) => (context, result),
+            Err(()) => (
+                BrickContext::new(node_id.as_str()),
+                Err(BrickError::Panicked),
+            ),
+        };
+
+        self.finish_node(shared, &node_id, &context, result)
+    }
+
+    /// Block until an async node of this wave completes, or the timeout elapses
+    ///
+    /// Also sleeps through the backoff of a retried node, or while the debugger holds the
+    /// wave, when nothing is in flight.
+    pub fn wait_for_async(&mut self, timeout: Duration) {
+        if !self.completed.is_empty() {
+            return;
+        }
+        if self.in_flight.is_empty() {
+            if self.held {
+                std::thread::sleep(timeout);
+            } else if let Some(at) = self.retry_at {
+                std::thread::sleep(at.saturating_duration_since(Instant::now()).min(timeout));
+            }
+            return;
+        }
+        if let Ok(completion) = self.completions.recv_timeout(timeout) {
+            self.completed.push_back(completion);
+        }
+    }
+
+    /// Execute a data node (skips if already cached)
+    fn execute_data_node(
+        &mut self,
+        shared: &EngineShared,
+        node_id: &str,
+    ) -> Result<(), BrickError> {
+        if self.is_cached(node_id) {
+            return Ok(());
+        }
+        // Data nodes have no execution outputs to fire
+        self.execute_node_internal(shared, node_id, 0).map(|_| ())
+    }
+
+    /// Start iterating a loop node, or continue if its body is already running
+    fn begin_iteration(&mut self, shared: &EngineShared, node_id: &str, body_output: &str) {
+        if matches!(self.loop_stack.last(), Some(frame) if frame.node_id == node_id) {
+            return;
+        }
+
+        let scope = shared.loop_scoped_data_nodes(node_id, body_output);
+        let mut cache = self.cache.lock().unwrap();
+        for scoped_node in &scope {
+            cache.remove(scoped_node);
+        }
+        drop(cache);
+
+        shared.debug_log(&format!("Loop started: {}", node_id));
+        self.loop_stack.push(LoopFrame {
+            node_id: node_id.to_string(),
+            iteration: 0,
+            outer_queue: std::mem::take(&mut self.queue),
+            scope,
+        });
+    }
+
+    /// Finish the innermost loop if it belongs to this node, resuming the flow queued before it
+    fn end_loop(&mut self, shared: &EngineShared, node_id: &str) {
+        if !matches!(self.loop_stack.last(), Some(frame) if frame.node_id == node_id) {
+            return;
+        }
+
+        if let Some(frame) = self.loop_stack.pop() {
+            shared.debug_log(&format!(
+                "Loop completed: {} ({} iterations)",
+                node_id, frame.iteration
+            ));
+            let body_queue = std::mem::replace(&mut self.queue, frame.outer_queue);
+            self.queue.extend(body_queue);
+        }
+    }
+}
+
+impl Drop for Wave {
+    fn drop(&mut self) {
+        // Async nodes of a cancelled (or finished) wave have nobody left to report to
+        for (_, task) in &self.in_flight {
+            task.abort();
+        }
+    }
+}
+
+/// Build brick inputs from connected edges and the outputs cached by a wave
+pub(super) fn build_inputs(
+    graph: &Graph,
+    cache: &Cache,
+    node: &Node,
+    brick: &Brick,
+) -> Result<Vec<BrickInputValue>, String> {
+    brick
+        .inputs
+        .iter()
+        .map(|input_def| {
+            // Find incoming data edge for this input
+            let connected_value = find_connected_input_value(graph, cache, &node.id, &input_def.id);
+
+            // Priority: connected edge > node defaults > brick defaults
+            let value = match connected_value {
+                Some(value) => value,
+                None => {
+                    let raw = node
+                        .data
+                        .defaults
+                        .get(&input_def.id)
+                        .or(input_def.default_value.as_ref())
+                        .ok_or_else(|| {
+                            format!(
+                                "No value available for input '{}' on node '{}'",
+                                input_def.id, node.id
+                            )
+                        })?;
+
+                    Value::parse(raw, &input_def.r#type).map_err(|e| {
+                        format!(
+                            "Invalid default for input '{}' on node '{}': {}",
+                            input_def.id, node.id, e
+                        )
+                    })?
+                }
+            };
+
+            Ok(BrickInputValue {
+                id: input_def.id.clone(),
+                value,
+            })
+        })
+        .collect()
+}
+
+/// Find the value for an input by traversing data edges
+fn find_connected_input_value(
+    graph: &Graph,
+    cache: &Cache,
+    target_node_id: &str,
+    target_input_id: &str,
+) -> Option<Value> {
+    for edge in &graph.edges {
+        if edge.target == target_node_id && edge.target_handle == target_input_id {
+            if let Some(cached_outputs) = cache.get(&edge.source) {
+                let source_output_id = edge.source_handle.as_str();
+
+                return cached_outputs
+                    .iter()
+                    .find(|output| output.id == source_output_id)
+                    .map(|output| output.value.clone());
+            }
+        }
+    }
+
+    None
+}
+
+/// Run a sync brick on its own thread, giving up on it once the timeout elapses
+///
+/// Threads can't be interrupted, so a brick that times out keeps running in the background
+/// and its outputs are dropped.
+fn run_with_timeout(
+    execution: BrickExecutionFn,
+    context: BrickContext,
+    arguments: Vec<BrickArgumentValue>,
+    inputs: Vec<BrickInputValue>,
+    timeout: Duration,
+) -> (BrickContext, Result<Vec<BrickOutputValue>, BrickError>) {
+    let node_id = context.node_id().to_string();
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            execution(&context, arguments, inputs)
+        }))
+        .unwrap_or(Err(BrickError::Panicked));
+        let _ = sender.send((context, result));
+    });
+
+    receiver.recv_timeout(timeout).unwrap_or_else(|error| {
+        let error = match error {
+            mpsc::RecvTimeoutError::Timeout => BrickError::TimedOut(timeout),
+            mpsc::RecvTimeoutError::Disconnected => BrickError::Panicked,
+        };
+        (BrickContext::new(node_id), Err(error))
+    })
+}
+
+/// Build brick arguments from node data
+fn build_arguments(node: &Node, brick: &Brick) -> Result<Vec<BrickArgumentValue>, String> {
+    brick
+        .arguments
+        .iter()
+        .map(|arg_def| {
+            // Get value from node arguments, or use brick default
+            let raw = node
+                .data
+                .arguments
+                .get(&arg_def.id)
+                .or(arg_def.default_value.as_ref())
+                .cloned()
+                .unwrap_or_default();
+
+            let value = Value::parse(&raw, &arg_def.r#type.connection_type()).map_err(|e| {
+                format!(
+                    "Invalid argument '{}' on node '{}': {}",
+                    arg_def.id, node.id, e
+                )
+            })?;
+
+            Ok(BrickArgumentValue {
+                id: arg_def.id.clone(),
+                value,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,413 @@
+/// Shared local HTTP server for webhook emission contexts
+///
+/// Every webhook node registers a (method, path) route on the server bound to its port.
+/// One server thread is spawned per port and shut down once its last route is removed,
+/// so any number of webhook nodes can share a single listening socket.
+///
+/// Routes that expect the graph to answer keep the connection open until `respond` is
+/// called with the request id from `HttpRequestData`, or until their timeout returns 504.
+use super::events::{ExecutionEvent, HttpRequestData, HttpResponseData};
+use std::collections::{BTreeMap, HashMap};
+use std::io::Read;
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+/// Port the webhook server binds to when a node doesn't configure one
+pub const DEFAULT_WEBHOOK_PORT: u16 = 7878;
+
+/// How long a held-open request waits for the graph to respond by default
+pub const DEFAULT_RESPONSE_TIMEOUT_MS: u64 = 30_000;
+
+/// Largest request body a webhook accepts, bigger ones are answered 413
+pub const MAX_BODY_BYTES: usize = 1024 * 1024;
+
+/// A node listening on a specific method and path
+#[derive(Clone)]
+struct Route {
+    node_id: String,
+    event_sender: Sender<ExecutionEvent>,
+    /// Hold the connection open for a graph response (None = answer 202 immediately)
+    response_timeout: Option<Duration>,
+}
+
+/// Routes are keyed by (uppercase method, path)
+type RouteTable = HashMap<(String, String), Route>;
+
+/// A running HTTP server bound to one local port
+pub struct WebhookServer {
+    port: u16,
+    server: Arc<tiny_http::Server>,
+    routes: Arc<Mutex<RouteTable>>,
+    thread_handle: Mutex<Option<std::thread::JoinHandle<()>>>,
+}
+
+/// All running webhook servers (port -> server)
+static SERVERS: OnceLock<Mutex<HashMap<u16, Arc<WebhookServer>>>> = OnceLock::new();
+
+/// Requests waiting for a graph response (request id -> response channel)
+static PENDING_RESPONSES: OnceLock<Mutex<HashMap<String, Sender<HttpResponseData>>>> =
+    OnceLock::new();
+
+fn servers() -> &'static Mutex<HashMap<u16, Arc<WebhookServer>>> {
+    SERVERS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn pending_responses() -> &'static Mutex<HashMap<String, Sender<HttpResponseData>>> {
+    PENDING_RESPONSES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+impl WebhookServer {
+    /// Bind a new server and spawn its request loop
+    fn bind(port: u16) -> Result<Arc<Self>, String> {
+        let server = tiny_http::Server::http(("127.0.0.1", port))
+            .map_err(|e| format!("Failed to bind webhook server on port {}: {}", port, e))?;
+
+        // Port 0 lets the OS pick a free port, so read back the one we actually got
+        let port = server
+            .server_addr()
+            .to_ip()
+            .map(|addr| addr.port())
+            .unwrap_or(port);
+
+        let server = Arc::new(server);
+        let routes: Arc<Mutex<RouteTable>> = Arc::new(Mutex::new(HashMap::new()));
+
+        let thread_server = Arc::clone(&server);
+        let thread_routes = Arc::clone(&routes);
+        let handle = std::thread::spawn(move || {
+            // recv() fails once the server is unblocked during shutdown
+            while let Ok(request) = thread_server.recv() {
+                handle_request(request, &thread_routes);
+            }
+        });
+
+        Ok(Arc::new(Self {
+            port,
+            server,
+            routes,
+            thread_handle: Mutex::new(Some(handle)),
+        }))
+    }
+
+    /// The port this server is listening on
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    fn shutdown(&self) {
+        self.server.unblock();
+        if let Some(handle) = self.thread_handle.lock().unwrap().take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Register a route for a webhook node, starting a server on the port if needed.
+/// Returns the port the route is reachable on.
+pub fn register_route(
+    port: u16,
+    method: &str,
+    path: &str,
+    node_id: String,
+    event_sender: Sender<ExecutionEvent>,
+    response_timeout: Option<Duration>,
+) -> Result<u16, String> {
+    let mut servers = servers().lock().unwrap();
+
+    let server = match servers.get(&port) {
+        Some(server) if port != 0 => Arc::clone(server),
+        _ => {
+            let server = WebhookServer::bind(port)?;
+            servers.insert(server.port(), Arc::clone(&server));
+            server
+        }
+    };
+
+    let key = route_key(method, path);
+    let mut routes = server.routes.lock().unwrap();
+    if let Some(existing) = routes.get(&key) {
+        return Err(format!(
+            "Route {} {} on port {} is already used by node '{}'",
+            key.0,
+            key.1,
+            server.port(),
+            existing.node_id
+        ));
+    }
+
+    routes.insert(
+        key,
+        Route {
+            node_id,
+            event_sender,
+            response_timeout,
+        },
+    );
+
+    Ok(server.port())
+}
+
+/// Remove a route, shutting the server down when it was the last one on its port
+pub fn unregister_route(port: u16, method: &str, path: &str) {
+    let mut servers = servers().lock().unwrap();
+
+    let Some(server) = servers.get(&port).cloned() else {
+        return;
+    };
+
+    let is_empty = {
+        let mut routes = server.routes.lock().unwrap();
+        routes.remove(&route_key(method, path));
+        routes.is_empty()
+    };
+
+    if is_empty {
+        servers.remove(&port);
+        server.shutdown();
+    }
+}
+
+/// Answer a held-open request. Fails if the request already timed out or was answered.
+pub fn respond(request_id: &str, response: HttpResponseData) -> Result<(), String> {
+    let sender = pending_responses()
+        .lock()
+        .unwrap()
+        .remove(request_id)
+        .ok_or_else(|| format!("No pending HTTP request with id '{}'", request_id))?;
+
+    sender
+        .send(response)
+        .map_err(|_| format!("HTTP request '{}' is no longer waiting", request_id))
+}
+
+fn route_key(method: &str, path: &str) -> (String, String) {
+    (method.to_uppercase(), normalize_path(path))
+}
+
+/// Ensure paths always start with a slash and never end with one (except the root)
+fn normalize_path(path: &str) -> String {
+    let trimmed = path.trim().trim_end_matches('/');
+    if trimmed.starts_with('/') {
+        trimmed.to_string()
+    } else {
+        format!("/{}", trimmed)
+    }
+}
+
+/// Route an incoming request to the owning node and answer the caller
+fn handle_request(request: tiny_http::Request, routes: &Mutex<RouteTable>) {
+    let method = request.method().as_str().to_uppercase();
+    let (path, query) = match request.url().split_once('?') {
+        Some((path, query)) => (normalize_path(path), query.to_string()),
+        None => (normalize_path(request.url()), String::new()),
+    };
+
+    // Copied out so the table isn't locked while the body is read
+    let route = routes
+        .lock()
+        .unwrap()
+        .get(&(method.clone(), path.clone()))
+        .cloned();
+    let Some(route) = route else {
+        let _ = request.respond(tiny_http::Response::empty(404));
+        return;
+    };
+
+    // Read the body and wait for the graph on a separate thread, so a slow client
+    // doesn't hold up the other requests on this port
+    std::thread::spawn(move || dispatch_request(request, route, method, path, query));
+}
+
+/// Send a routed request to its node and answer the caller once the graph is done with it
+fn dispatch_request(
+    mut request: tiny_http::Request,
+    route: Route,
+    method: String,
+    path: String,
+    query: String,
+) {
+    let Some(body) = read_body(&mut request) else {
+        let _ = request.respond(tiny_http::Response::empty(413));
+        return;
+    };
+
+    let request_id = uuid::Uuid::new_v4().to_string();
+    let data = HttpRequestData {
+        method,
+        path,
+        body,
+        headers: encode_headers(request.headers()),
+        query: encode_query(&query),
+        request_id: request_id.clone(),
+    };
+
+    // Register the pending response before the graph can possibly answer
+    let response_receiver = route.response_timeout.map(|timeout| {
+        let (sender, receiver) = mpsc::channel();
+        pending_responses()
+            .lock()
+            .unwrap()
+            .insert(request_id.clone(), sender);
+        (receiver, timeout)
+    });
+
+    let event = ExecutionEvent::HttpRequest {
+        node_id: route.node_id,
+        request: data,
+    };
+
+    if route.event_sender.send(event).is_err() {
+        // Engine is gone, nothing will handle this request
+        pending_responses().lock().unwrap().remove(&request_id);
+        let _ = request.respond(tiny_http::Response::empty(503));
+        return;
+    }
+
+    let Some((receiver, timeout)) = response_receiver else {
+        let _ = request.respond(tiny_http::Response::empty(202));
+        return;
+    };
+
+    let response = receiver.recv_timeout(timeout);
+    pending_responses().lock().unwrap().remove(&request_id);
+
+    match response {
+        Ok(response) => {
+            let _ = request.respond(build_response(response));
+        }
+        Err(_) => {
+            let _ = request.respond(tiny_http::Response::empty(504));
+        }
+    }
+}
+
+/// Convert a graph response into a tiny_http response
+fn build_response(response: HttpResponseData) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
+    let headers: BTreeMap<String, String> =
+        serde_json::from_str(&response.headers).unwrap_or_default();
+
+    headers.into_iter().fold(
+        tiny_http::Response::from_string(response.body).with_status_code(response.status),
+        |http_response, (name, value)| match tiny_http::Header::from_bytes(
+            name.as_bytes(),
+            value.as_bytes(),
+        ) {
+            Ok(header) => http_response.with_header(header),
+            Err(_) => http_response,
+        },
+    )
+}
+
+/// Read the request body, None when it is larger than `MAX_BODY_BYTES`
+fn read_body(request: &mut tiny_http::Request) -> Option<String> {
+    if request
+        .body_length()
+        .is_some_and(|length| length > MAX_BODY_BYTES)
+    {
+        return None;
+    }
+
+    // Content-Length may be missing (chunked) or wrong, so cap the read itself too
+    let mut bytes = Vec::new();
+    if request
+        .as_reader()
+        .take(MAX_BODY_BYTES as u64 + 1)
+        .read_to_end(&mut bytes)
+        .is_err()
+    {
+        bytes.clear();
+    }
+    if bytes.len() > MAX_BODY_BYTES {
+        return None;
+    }
+
+    Some(String::from_utf8(bytes).unwrap_or_default())
+}
+
+/// Encode headers as a JSON object (lowercase names, last value wins)
+fn encode_headers(headers: &[tiny_http::Header]) -> String {
+    let map: BTreeMap<String, String> = headers
+        .iter()
+        .map(|header| {
+            (
+                header.field.as_str().as_str().to_lowercase(),
+                header.value.as_str().to_string(),
+            )
+        })
+        .collect();
+
+    serde_json::to_string(&map).unwrap_or_else(|_| "{}".to_string())
+}
+
+/// Encode a raw query string as a JSON object
+fn encode_query(query: &str) -> String {
+    let map: BTreeMap<String, String> = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| match pair.split_once('=') {
+            Some((key, value)) => (percent_decode(key), percent_decode(value)),
+            None => (percent_decode(pair), String::new()),
+        })
+        .collect();
+
+    serde_json::to_string(&map).unwrap_or_else(|_| "{}".to_string())
+}
+
+/// Decode `+` and `%XX` escapes from a query component
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap_or("");
+                match u8::from_str_radix(hex, 16) {
+                    Ok(byte) => {
+                        decoded.push(byte);
+                        i += 2;
+                    }
+                    Err(_) => decoded.push(b'%'),
+                }
+            }
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_query() {
+        let query = encode_query("name=vla&msg=hello+world&pct=100%25&flag");
+        let map: BTreeMap<String, String> = serde_json::from_str(&query).unwrap();
+
+        assert_eq!(map["name"], "vla");
+        assert_eq!(map["msg"], "hello world");
+        assert_eq!(map["pct"], "100%");
+        assert_eq!(map["flag"], "");
+        assert_eq!(encode_query(""), "{}");
+    }
+
+    #[test]
+    fn test_respond_without_pending_request() {
+        let result = respond("missing", HttpResponseData::default());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_normalize_path() {
+        assert_eq!(normalize_path("/hook"), "/hook");
+        assert_eq!(normalize_path("hook/"), "/hook");
+        assert_eq!(normalize_path("/"), "/");
+        assert_eq!(normalize_path(""), "/");
+    }
+}
@@ -0,0 +1,625 @@
+/// C functions to run graphs from other languages, for the staticlib and cdylib builds
+///
+/// The header is generated into `include/vla.h` by the build script. An engine is created
+/// from the JSON of a graph, told who to call when node states change, started, triggered and
+/// stepped, and freed once done:
+///
+/// ```c
+/// char *error = NULL;
+/// VlaEngine *engine = vla_engine_new(json, false, &error);
+/// if (!engine) { fprintf(stderr, "%s\n", error); vla_string_free(error); return 1; }
+/// vla_engine_set_state_callback(engine, on_state, NULL);
+/// vla_engine_start(engine);
+/// vla_engine_trigger(engine, "trigger-node-id");
+/// vla_engine_stop(engine);
+/// vla_engine_free(engine);
+/// ```
+///
+/// Functions returning a `VlaStatus` other than `VLA_STATUS_OK` describe what went wrong in
+/// `vla_engine_last_error`. Panics never unwind into the caller, they are reported as
+/// `VLA_STATUS_PANICKED` (or a null engine from `vla_engine_new`).
+///
+/// A state callback may only call `vla_engine_set_state_callback`, the other functions can't
+/// be called while the engine is being used from another thread.
+use crate::engine::observer::ExecutionObserver;
+use crate::engine::{ExecutionMode, ExecutionPhase, ExecutionStateUpdate};
+use crate::runtime::{Runtime, RuntimeError, Session};
+use std::any::Any;
+use std::ffi::{c_char, c_void, CStr, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+use std::sync::{Arc, Mutex};
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VlaStatus {
+    Ok = 0,
+    /// A pointer was null or a string wasn't UTF-8
+    InvalidArgument,
+    /// The graph couldn't be parsed or has errors
+    InvalidGraph,
+    UnknownNode,
+    /// The node isn't a manual trigger
+    NotListening,
+    AlreadyRunning,
+    NotRunning,
+    /// Nodes ended the run errored
+    Failed,
+    /// The call panicked, the engine may be left in an inconsistent state
+    Panicked,
+    /// The engine's thread couldn't be started
+    SpawnFailed,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VlaPhase {
+    Waiting,
+    Queued,
+    Paused,
+    Running,
+    Completed,
+    Errored,
+}
+
+impl From<&ExecutionPhase> for VlaPhase {
+    fn from(phase: &ExecutionPhase) -> Self {
+        match phase {
+            ExecutionPhase::Waiting => VlaPhase::Waiting,
+            ExecutionPhase::Queued => VlaPhase::Queued,
+            ExecutionPhase::Paused => VlaPhase::Paused,
+            ExecutionPhase::Running => VlaPhase::Running,
+            ExecutionPhase::Completed => VlaPhase::Completed,
+            ExecutionPhase::Errored => VlaPhase::Errored,
+        }
+    }
+}
+
+/// Called with the id of a node and its new state, serialized as JSON
+///
+/// Called on the engine's threads, the strings are only valid during the call. The only
+/// function it may call on the engine is `vla_engine_set_state_callback`.
+pub type VlaStateCallback = Option<
+    unsafe extern "C" fn(
+        user_data: *mut c_void,
+        node_id: *const c_char,
+        phase: VlaPhase,
+        state_json: *const c_char,
+    ),
+>;
+
+/// A graph loaded into an engine, see `vla_engine_new`
+pub struct VlaEngine {
+    /// Shared with callbacks on the engine's threads
+    callback: Arc<CallbackObserver>,
+    engine: Engine,
+}
+
+/// What the functions called on the caller's thread use, borrowed apart from the callback
+struct Engine {
+    session: Session,
+    last_error: Option<CString>,
+}
+
+impl Engine {
+    /// Status of a result, keeping the message of an error for `vla_engine_last_error`
+    fn status(&mut self, result: Result<(), RuntimeError>) -> VlaStatus {
+        let Err(error) = result else {
+            self.last_error = None;
+            return VlaStatus::Ok;
+        };
+
+        let status = match error {
+            RuntimeError::Load(_) | RuntimeError::Invalid(_) => VlaStatus::InvalidGraph,
+            RuntimeError::UnknownNode(_) | RuntimeError::UnknownInput(_) => VlaStatus::UnknownNode,
+            RuntimeError::NotListening { .. } => VlaStatus::NotListening,
+            RuntimeError::AlreadyRunning => VlaStatus::AlreadyRunning,
+            RuntimeError::NotRunning => VlaStatus::NotRunning,
+            RuntimeError::Failed(_) => VlaStatus::Failed,
+            RuntimeError::Spawn(_) => VlaStatus::SpawnFailed,
+        };
+        self.last_error = Some(c_string(error.to_string()));
+        status
+    }
+
+    fn invalid_argument(&mut self, message: &str) -> VlaStatus {
+        self.last_error = Some(c_string(message.to_string()));
+        VlaStatus::InvalidArgument
+    }
+}
+
+/// The callback set on an engine, with the pointer it is given back
+#[derive(Clone, Copy)]
+struct StateCallback {
+    callback: unsafe extern "C" fn(*mut c_void, *const c_char, VlaPhase, *const c_char),
+    user_data: *mut c_void,
+}
+
+// What user data points to is the caller's to share between threads
+unsafe impl Send for StateCallback {}
+
+#[derive(Default)]
+struct CallbackObserver {
+    callback: Mutex<Option<StateCallback>>,
+}
+
+impl ExecutionObserver for CallbackObserver {
+    fn node_state_changed(&self, update: &ExecutionStateUpdate) {
+        // Copied out, the callback may well replace itself
+        let Some(callback) = *self.callback.lock().unwrap() else {
+            return;
+        };
+
+        let node_id = c_string(update.node_id.clone());
+        let state = c_string(serde_json::to_string(&update.state).unwrap_or_default());
+        unsafe {
+            (callback.callback)(
+                callback.user_data,
+                node_id.as_ptr(),
+                VlaPhase::from(&update.state.phase),
+                state.as_ptr(),
+            )
+        };
+    }
+}
+
+/// Run the body of a function on an engine, reporting a panic instead of unwinding into C
+///
+/// # Safety
+/// `engine` has to be null or come from `vla_engine_new`.
+unsafe fn with_engine(
+    engine: *mut VlaEngine,
+    body: impl FnOnce(&mut Engine) -> VlaStatus,
+) -> VlaStatus {
+    if engine.is_null() {
+        return VlaStatus::InvalidArgument;
+    }
+    // Only the fields of this thread, a callback may be setting the callback meanwhile
+    let engine = &mut *ptr::addr_of_mut!((*engine).engine);
+    match panic::catch_unwind(AssertUnwindSafe(|| body(engine))) {
+        Ok(status) => status,
+        Err(panic) => {
+            engine.last_error = Some(c_string(panic_message(panic)));
+            VlaStatus::Panicked
+        }
+    }
+}
+
+/// Run the body of a function, returning `fallback` if it panics
+fn or_on_panic<T>(fallback: T, body: impl FnOnce() -> T) -> T {
+    panic::catch_unwind(AssertUnwindSafe(body)).unwrap_or(fallback)
+}
+
+fn panic_message(panic: Box<dyn Any + Send>) -> String {
+    let message = match panic.downcast::<String>() {
+        Ok(message) => *message,
+        Err(panic) => panic
+            .downcast_ref::<&str>()
+            .map_or("unknown cause".to_string(), |message| message.to_string()),
+    };
+    format!("Panicked: {}", message)
+}
+
+/// A C string of a message, which can't contain nul bytes
+fn c_string(message: String) -> CString {
+    CString::new(message.replace('\0', "")).unwrap_or_default()
+}
+
+/// Borrow a C string as UTF-8, `None` when it is null or isn't UTF-8
+///
+/// # Safety
+/// The pointer has to be null or point to a nul-terminated string.
+unsafe fn str_from<'a>(string: *const c_char) -> Option<&'a str> {
+    if string.is_null() {
+        return None;
+    }
+    CStr::from_ptr(string).to_str().ok()
+}
+
+/// Create an engine for the JSON of a graph, started paused when `stepped` is set
+///
+/// Returns null when the graph can't be loaded, with the reason in `error` (when it isn't
+/// null) to be freed with `vla_string_free`.
+///
+/// # Safety
+/// `graph_json` has to be a nul-terminated string, `error` null or writable.
+#[no_mangle]
+pub unsafe extern "C" fn vla_engine_new(
+    graph_json: *const c_char,
+    stepped: bool,
+    error: *mut *mut c_char,
+) -> *mut VlaEngine {
+    let fail = |message: String| {
+        if !error.is_null() {
+            *error = c_string(message).into_raw();
+        }
+        ptr::null_mut()
+    };
+
+    let Some(json) = str_from(graph_json) else {
+        return fail("Graph JSON is null or isn't UTF-8".to_string());
+    };
+    let mode = if stepped {
+        ExecutionMode::Stepped
+    } else {
+        ExecutionMode::Normal
+    };
+    let session = panic::catch_unwind(|| Runtime::new().with_mode(mode).load_json(json));
+    let mut session = match session {
+        Ok(Ok(session)) => session,
+        Ok(Err(e)) => return fail(e.to_string()),
+        Err(panic) => return fail(panic_message(panic)),
+    };
+
+    or_on_panic(ptr::null_mut(), || {
+        let callback = Arc::new(CallbackObserver::default());
+        session.add_observer(Arc::clone(&callback) as Arc<dyn ExecutionObserver>);
+        Box::into_raw(Box::new(VlaEngine {
+            callback,
+            engine: Engine {
+                session,
+                last_error: None,
+            },
+        }))
+    })
+}
+
+/// Stop an engine and free it
+///
+/// # Safety
+/// `engine` has to be null or come from `vla_engine_new`, and isn't used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn vla_engine_free(engine: *mut VlaEngine) {
+    if !engine.is_null() {
+        // Dropping the session stops its run
+        let engine = Box::from_raw(engine);
+        or_on_panic((), || drop(engine));
+    }
+}
+
+/// Free a string returned by these functions
+///
+/// # Safety
+/// `string` has to be null or come from these functions, and isn't used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn vla_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        let string = CString::from_raw(string);
+        or_on_panic((), || drop(string));
+    }
+}
+
+/// What went wrong in the last call on an engine that didn't return `VLA_STATUS_OK`, null
+/// when it succeeded
+///
+/// The string belongs to the engine and is valid until the next call on it.
+///
+/// # Safety
+/// `engine` has to be null or come from `vla_engine_new`.
+#[no_mangle]
+pub unsafe extern "C" fn vla_engine_last_error(engine: *const VlaEngine) -> *const c_char {
+    or_on_panic(ptr::null(), || {
+        engine
+            .as_ref()
+            .and_then(|engine| engine.engine.last_error.as_ref())
+            .map_or(ptr::null(), |error| error.as_ptr())
+    })
+}
+
+/// Call `callback` with `user_data` whenever a node's state changes, null stops calling
+///
+/// Can be called from a callback, while the engine is running, and leaves
+/// `vla_engine_last_error` as it was.
+///
+/// # Safety
+/// `engine` has to come from `vla_engine_new`. `callback` can be called from any thread
+/// until it is replaced or the engine is freed.
+#[no_mangle]
+pub unsafe extern "C" fn vla_engine_set_state_callback(
+    engine: *const VlaEngine,
+    callback: VlaStateCallback,
+    user_data: *mut c_void,
+) -> VlaStatus {
+    if engine.is_null() {
+        return VlaStatus::InvalidArgument;
+    }
+    // Only the callback, the caller's thread may be using the rest of the engine meanwhile
+    let observer = &*ptr::addr_of!((*engine).callback);
+    or_on_panic(VlaStatus::Panicked, || {
+        *observer.callback.lock().unwrap() = callback.map(|callback| StateCallback {
+            callback,
+            user_data,
+        });
+        VlaStatus::Ok
+    })
+}
+
+/// Start executing the graph on a thread of its own
+///
+/// # Safety
+/// `engine` has to come from `vla_engine_new`.
+#[no_mangle]
+pub unsafe extern "C" fn vla_engine_start(engine: *mut VlaEngine) -> VlaStatus {
+    with_engine(engine, |engine| {
+        let result = engine.session.start();
+        engine.status(result)
+    })
+}
+
+/// Trigger a manual trigger node of the running graph
+///
+/// # Safety
+/// `engine` has to come from `vla_engine_new`, `node_id` has to be a nul-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn vla_engine_trigger(
+    engine: *mut VlaEngine,
+    node_id: *const c_char,
+) -> VlaStatus {
+    with_engine(engine, |engine| {
+        let Some(node_id) = str_from(node_id) else {
+            return engine.invalid_argument("Node id is null or isn't UTF-8");
+        };
+        let result = engine.session.trigger(node_id);
+        engine.status(result)
+    })
+}
+
+/// Run a debugger action on the running engine
+unsafe fn with_debugger(
+    engine: *mut VlaEngine,
+    action: impl FnOnce(&crate::engine::debugger::Debugger),
+) -> VlaStatus {
+    with_engine(engine, |engine| {
+        let result = match engine.session.debugger() {
+            Some(debugger) if engine.session.is_running() => {
+                action(&debugger);
+                Ok(())
+            }
+            _ => Err(RuntimeError::NotRunning),
+        };
+        engine.status(result)
+    })
+}
+
+/// Execute the next node, then pause again
+///
+/// # Safety
+/// `engine` has to come from `vla_engine_new`.
+#[no_mangle]
+pub unsafe extern "C" fn vla_engine_step(engine: *mut VlaEngine) -> VlaStatus {
+    with_debugger(engine, |debugger| debugger.step())
+}
+
+/// Run until the next breakpoint
+///
+/// # Safety
+/// `engine` has to come from `vla_engine_new`.
+#[no_mangle]
+pub unsafe extern "C" fn vla_engine_continue(engine: *mut VlaEngine) -> VlaStatus {
+    with_debugger(engine, |debugger| debugger.resume())
+}
+
+/// Pause before the next node
+///
+/// # Safety
+/// `engine` has to come from `vla_engine_new`.
+#[no_mangle]
+pub unsafe extern "C" fn vla_engine_pause(engine: *mut VlaEngine) -> VlaStatus {
+    with_debugger(engine, |debugger| debugger.pause())
+}
+
+/// Whether the engine was started and hasn't finished yet
+///
+/// # Safety
+/// `engine` has to be null or come from `vla_engine_new`.
+#[no_mangle]
+pub unsafe extern "C" fn vla_engine_is_running(engine: *const VlaEngine) -> bool {
+    or_on_panic(false, || {
+        engine
+            .as_ref()
+            .is_some_and(|engine| engine.engine.session.is_running())
+    })
+}
+
+/// Wait for the run to finish, `VLA_STATUS_FAILED` when nodes errored
+///
+/// # Safety
+/// `engine` has to come from `vla_engine_new`.
+#[no_mangle]
+pub unsafe extern "C" fn vla_engine_wait(engine: *mut VlaEngine) -> VlaStatus {
+    with_engine(engine, |engine| {
+        let result = engine.session.wait();
+        engine.status(result)
+    })
+}
+
+/// Stop the run, `VLA_STATUS_FAILED` when nodes errored
+///
+/// # Safety
+/// `engine` has to come from `vla_engine_new`.
+#[no_mangle]
+pub unsafe extern "C" fn vla_engine_stop(engine: *mut VlaEngine) -> VlaStatus {
+    with_engine(engine, |engine| {
+        let result = engine.session.stop();
+        engine.status(result)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bricks::debug::print_brick;
+    use crate::bricks::events::manual_trigger_brick;
+    use crate::canvas::new_node;
+    use crate::prelude::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::{Duration, Instant};
+
+    unsafe extern "C" fn count_completions(
+        user_data: *mut c_void,
+        _node_id: *const c_char,
+        phase: VlaPhase,
+        state_json: *const c_char,
+    ) {
+        let state = CStr::from_ptr(state_json).to_str().unwrap();
+        assert!(state.contains("\"phase\""));
+        if phase == VlaPhase::Completed {
+            (*(user_data as *const AtomicUsize)).fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    /// Engine and call count handed to `unset_callback`
+    struct Unsetting {
+        engine: *mut VlaEngine,
+        calls: AtomicUsize,
+    }
+
+    unsafe extern "C" fn unset_callback(
+        user_data: *mut c_void,
+        _node_id: *const c_char,
+        _phase: VlaPhase,
+        _state_json: *const c_char,
+    ) {
+        let unsetting = &*(user_data as *const Unsetting);
+        vla_engine_set_state_callback(unsetting.engine, None, ptr::null_mut());
+        unsetting.calls.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn trigger_graph() -> (Graph, String) {
+        let trigger = new_node(manual_trigger_brick(), Point { x: 0.0, y: 0.0 });
+        let trigger_id = trigger.id.clone();
+        let graph = Graph {
+            nodes: vec![trigger],
+            edges: vec![],
+        };
+        (graph, trigger_id)
+    }
+
+    #[test]
+    fn test_callback_can_replace_itself() {
+        let (graph, trigger_id) = trigger_graph();
+        let json = CString::new(serde_json::to_string(&graph).unwrap()).unwrap();
+        let trigger_id = CString::new(trigger_id).unwrap();
+
+        unsafe {
+            let engine = vla_engine_new(json.as_ptr(), false, ptr::null_mut());
+            let unsetting = Unsetting {
+                engine,
+                calls: AtomicUsize::new(0),
+            };
+            let user_data = &unsetting as *const Unsetting as *mut c_void;
+            vla_engine_set_state_callback(engine, Some(unset_callback), user_data);
+
+            assert_eq!(vla_engine_start(engine), VlaStatus::Ok);
+            assert_eq!(
+                vla_engine_trigger(engine, trigger_id.as_ptr()),
+                VlaStatus::Ok
+            );
+
+            // Called once, then never again
+            let started = Instant::now();
+            while unsetting.calls.load(Ordering::SeqCst) == 0
+                && started.elapsed() < Duration::from_secs(5)
+            {
+                std::thread::sleep(Duration::from_millis(10));
+            }
+            std::thread::sleep(Duration::from_millis(50));
+            assert_eq!(unsetting.calls.load(Ordering::SeqCst), 1);
+
+            assert_eq!(vla_engine_stop(engine), VlaStatus::Ok);
+            vla_engine_free(engine);
+        }
+    }
+
+    #[test]
+    fn test_panics_become_a_status() {
+        let (graph, _) = trigger_graph();
+        let json = CString::new(serde_json::to_string(&graph).unwrap()).unwrap();
+
+        unsafe {
+            let engine = vla_engine_new(json.as_ptr(), false, ptr::null_mut());
+            let status = with_engine(engine, |_| panic!("out of bricks"));
+            assert_eq!(status, VlaStatus::Panicked);
+            let error = CStr::from_ptr(vla_engine_last_error(engine));
+            assert_eq!(error.to_str().unwrap(), "Panicked: out of bricks");
+
+            assert_eq!(vla_engine_start(engine), VlaStatus::Ok);
+            assert_eq!(vla_engine_stop(engine), VlaStatus::Ok);
+            vla_engine_free(engine);
+        }
+    }
+
+    #[test]
+    fn test_engine_lifecycle() {
+        let trigger = new_node(manual_trigger_brick(), Point { x: 0.0, y: 0.0 });
+        let mut print = new_node(print_brick(), Point { x: 0.0, y: 0.0 });
+        print
+            .data
+            .defaults
+            .insert("value".to_string(), "\"hello\"".to_string());
+        let edge = Edge {
+            id: "edge".to_string(),
+            source: trigger.id.clone(),
+            target: print.id.clone(),
+            source_handle: "triggered".to_string(),
+            target_handle: "execute".to_string(),
+        };
+        let graph = Graph {
+            nodes: vec![trigger.clone(), print.clone()],
+            edges: vec![edge],
+        };
+        let json = CString::new(serde_json::to_string(&graph).unwrap()).unwrap();
+        let trigger_id = CString::new(trigger.id).unwrap();
+        let print_id = CString::new(print.id).unwrap();
+        let completions = AtomicUsize::new(0);
+
+        unsafe {
+            let mut error = ptr::null_mut();
+            let invalid = CString::new("not a graph").unwrap();
+            assert!(vla_engine_new(invalid.as_ptr(), false, &mut error).is_null());
+            assert!(!error.is_null());
+            vla_string_free(error);
+
+            let engine = vla_engine_new(json.as_ptr(), false, ptr::null_mut());
+            assert!(!engine.is_null());
+            let user_data = &completions as *const AtomicUsize as *mut c_void;
+            assert_eq!(
+                vla_engine_set_state_callback(engine, Some(count_completions), user_data),
+                VlaStatus::Ok
+            );
+
+            assert_eq!(
+                vla_engine_trigger(engine, trigger_id.as_ptr()),
+                VlaStatus::NotRunning
+            );
+            assert!(!vla_engine_last_error(engine).is_null());
+            // Setting the callback leaves the error of the last call
+            vla_engine_set_state_callback(engine, Some(count_completions), user_data);
+            assert!(!vla_engine_last_error(engine).is_null());
+            assert_eq!(vla_engine_start(engine), VlaStatus::Ok);
+            assert!(vla_engine_last_error(engine).is_null());
+            assert_eq!(
+                vla_engine_trigger(engine, print_id.as_ptr()),
+                VlaStatus::NotListening
+            );
+            assert_eq!(
+                vla_engine_trigger(engine, trigger_id.as_ptr()),
+                VlaStatus::Ok
+            );
+
+            // The trigger and the print node complete
+            let started = Instant::now();
+            while completions.load(Ordering::SeqCst) < 2
+                && started.elapsed() < Duration::from_secs(5)
+            {
+                std::thread::sleep(Duration::from_millis(10));
+            }
+            assert_eq!(completions.load(Ordering::SeqCst), 2);
+
+            assert!(vla_engine_is_running(engine));
+            assert_eq!(vla_engine_stop(engine), VlaStatus::Ok);
+            assert_eq!(vla_engine_step(engine), VlaStatus::NotRunning);
+            vla_engine_free(engine);
+        }
+    }
+}
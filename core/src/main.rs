@@ -1,13 +1,50 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
-use vla_lib::prelude::*;
+use std::process::ExitCode;
+use vla_lib::cli;
 
-fn main() {
-    run()
+fn main() -> ExitCode {
+    // `vla run graph.json` and friends run headless, anything else opens the editor
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if cli::Command::parse(&args).is_some() {
+        attach_console();
+    }
+    if let Some(exit_code) = cli::main(&args) {
+        return exit_code;
+    }
+
+    #[cfg(feature = "tauri")]
+    {
+        run();
+        ExitCode::SUCCESS
+    }
+    #[cfg(not(feature = "tauri"))]
+    {
+        eprintln!("This build of vla has no editor, see `vla help` for the commands");
+        ExitCode::FAILURE
+    }
+}
+
+/// Release builds on Windows have no console of their own, write to the terminal
+/// the command line was started from instead
+#[cfg(all(windows, not(debug_assertions)))]
+fn attach_console() {
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+    extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+    }
+    // Fails when started without a terminal, there is nothing to write to then
+    unsafe { AttachConsole(ATTACH_PARENT_PROCESS) };
 }
 
+#[cfg(not(all(windows, not(debug_assertions))))]
+fn attach_console() {}
+
+#[cfg(feature = "tauri")]
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 fn run() {
+    use vla_lib::prelude::*;
+
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .invoke_handler(taurpc::create_ipc_handler(CoreApiImpl.into_handler()))
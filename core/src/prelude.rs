@@ -1,3 +1,7 @@
+#[cfg(feature = "tauri")]
 pub use crate::api::{CoreApi, CoreApiImpl};
+pub use crate::bricks::context::BrickContext;
+pub use crate::bricks::error::BrickError;
 pub use crate::bricks::types::Brick;
+pub use crate::bricks::value::Value;
 pub use crate::canvas::{Edge, Graph, Node, NodeData, Point};
@@ -0,0 +1,596 @@
+/// Running graphs from Rust code
+///
+/// A `Runtime` holds the settings graphs are executed with and loads them into sessions.
+/// A `Session` is one loaded graph: supply its inputs, subscribe to node outputs, start it,
+/// send it events and wait for it to finish (or stop it).
+///
+/// ```no_run
+/// use vla_lib::runtime::Runtime;
+///
+/// let mut session = Runtime::new().load("graph.json")?;
+/// session.set_input("name", "world")?;
+/// session.start()?;
+/// session.wait()?;
+/// # Ok::<(), vla_lib::runtime::RuntimeError>(())
+/// ```
+use crate::bricks::ports;
+use crate::bricks::registry::BrickRegistry;
+use crate::bricks::types::{BrickEmissionType, BrickOutputValue};
+use crate::engine::debugger::Debugger;
+use crate::engine::events::{ExecutionEvent, HttpRequestData};
+use crate::engine::observer::ExecutionObserver;
+use crate::engine::pool;
+use crate::engine::validation::{self, Diagnostic};
+use crate::engine::{
+    Engine, ExecutionMode, ExecutionPhase, ExecutionStateUpdate, NodeExecutionState,
+};
+use crate::prelude::*;
+use crate::subgraph;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RuntimeError {
+    /// The graph couldn't be read or parsed, or its subgraphs couldn't be expanded
+    Load(String),
+    /// The graph has errors that keep it from being executed
+    Invalid(Vec<Diagnostic>),
+    /// The graph has no node with this id
+    UnknownNode(String),
+    /// The graph has no input with this name
+    UnknownInput(String),
+    /// The node doesn't listen for the kind of event it was sent
+    NotListening {
+        node_id: String,
+        event: &'static str,
+    },
+    /// The session was already started and hasn't finished yet
+    AlreadyRunning,
+    /// The session isn't running, it wasn't started or has finished
+    NotRunning,
+    /// Nodes ended the run errored
+    Failed(Vec<NodeFailure>),
+    /// The thread executing the graph couldn't be started
+    Spawn(String),
+}
+
+/// A node that errored, with what went wrong
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeFailure {
+    pub node_id: String,
+    pub message: String,
+}
+
+impl fmt::Display for RuntimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuntimeError::Load(message) => write!(f, "{}", message),
+            RuntimeError::Invalid(diagnostics) => {
+                let errors: Vec<&str> = diagnostics
+                    .iter()
+                    .filter(|diagnostic| diagnostic.is_error())
+                    .map(|diagnostic| diagnostic.message.as_str())
+                    .collect();
+                write!(f, "Graph can't be executed: {}", errors.join(", "))
+            }
+            RuntimeError::UnknownNode(node_id) => write!(f, "Unknown node '{}'", node_id),
+            RuntimeError::UnknownInput(name) => write!(f, "Unknown graph input '{}'", name),
+            RuntimeError::NotListening { node_id, event } => {
+                write!(f, "Node '{}' doesn't listen for {} events", node_id, event)
+            }
+            RuntimeError::AlreadyRunning => write!(f, "Session is already running"),
+            RuntimeError::NotRunning => write!(f, "Session isn't running"),
+            RuntimeError::Failed(failures) => {
+                let failures: Vec<String> = failures
+                    .iter()
+                    .map(|failure| format!("{}: {}", failure.node_id, failure.message))
+                    .collect();
+                write!(f, "Run failed: {}", failures.join(", "))
+            }
+            RuntimeError::Spawn(message) => write!(f, "Failed to start the engine: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for RuntimeError {}
+
+/// Load a graph file, with its subgraphs (stored next to it) expanded and bricks attached
+pub fn load_graph(path: &Path) -> Result<Graph, RuntimeError> {
+    let json = fs::read_to_string(path)
+        .map_err(|e| RuntimeError::Load(format!("Failed to read '{}': {}", path.display(), e)))?;
+    let graph = Graph::from_json(json).map_err(RuntimeError::Load)?;
+
+    prepare(graph, Some(&subgraph::directory_of(path)))
+}
+
+/// Expand the subgraphs of a graph and attach the bricks of its nodes
+fn prepare(graph: Graph, subgraph_directory: Option<&Path>) -> Result<Graph, RuntimeError> {
+    let mut graph = subgraph::expand(graph, subgraph_directory).map_err(RuntimeError::Load)?;
+    for node in &mut graph.nodes {
+        if node.data.brick.is_none() {
+            node.data.brick = BrickRegistry::global().get(&node.data.brick_id);
+        }
+    }
+    Ok(graph)
+}
+
+/// Settings graphs are executed with, and where they get loaded from
+#[derive(Debug, Clone)]
+pub struct Runtime {
+    debug: bool,
+    mode: ExecutionMode,
+    max_concurrent_waves: usize,
+    subgraph_directory: Option<PathBuf>,
+}
+
+impl Default for Runtime {
+    fn default() -> Self {
+        Self {
+            debug: false,
+            mode: ExecutionMode::Normal,
+            max_concurrent_waves: pool::DEFAULT_MAX_CONCURRENT_WAVES,
+            subgraph_directory: None,
+        }
+    }
+}
+
+impl Runtime {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Print what the engine does to stdout
+    pub fn with_debug(mut self, debug: bool) -> Self {
+        self.debug = debug;
+        self
+    }
+
+    /// Start sessions paused, to step through them with their debugger
+    pub fn with_mode(mut self, mode: ExecutionMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Limit how many event waves of a session run at the same time
+    pub fn with_max_concurrent_waves(mut self, max: usize) -> Self {
+        self.max_concurrent_waves = max;
+        self
+    }
+
+    /// Where the subgraphs of graphs not loaded from a file are stored, without one they can't
+    /// contain subgraph nodes
+    pub fn with_subgraph_directory(mut self, directory: impl Into<PathBuf>) -> Self {
+        self.subgraph_directory = Some(directory.into());
+        self
+    }
+
+    /// Load a graph file into a session, its subgraphs are stored next to it
+    pub fn load(&self, path: impl AsRef<Path>) -> Result<Session, RuntimeError> {
+        self.open(load_graph(path.as_ref())?)
+    }
+
+    /// Load a graph from its JSON into a session
+    pub fn load_json(&self, json: &str) -> Result<Session, RuntimeError> {
+        let graph = Graph::from_json(json.to_string()).map_err(RuntimeError::Load)?;
+        self.session(graph)
+    }
+
+    /// Create a session for a graph
+    pub fn session(&self, graph: Graph) -> Result<Session, RuntimeError> {
+        self.open(prepare(graph, self.subgraph_directory.as_deref())?)
+    }
+
+    /// Create a session for a prepared graph, refusing graphs that can't run
+    fn open(&self, graph: Graph) -> Result<Session, RuntimeError> {
+        let diagnostics = validation::validate(&graph);
+        if validation::has_errors(&diagnostics) {
+            return Err(RuntimeError::Invalid(diagnostics));
+        }
+
+        Ok(Session {
+            graph,
+            runtime: self.clone(),
+            state: Arc::new(SessionState::default()),
+            observers: Vec::new(),
+            run: None,
+        })
+    }
+}
+
+/// One graph loaded into a runtime, see the module documentation
+pub struct Session {
+    graph: Graph,
+    runtime: Runtime,
+    state: Arc<SessionState>,
+    observers: Vec<Arc<dyn ExecutionObserver>>,
+    run: Option<Run>,
+}
+
+/// The engine of a started session, running on its own thread
+struct Run {
+    stop: Arc<AtomicBool>,
+    events: Sender<ExecutionEvent>,
+    debugger: Arc<Debugger>,
+    thread: JoinHandle<()>,
+}
+
+impl Session {
+    /// The graph this session runs, with its subgraphs expanded
+    pub fn graph(&self) -> &Graph {
+        &self.graph
+    }
+
+    /// Names of the graph's inputs: its subgraph input nodes
+    pub fn inputs(&self) -> Vec<String> {
+        self.input_nodes().map(subgraph::port_name).collect()
+    }
+
+    /// Give a graph input a value, used from the next `start`
+    pub fn set_input(&mut self, name: &str, value: impl Into<Value>) -> Result<(), RuntimeError> {
+        // Stored like a value typed into the node, which the input's `Any` type parses back
+        let raw = value.into().to_json().to_string();
+
+        let mut found = false;
+        for node in &mut self.graph.nodes {
+            if node.data.brick_id == ports::INPUT && subgraph::port_name(node) == name {
+                node.data.defaults.insert("value".to_string(), raw.clone());
+                found = true;
+            }
+        }
+
+        if found {
+            Ok(())
+        } else {
+            Err(RuntimeError::UnknownInput(name.to_string()))
+        }
+    }
+
+    fn input_nodes(&self) -> impl Iterator<Item = &Node> {
+        self.graph
+            .nodes
+            .iter()
+            .filter(|node| node.data.brick_id == ports::INPUT)
+    }
+
+    /// Receive the outputs of a node every time it completes
+    pub fn subscribe(
+        &self,
+        node_id: &str,
+    ) -> Result<Receiver<Vec<BrickOutputValue>>, RuntimeError> {
+        self.node(node_id)?;
+
+        let (sender, receiver) = mpsc::channel();
+        self.state
+            .subscribers
+            .lock()
+            .unwrap()
+            .entry(node_id.to_string())
+            .or_default()
+            .push(sender);
+        Ok(receiver)
+    }
+
+    /// Tell an observer about everything the session's engine does, from the next `start`
+    pub fn add_observer(&mut self, observer: Arc<dyn ExecutionObserver>) {
+        self.observers.push(observer);
+    }
+
+    /// Latest state of a node
+    pub fn node_state(&self, node_id: &str) -> Option<NodeExecutionState> {
+        self.state.states.lock().unwrap().get(node_id).cloned()
+    }
+
+    /// Outputs of a node's latest completion
+    pub fn outputs(&self, node_id: &str) -> Option<Vec<BrickOutputValue>> {
+        self.node_state(node_id).and_then(|state| state.outputs)
+    }
+
+    /// Debugger of the running engine, to step through or pause it
+    pub fn debugger(&self) -> Option<Arc<Debugger>> {
+        self.run.as_ref().map(|run| Arc::clone(&run.debugger))
+    }
+
+    /// Execute the graph on a thread of its own
+    ///
+    /// The run goes on until nothing is left to execute, which for graphs with listeners
+    /// (timers, webhooks, manual triggers) is when it is stopped.
+    pub fn start(&mut self) -> Result<(), RuntimeError> {
+        if self.is_running() {
+            return Err(RuntimeError::AlreadyRunning);
+        }
+        if let Some(run) = self.run.take() {
+            let _ = run.thread.join();
+        }
+        self.state.states.lock().unwrap().clear();
+
+        let mut engine = Engine::with_debug(self.graph.clone(), self.runtime.debug);
+        engine.set_max_concurrent_waves(self.runtime.max_concurrent_waves);
+        engine.set_execution_mode(self.runtime.mode.clone());
+        engine.add_observer(Arc::clone(&self.state) as Arc<dyn ExecutionObserver>);
+        for observer in &self.observers {
+            engine.add_observer(Arc::clone(observer));
+        }
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let (event_sender, event_receiver) = mpsc::channel();
+        let debugger = engine.debugger();
+
+        let stopped = Arc::clone(&stop);
+        let events = event_sender.clone();
+        let thread = std::thread::Builder::new()
+            .name("vla-session".to_string())
+            .spawn(move || {
+                engine.start_with_event_channel(event_receiver, events);
+                // Node errors end up in the node states, the session reports them from there
+                for _ in &mut engine {
+                    if stopped.load(Ordering::SeqCst) {
+                        break;
+                    }
+                }
+                // Engine dropped here, which stops all listeners
+            })
+            .map_err(|e| RuntimeError::Spawn(e.to_string()))?;
+
+        self.run = Some(Run {
+            stop,
+            events: event_sender,
+            debugger,
+            thread,
+        });
+        Ok(())
+    }
+
+    /// Whether the session was started and hasn't finished yet
+    pub fn is_running(&self) -> bool {
+        self.run
+            .as_ref()
+            .is_some_and(|run| !run.thread.is_finished())
+    }
+
+    /// Trigger a manual trigger node
+    pub fn trigger(&self, node_id: &str) -> Result<(), RuntimeError> {
+        self.expect_emission(node_id, "manual trigger", |emission| {
+            matches!(emission, BrickEmissionType::ManualTrigger)
+        })?;
+        self.send(ExecutionEvent::manual_trigger(node_id))
+    }
+
+    /// Hand a webhook node a request, as if it was received over HTTP
+    pub fn send_http_request(
+        &self,
+        node_id: &str,
+        request: HttpRequestData,
+    ) -> Result<(), RuntimeError> {
+        self.expect_emission(node_id, "HTTP request", |emission| {
+            matches!(emission, BrickEmissionType::HttpWebhook { .. })
+        })?;
+        self.send(ExecutionEvent::HttpRequest {
+            node_id: node_id.to_string(),
+            request,
+        })
+    }
+
+    /// Send any event, it starts a wave at the node it targets
+    pub fn send(&self, event: ExecutionEvent) -> Result<(), RuntimeError> {
+        self.node(event.target_node_id())?;
+
+        let run = self.run.as_ref().ok_or(RuntimeError::NotRunning)?;
+        // The receiver is gone once the engine is
+        run.events.send(event).map_err(|_| RuntimeError::NotRunning)
+    }
+
+    /// Wait for the run to finish
+    pub fn wait(&mut self) -> Result<(), RuntimeError> {
+        let run = self.run.take().ok_or(RuntimeError::NotRunning)?;
+        let _ = run.thread.join();
+        self.result()
+    }
+
+    /// Stop the run, waiting for nodes that are executing
+    pub fn stop(&mut self) -> Result<(), RuntimeError> {
+        let run = self.run.as_ref().ok_or(RuntimeError::NotRunning)?;
+        run.stop.store(true, Ordering::SeqCst);
+        self.wait()
+    }
+
+    /// How the finished run went, based on the states its nodes ended in
+    fn result(&self) -> Result<(), RuntimeError> {
+        let states = self.state.states.lock().unwrap();
+        let mut failures: Vec<NodeFailure> = states
+            .iter()
+            .filter(|(_, state)| state.phase == ExecutionPhase::Errored)
+            .map(|(node_id, state)| NodeFailure {
+                node_id: node_id.clone(),
+                message: state
+                    .error_message
+                    .clone()
+                    .unwrap_or_else(|| "unknown error".to_string()),
+            })
+            .collect();
+
+        if failures.is_empty() {
+            return Ok(());
+        }
+        failures.sort_by(|a, b| a.node_id.cmp(&b.node_id));
+        Err(RuntimeError::Failed(failures))
+    }
+
+    fn node(&self, node_id: &str) -> Result<&Node, RuntimeError> {
+        self.graph
+            .nodes
+            .iter()
+            .find(|node| node.id == node_id)
+            .ok_or_else(|| RuntimeError::UnknownNode(node_id.to_string()))
+    }
+
+    /// Check that a node's brick listens for an event
+    fn expect_emission(
+        &self,
+        node_id: &str,
+        event: &'static str,
+        listens: impl Fn(&BrickEmissionType) -> bool,
+    ) -> Result<(), RuntimeError> {
+        let node = self.node(node_id)?;
+        if node
+            .data
+            .brick
+            .as_ref()
+            .is_some_and(|brick| listens(&brick.emission_type))
+        {
+            Ok(())
+        } else {
+            Err(RuntimeError::NotListening {
+                node_id: node_id.to_string(),
+                event,
+            })
+        }
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        if self.run.is_some() {
+            let _ = self.stop();
+        }
+    }
+}
+
+/// Node states of a session's run, and who to send node outputs to
+#[derive(Default)]
+struct SessionState {
+    states: Mutex<HashMap<String, NodeExecutionState>>,
+    subscribers: Mutex<HashMap<String, Vec<Sender<Vec<BrickOutputValue>>>>>,
+}
+
+impl ExecutionObserver for SessionState {
+    fn node_state_changed(&self, update: &ExecutionStateUpdate) {
+        self.states
+            .lock()
+            .unwrap()
+            .insert(update.node_id.clone(), update.state.clone());
+
+        let (ExecutionPhase::Completed, Some(outputs)) =
+            (&update.state.phase, &update.state.outputs)
+        else {
+            return;
+        };
+        if let Some(subscribers) = self.subscribers.lock().unwrap().get_mut(&update.node_id) {
+            // Dropped receivers unsubscribe
+            subscribers.retain(|subscriber| subscriber.send(outputs.clone()).is_ok());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bricks::arithmetics::add_brick;
+    use crate::bricks::conversions::string_to_number_brick;
+    use crate::bricks::debug::print_brick;
+    use crate::bricks::events::manual_trigger_brick;
+    use crate::bricks::ports::subgraph_input_brick;
+    use crate::engine::observer::RecordingObserver;
+    use crate::test_utils::{edge, node, with_arguments, with_defaults};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_run_and_subscribe() {
+        let graph = Graph {
+            nodes: vec![with_defaults(
+                node("add", add_brick()),
+                &[("a", "2"), ("b", "3")],
+            )],
+            edges: vec![],
+        };
+
+        let mut session = Runtime::new().session(graph).unwrap();
+        let outputs = session.subscribe("add").unwrap();
+        assert_eq!(
+            session.subscribe("missing").err(),
+            Some(RuntimeError::UnknownNode("missing".to_string()))
+        );
+        assert_eq!(session.wait(), Err(RuntimeError::NotRunning));
+
+        session.start().unwrap();
+        assert_eq!(session.wait(), Ok(()));
+
+        let received = outputs.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(received[0].value, Value::Number(5.0));
+        assert_eq!(session.outputs("add").unwrap()[0].value, Value::Number(5.0));
+    }
+
+    #[test]
+    fn test_inputs_and_triggers() {
+        let graph = Graph {
+            nodes: vec![
+                node("trigger", manual_trigger_brick()),
+                with_arguments(
+                    node("input", subgraph_input_brick()),
+                    &[("name", "\"greeting\"")],
+                ),
+                node("print", print_brick()),
+            ],
+            edges: vec![
+                edge("trigger", "triggered", "print", "execute"),
+                edge("input", "value", "print", "value"),
+            ],
+        };
+
+        let mut session = Runtime::new().session(graph).unwrap();
+        assert_eq!(session.inputs(), vec!["greeting"]);
+        session.set_input("greeting", "hello").unwrap();
+        assert_eq!(
+            session.set_input("farewell", "bye"),
+            Err(RuntimeError::UnknownInput("farewell".to_string()))
+        );
+        assert_eq!(session.trigger("trigger"), Err(RuntimeError::NotRunning));
+
+        let recording = Arc::new(RecordingObserver::new());
+        session.add_observer(recording.clone());
+        session.start().unwrap();
+        assert_eq!(session.start(), Err(RuntimeError::AlreadyRunning));
+        assert!(matches!(
+            session.trigger("print"),
+            Err(RuntimeError::NotListening { .. })
+        ));
+        session.trigger("trigger").unwrap();
+
+        // The manual trigger keeps the session running until it is stopped
+        let started = Instant::now();
+        while recording.logs("print").is_empty() && started.elapsed() < Duration::from_secs(5) {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert!(session.is_running());
+        assert_eq!(session.stop(), Ok(()));
+        assert_eq!(recording.logs("print"), vec!["hello"]);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(matches!(
+            Runtime::new().load("/definitely/not/a/graph.json"),
+            Err(RuntimeError::Load(_))
+        ));
+
+        let graph = Graph {
+            nodes: vec![with_defaults(
+                node("parse", string_to_number_brick()),
+                &[("text", "\"five\"")],
+            )],
+            edges: vec![],
+        };
+
+        let mut session = Runtime::new().session(graph).unwrap();
+        session.start().unwrap();
+        let Err(RuntimeError::Failed(failures)) = session.wait() else {
+            panic!("Parsing text that isn't a number should fail the run");
+        };
+        assert_eq!(failures[0].node_id, "parse");
+    }
+}
@@ -0,0 +1,696 @@
+/// Subgraphs: user-defined bricks composed from other bricks, stored as files
+///
+/// The interface of a subgraph is declared by the port nodes inside it (see `bricks::ports`),
+/// every port becomes an input or output of the subgraph's brick, named after the port. The
+/// engine doesn't know about subgraphs: `expand` replaces every subgraph node with the nodes
+/// of its subgraph before execution, connecting the edges of the subgraph node to its ports.
+use crate::bricks::types::{
+    BrickArgumentValue, BrickEmissionType, BrickExecutionInput, BrickExecutionOutput, BrickInput,
+    BrickInputValue, BrickOutput, BrickOutputValue, ConnectionType, FIRST_VERSION,
+};
+use crate::bricks::{self, ports};
+use crate::canvas;
+use crate::engine::validation;
+use crate::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// Name of the subgraph directory, which sits next to the graph file
+pub const DIRECTORY_NAME: &str = "subgraphs";
+/// Brick ids of subgraphs are their id with this prefix, so they can't clash with built-ins
+pub const BRICK_ID_PREFIX: &str = "subgraph:";
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "tauri", derive(specta::Type))]
+pub struct Subgraph {
+    /// Name of the file the subgraph is stored in
+    pub id: String,
+    pub label: String,
+    #[serde(default)]
+    pub description: String,
+    pub graph: Graph,
+}
+
+/// Where the subgraphs of a graph file are stored
+pub fn directory_of(graph_path: &Path) -> PathBuf {
+    graph_path
+        .parent()
+        .unwrap_or(Path::new("."))
+        .join(DIRECTORY_NAME)
+}
+
+impl Subgraph {
+    /// The brick subgraph nodes use, with an input or output for every port node
+    pub fn brick(&self) -> Brick {
+        let builtins = bricks::all_bricks();
+        let brick = |node: &Node| builtins.iter().find(|brick| brick.id == node.data.brick_id);
+
+        // Ports take the type of the handle they are connected to inside the subgraph
+        let input_type = |port: &Node| {
+            self.graph
+                .edges
+                .iter()
+                .filter(|edge| edge.source == port.id)
+                .find_map(|edge| {
+                    let target = self.node(&edge.target)?;
+                    validation::input_type(brick(target)?, &edge.target_handle)
+                })
+                .unwrap_or(ConnectionType::Any)
+        };
+        let output_type = |port: &Node| {
+            self.graph
+                .edges
+                .iter()
+                .filter(|edge| edge.target == port.id)
+                .find_map(|edge| {
+                    let source = self.node(&edge.source)?;
+                    validation::output_type(brick(source)?, &edge.source_handle)
+                })
+                .unwrap_or(ConnectionType::Any)
+        };
+
+        Brick {
+            id: format!("{}{}", BRICK_ID_PREFIX, self.id),
+            label: self.label.clone(),
+            description: self.description.clone(),
+            keywords: vec!["subgraph".to_string()],
+            category: "Subgraphs".to_string(),
+            version: FIRST_VERSION,
+            migrations: vec![],
+            arguments: vec![],
+            inputs: self
+                .ports(ports::INPUT)
+                .map(|port| BrickInput {
+                    id: port_name(port),
+                    label: port_name(port),
+                    r#type: input_type(port),
+                    default_value: port.data.defaults.get("value").cloned(),
+                })
+                .collect(),
+            outputs: self
+                .ports(ports::OUTPUT)
+                .map(|port| BrickOutput {
+                    id: port_name(port),
+                    label: port_name(port),
+                    r#type: output_type(port),
+                })
+                .collect(),
+            execution_inputs: self
+                .ports(ports::ENTRY)
+                .map(|port| BrickExecutionInput {
+                    id: port_name(port),
+                    label: port_name(port),
+                })
+                .collect(),
+            execution_outputs: self
+                .ports(ports::EXIT)
+                .map(|port| BrickExecutionOutput {
+                    id: port_name(port),
+                    label: port_name(port),
+                })
+                .collect(),
+            emission_type: BrickEmissionType::FlowTriggered,
+            execution: unexpanded,
+            async_execution: None,
+        }
+    }
+
+    fn node(&self, node_id: &str) -> Option<&Node> {
+        self.graph.nodes.iter().find(|node| node.id == node_id)
+    }
+
+    fn ports<'a>(&'a self, brick_id: &'a str) -> impl Iterator<Item = &'a Node> {
+        self.graph
+            .nodes
+            .iter()
+            .filter(move |node| node.data.brick_id == brick_id)
+    }
+}
+
+/// Subgraph nodes are replaced before execution, this only runs when that was skipped
+fn unexpanded(
+    _context: &BrickContext,
+    _args: Vec<BrickArgumentValue>,
+    _inputs: Vec<BrickInputValue>,
+) -> Result<Vec<BrickOutputValue>, BrickError> {
+    Err(BrickError::failed(
+        "subgraph nodes have to be expanded before execution",
+    ))
+}
+
+/// Name of a port node, falling back to its id when it has none
+pub(crate) fn port_name(port: &Node) -> String {
+    port.data
+        .arguments
+        .get("name")
+        .map(|raw| serde_json::from_str::<String>(raw).unwrap_or_else(|_| raw.clone()))
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| port.id.clone())
+}
+
+fn path(directory: &Path, id: &str) -> Result<std::path::PathBuf, String> {
+    let valid = !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(format!(
+            "Subgraph id '{}' may only contain letters, digits, '-' and '_'",
+            id
+        ));
+    }
+    Ok(directory.join(format!("{}.json", id)))
+}
+
+/// Load a subgraph, without attaching bricks to its nodes
+///
+/// Nodes of built-in bricks are migrated, nodes of other subgraphs are migrated when those get
+/// loaded to be expanded.
+pub fn load(directory: &Path, id: &str) -> Result<Subgraph, String> {
+    let json = fs::read_to_string(path(directory, id)?)
+        .map_err(|e| format!("Failed to read subgraph '{}': {}", id, e))?;
+    let mut subgraph: Subgraph = serde_json::from_str(&json)
+        .map_err(|e| format!("Failed to parse subgraph '{}': {}", id, e))?;
+
+    let builtins = bricks::all_bricks();
+    subgraph
+        .graph
+        .migrate(|brick_id| builtins.iter().find(|brick| brick.id == brick_id).cloned())
+        .map_err(|e| format!("Failed to migrate subgraph '{}': {}", id, e))?;
+
+    Ok(subgraph)
+}
+
+/// Every subgraph in the directory, skipping files that aren't subgraphs
+pub fn load_all(directory: &Path) -> Vec<Subgraph> {
+    let Ok(entries) = fs::read_dir(directory) else {
+        return vec![];
+    };
+
+    let mut subgraphs: Vec<Subgraph> = entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let id = path.file_stem()?.to_str()?;
+            if path.extension()? != "json" {
+                return None;
+            }
+            load(directory, id).ok()
+        })
+        .collect();
+    subgraphs.sort_by(|a, b| a.id.cmp(&b.id));
+    subgraphs
+}
+
+pub fn save(directory: &Path, subgraph: &Subgraph) -> Result<(), String> {
+    let path = path(directory, &subgraph.id)?;
+
+    // Bricks are attached again when loading, like in graph files
+    let mut json = serde_json::to_value(subgraph)
+        .map_err(|e| format!("Failed to serialize subgraph: {}", e))?;
+    if let Some(nodes) = json
+        .pointer_mut("/graph/nodes")
+        .and_then(|n| n.as_array_mut())
+    {
+        for node in nodes {
+            if let Some(data) = node.get_mut("data").and_then(|d| d.as_object_mut()) {
+                data.remove("brick");
+            }
+        }
+    }
+    let json =
+        serde_json::to_string_pretty(&json).map_err(|e| format!("Failed to format JSON: {}", e))?;
+
+    fs::create_dir_all(directory)
+        .map_err(|e| format!("Failed to create subgraph directory: {}", e))?;
+    fs::write(path, json).map_err(|e| format!("Failed to write subgraph: {}", e))
+}
+
+/// Bricks of the subgraphs stored in a directory
+pub fn bricks(directory: &Path) -> Vec<Brick> {
+    load_all(directory).iter().map(Subgraph::brick).collect()
+}
+
+/// Replace every subgraph node with the nodes of its subgraph, stored in `directory`
+///
+/// Nodes taken from a subgraph get the id of the subgraph node as prefix: `node/inner`. Graphs
+/// without a subgraph directory can't contain subgraph nodes.
+pub fn expand(graph: Graph, directory: Option<&Path>) -> Result<Graph, String> {
+    let load = |id: &str| match directory {
+        Some(directory) => load(directory, id),
+        None => Err(format!(
+            "Subgraph '{}' can't be loaded, there is no subgraph directory",
+            id
+        )),
+    };
+    expand_with(graph, &load, &mut Vec::new())
+}
+
+fn expand_with(
+    graph: Graph,
+    load: &dyn Fn(&str) -> Result<Subgraph, String>,
+    expanding: &mut Vec<String>,
+) -> Result<Graph, String> {
+    let mut nodes = Vec::new();
+    let mut edges = graph.edges;
+
+    for node in graph.nodes {
+        let Some(id) = node.data.brick_id.strip_prefix(BRICK_ID_PREFIX) else {
+            nodes.push(node);
+            continue;
+        };
+        if expanding.iter().any(|expanding| expanding == id) {
+            return Err(format!("Subgraph '{}' contains itself", id));
+        }
+
+        let subgraph = load(id)?;
+        expanding.push(id.to_string());
+        let inner = expand_with(subgraph.graph, load, expanding)?;
+        expanding.pop();
+
+        let prefix = |inner_id: &str| format!("{}/{}", node.id, inner_id);
+        let port = |brick_id: &str, name: &str| {
+            inner
+                .nodes
+                .iter()
+                .find(|inner| inner.data.brick_id == brick_id && port_name(inner) == name)
+                .map(|inner| prefix(&inner.id))
+        };
+
+        // Edges to the subgraph node continue at its ports
+        for edge in &mut edges {
+            if edge.target == node.id {
+                let (target, handle) = port(ports::INPUT, &edge.target_handle)
+                    .map(|port| (port, "value"))
+                    .or_else(|| {
+                        port(ports::ENTRY, &edge.target_handle).map(|port| (port, "execute"))
+                    })
+                    .ok_or_else(|| {
+                        format!("Subgraph '{}' has no input '{}'", id, edge.target_handle)
+                    })?;
+                edge.target = target;
+                edge.target_handle = handle.to_string();
+            }
+            if edge.source == node.id {
+                let (source, handle) = port(ports::OUTPUT, &edge.source_handle)
+                    .map(|port| (port, "value"))
+                    .or_else(|| port(ports::EXIT, &edge.source_handle).map(|port| (port, "next")))
+                    .ok_or_else(|| {
+                        format!("Subgraph '{}' has no output '{}'", id, edge.source_handle)
+                    })?;
+                edge.source = source;
+                edge.source_handle = handle.to_string();
+            }
+        }
+
+        for mut inner_node in inner.nodes {
+            // Values set on the subgraph node's inputs replace the defaults of its ports
+            if inner_node.data.brick_id == ports::INPUT {
+                if let Some(value) = node.data.defaults.get(&port_name(&inner_node)) {
+                    inner_node
+                        .data
+                        .defaults
+                        .insert("value".to_string(), value.clone());
+                }
+            }
+            inner_node.id = prefix(&inner_node.id);
+            nodes.push(inner_node);
+        }
+        edges.extend(inner.edges.into_iter().map(|edge| Edge {
+            id: prefix(&edge.id),
+            source: prefix(&edge.source),
+            target: prefix(&edge.target),
+            ..edge
+        }));
+    }
+
+    Ok(Graph { nodes, edges })
+}
+
+/// Move a selection of nodes out of a graph into a new subgraph, replacing it with a subgraph
+/// node
+///
+/// Every edge crossing the border of the selection gets a port. Executions entering the same
+/// input share an entry, values coming from (or going out of) the same output share a port.
+pub fn extract(
+    graph: &mut Graph,
+    node_ids: &[String],
+    id: &str,
+    label: &str,
+) -> Result<Subgraph, String> {
+    let selected: HashSet<&str> = node_ids.iter().map(String::as_str).collect();
+    if selected.is_empty() {
+        return Err("Select the nodes to create a subgraph from".to_string());
+    }
+    if let Some(missing) = selected
+        .iter()
+        .find(|node_id| !graph.nodes.iter().any(|node| node.id == **node_id))
+    {
+        return Err(format!("Node '{}' not found", missing));
+    }
+
+    let is_flow_input = |node_id: &str, handle: &str| {
+        graph.nodes.iter().any(|node| {
+            node.id == node_id
+                && node.data.brick.as_ref().is_some_and(|brick| {
+                    brick
+                        .execution_inputs
+                        .iter()
+                        .any(|input| input.id == handle)
+                })
+        })
+    };
+    let flow_edges: HashSet<String> = graph
+        .edges
+        .iter()
+        .filter(|edge| is_flow_input(&edge.target, &edge.target_handle))
+        .map(|edge| edge.id.clone())
+        .collect();
+
+    let (inner_nodes, outer_nodes): (Vec<Node>, Vec<Node>) = std::mem::take(&mut graph.nodes)
+        .into_iter()
+        .partition(|node| selected.contains(node.id.as_str()));
+    let positions = || inner_nodes.iter().map(|node| &node.position);
+    let center = Point {
+        x: positions().map(|p| p.x).sum::<f64>() / inner_nodes.len() as f64,
+        y: positions().map(|p| p.y).sum::<f64>() / inner_nodes.len() as f64,
+    };
+    let left = positions().map(|p| p.x).fold(f64::INFINITY, f64::min) - 250.0;
+    let right = positions().map(|p| p.x).fold(f64::NEG_INFINITY, f64::max) + 250.0;
+
+    let subgraph_node_id = Uuid::new_v4().to_string();
+    let mut inner = Graph {
+        nodes: inner_nodes,
+        edges: vec![],
+    };
+    let mut outer = Graph {
+        nodes: outer_nodes,
+        edges: vec![],
+    };
+    // Port node id and name, by port brick and the handle the port stands for
+    let mut created: HashMap<(&str, String, String), (String, String)> = HashMap::new();
+    let mut names: HashSet<String> = HashSet::new();
+
+    for edge in std::mem::take(&mut graph.edges) {
+        let source_inside = selected.contains(edge.source.as_str());
+        let target_inside = selected.contains(edge.target.as_str());
+        let is_flow = flow_edges.contains(&edge.id);
+        let (brick_id, source, handle) = match (source_inside, target_inside) {
+            (true, true) => {
+                inner.edges.push(edge);
+                continue;
+            }
+            (false, false) => {
+                outer.edges.push(edge);
+                continue;
+            }
+            (false, true) if is_flow => (ports::ENTRY, &edge.target, &edge.target_handle),
+            (false, true) => (ports::INPUT, &edge.source, &edge.source_handle),
+            (true, false) if is_flow => (ports::EXIT, &edge.source, &edge.source_handle),
+            (true, false) => (ports::OUTPUT, &edge.source, &edge.source_handle),
+        };
+
+        let (port_id, name) = created
+            .entry((brick_id, source.clone(), handle.clone()))
+            .or_insert_with(|| {
+                // Name ports after the inner handle they connect to
+                let name = unique_name(
+                    if target_inside {
+                        &edge.target_handle
+                    } else {
+                        &edge.source_handle
+                    },
+                    &mut names,
+                );
+                let position = Point {
+                    x: if target_inside { left } else { right },
+                    y: center.y + 100.0 * (names.len() - 1) as f64,
+                };
+                let brick = canvas::get_brick(brick_id).expect("port bricks are built in");
+                let mut port = canvas::new_node(brick, position);
+                port.data.arguments.insert("name".to_string(), name.clone());
+                let port_id = port.id.clone();
+                inner.nodes.push(port);
+                (port_id, name)
+            })
+            .clone();
+
+        let (inner_edge, outer_edge) = match brick_id {
+            ports::ENTRY | ports::INPUT => (
+                Edge {
+                    id: Uuid::new_v4().to_string(),
+                    source: port_id,
+                    source_handle: if is_flow { "next" } else { "value" }.to_string(),
+                    target: edge.target.clone(),
+                    target_handle: edge.target_handle.clone(),
+                },
+                Edge {
+                    target: subgraph_node_id.clone(),
+                    target_handle: name,
+                    ..edge
+                },
+            ),
+            _ => (
+                Edge {
+                    id: Uuid::new_v4().to_string(),
+                    source: edge.source.clone(),
+                    source_handle: edge.source_handle.clone(),
+                    target: port_id,
+                    target_handle: if is_flow { "execute" } else { "value" }.to_string(),
+                },
+                Edge {
+                    source: subgraph_node_id.clone(),
+                    source_handle: name,
+                    ..edge
+                },
+            ),
+        };
+        push_new_edge(&mut inner.edges, inner_edge);
+        push_new_edge(&mut outer.edges, outer_edge);
+    }
+
+    let subgraph = Subgraph {
+        id: id.to_string(),
+        label: label.to_string(),
+        description: String::new(),
+        graph: inner,
+    };
+    let mut subgraph_node = canvas::new_node(subgraph.brick(), center);
+    subgraph_node.id = subgraph_node_id;
+    outer.nodes.push(subgraph_node);
+    *graph = outer;
+    Ok(subgraph)
+}
+
+/// Add an edge unless the same handles are connected already
+fn push_new_edge(edges: &mut Vec<Edge>, edge: Edge) {
+    let exists = edges.iter().any(|existing| {
+        existing.source == edge.source
+            && existing.source_handle == edge.source_handle
+            && existing.target == edge.target
+            && existing.target_handle == edge.target_handle
+    });
+    if !exists {
+        edges.push(edge);
+    }
+}
+
+/// The name, with a number appended when it is taken already
+fn unique_name(name: &str, taken: &mut HashSet<String>) -> String {
+    let mut unique = name.to_string();
+    let mut number = 2;
+    while taken.contains(&unique) {
+        unique = format!("{}_{}", name, number);
+        number += 1;
+    }
+    taken.insert(unique.clone());
+    unique
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bricks::arithmetics::add_brick;
+    use crate::bricks::debug::{print_brick, trigger_brick};
+    use crate::engine::Engine;
+    use crate::test_utils::{edge, node, with_arguments};
+
+    fn port(id: &str, brick_id: &str, name: &str) -> Node {
+        with_arguments(
+            node(id, canvas::get_brick(brick_id).unwrap()),
+            &[("name", name)],
+        )
+    }
+
+    /// Prints the sum of its two inputs when run
+    fn print_sum() -> Subgraph {
+        Subgraph {
+            id: "print_sum".to_string(),
+            label: "Print Sum".to_string(),
+            description: String::new(),
+            graph: Graph {
+                nodes: vec![
+                    port("run", ports::ENTRY, "run"),
+                    port("a", ports::INPUT, "a"),
+                    port("b", ports::INPUT, "b"),
+                    node("add", add_brick()),
+                    node("print", print_brick()),
+                    port("sum", ports::OUTPUT, "sum"),
+                ],
+                edges: vec![
+                    edge("run", "next", "print", "execute"),
+                    edge("a", "value", "add", "a"),
+                    edge("b", "value", "add", "b"),
+                    edge("add", "output_0", "print", "value"),
+                    edge("add", "output_0", "sum", "value"),
+                ],
+            },
+        }
+    }
+
+    fn expand_print_sum(graph: Graph) -> Result<Graph, String> {
+        expand_with(graph, &|_| Ok(print_sum()), &mut Vec::new())
+    }
+
+    #[test]
+    fn test_brick_from_ports() {
+        let brick = print_sum().brick();
+
+        assert_eq!(brick.id, "subgraph:print_sum");
+        let inputs: Vec<_> = brick.inputs.iter().map(|i| (&i.id, &i.r#type)).collect();
+        let number = ConnectionType::Number;
+        assert_eq!(
+            inputs,
+            vec![(&"a".to_string(), &number), (&"b".to_string(), &number)]
+        );
+        assert_eq!(brick.outputs[0].id, "sum");
+        assert_eq!(brick.outputs[0].r#type, ConnectionType::Number);
+        assert_eq!(brick.execution_inputs[0].id, "run");
+        assert!(brick.execution_outputs.is_empty());
+    }
+
+    #[test]
+    fn test_expanded_subgraph_runs() {
+        let mut subgraph_node = node("sub", print_sum().brick());
+        subgraph_node.data.defaults = [("a", "2"), ("b", "3")]
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let graph = Graph {
+            nodes: vec![node("trigger", trigger_brick()), subgraph_node],
+            edges: vec![edge("trigger", "triggered", "sub", "run")],
+        };
+
+        let mut graph = expand_print_sum(graph).unwrap();
+        for node in &mut graph.nodes {
+            node.data.brick = canvas::get_brick(&node.data.brick_id);
+        }
+        assert!(!validation::has_errors(&validation::validate(&graph)));
+
+        let mut engine = Engine::new(graph);
+        engine.start();
+        let results: Vec<_> = (&mut engine).collect::<Result<_, _>>().unwrap();
+        assert!(results.contains(&"sub/print".to_string()), "{:?}", results);
+        let sum = engine.node_state("sub/add").unwrap().outputs.unwrap();
+        assert_eq!(sum[0].value, Value::Number(5.0));
+    }
+
+    #[test]
+    fn test_subgraph_containing_itself() {
+        let mut recursive = print_sum();
+        recursive.graph.nodes.push(node("inner", recursive.brick()));
+        let graph = Graph {
+            nodes: vec![node("outer", recursive.brick())],
+            edges: vec![],
+        };
+
+        let result = expand_with(graph, &|_| Ok(recursive.clone()), &mut Vec::new());
+        assert_eq!(
+            result.err().as_deref(),
+            Some("Subgraph 'print_sum' contains itself")
+        );
+    }
+
+    #[test]
+    fn test_extract_selection() {
+        // Graph: trigger → print, with a → b → print, then b is moved into a subgraph
+        let mut graph = Graph {
+            nodes: vec![
+                node("trigger", trigger_brick()),
+                node("a", add_brick()),
+                node("b", add_brick()),
+                node("print", print_brick()),
+            ],
+            edges: vec![
+                edge("trigger", "triggered", "print", "execute"),
+                edge("a", "output_0", "b", "a"),
+                edge("a", "output_0", "b", "b"),
+                edge("b", "output_0", "print", "value"),
+            ],
+        };
+
+        let subgraph = extract(&mut graph, &["b".to_string()], "double", "Double").unwrap();
+
+        // Both inputs of b are fed by the same output, so they share a port
+        let brick = subgraph.brick();
+        let inputs: Vec<_> = brick.inputs.iter().map(|input| &input.id).collect();
+        let outputs: Vec<_> = brick.outputs.iter().map(|output| &output.id).collect();
+        assert_eq!(inputs, vec!["a"]);
+        assert_eq!(outputs, vec!["output_0"]);
+        assert_eq!(subgraph.graph.nodes.len(), 3);
+        assert_eq!(subgraph.graph.edges.len(), 3);
+
+        assert_eq!(graph.nodes.len(), 4);
+        assert_eq!(graph.edges.len(), 3);
+
+        // Expanding it again gives the graph it came from, apart from the ports
+        let mut expanded = expand_with(graph, &|_| Ok(subgraph.clone()), &mut Vec::new()).unwrap();
+        for node in &mut expanded.nodes {
+            node.data.brick = canvas::get_brick(&node.data.brick_id);
+        }
+        assert_eq!(validation::validate(&expanded), vec![]);
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let directory = std::env::temp_dir().join(format!("vla-subgraphs-{}", Uuid::new_v4()));
+        save(&directory, &print_sum()).unwrap();
+
+        let loaded = load_all(&directory);
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].graph.nodes.len(), 6);
+        assert!(loaded[0].graph.nodes[0].data.brick.is_none());
+        assert!(save(
+            &directory,
+            &Subgraph {
+                id: "../escape".to_string(),
+                ..print_sum()
+            }
+        )
+        .is_err());
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn test_subgraphs_are_stored_next_to_the_graph() {
+        let root = std::env::temp_dir().join(format!("vla-graph-{}", Uuid::new_v4()));
+        let directory = directory_of(&root.join("graph.json"));
+        assert_eq!(directory, root.join("subgraphs"));
+        save(&directory, &print_sum()).unwrap();
+
+        let graph = Graph {
+            nodes: vec![node("sub", print_sum().brick())],
+            edges: vec![],
+        };
+        assert_eq!(bricks(&directory)[0].id, "subgraph:print_sum");
+        assert_eq!(
+            expand(graph.clone(), Some(&directory)).unwrap().nodes.len(),
+            6
+        );
+        assert!(expand(graph, None).is_err());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
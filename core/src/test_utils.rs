@@ -0,0 +1,41 @@
+/// Graph fixtures shared by the tests
+use crate::canvas;
+use crate::prelude::*;
+
+/// A node for a brick with a readable id, its input defaults filled in like in the editor
+pub fn node(id: &str, brick: Brick) -> Node {
+    let mut node = canvas::new_node(brick, Point { x: 0.0, y: 0.0 });
+    node.id = id.to_string();
+    node
+}
+
+/// The node with arguments set, values as they are stored in the graph file
+pub fn with_arguments(mut node: Node, arguments: &[(&str, &str)]) -> Node {
+    node.data.arguments.extend(
+        arguments
+            .iter()
+            .map(|(id, value)| (id.to_string(), value.to_string())),
+    );
+    node
+}
+
+/// The node with input defaults set, values as they are stored in the graph file
+pub fn with_defaults(mut node: Node, defaults: &[(&str, &str)]) -> Node {
+    node.data.defaults.extend(
+        defaults
+            .iter()
+            .map(|(id, value)| (id.to_string(), value.to_string())),
+    );
+    node
+}
+
+/// An edge between two handles, its id made of the nodes it connects
+pub fn edge(source: &str, source_handle: &str, target: &str, target_handle: &str) -> Edge {
+    Edge {
+        id: format!("{}-{}", source, target),
+        source: source.to_string(),
+        target: target.to_string(),
+        source_handle: source_handle.to_string(),
+        target_handle: target_handle.to_string(),
+    }
+}
@@ -1,9 +1,13 @@
-import { createTauRPCProxy, type NodeData } from './core'
+import { createTauRPCProxy, type ConnectionType, type NodeData } from './core'
 import type { Node, NodeProps } from '@xyflow/svelte';
 
 export type CanvasNode = Node<NodeData>;
 export type CanvasNodeProps = NodeProps<CanvasNode>;
 
+// Collection types carry their item type, so reduce them to a plain name for styling
+export const connectionTypeName = (type: ConnectionType): string =>
+    typeof type === 'string' ? type : 'list' in type ? 'list' : 'map';
+
 let saveCallback: (() => void) | null = null;
 
 export const setSaveCallback = (callback: () => void) => {
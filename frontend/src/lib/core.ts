@@ -6,7 +6,7 @@ type TAURI_CHANNEL<T> = (response: T) => void
 
 export type ArgumentType = "string" | "number" | "boolean" | "enum"
 
-export type Brick = { id: string; label: string; description: string; keywords: string[]; category: string; arguments: BrickArgument[]; inputs: BrickInput[]; outputs: BrickOutput[]; execution_inputs: BrickExecutionInput[]; execution_outputs: BrickExecutionOutput[]; emissionType: BrickEmissionType }
+export type Brick = { id: string; label: string; description: string; keywords: string[]; category: string; version?: number; arguments: BrickArgument[]; inputs: BrickInput[]; outputs: BrickOutput[]; execution_inputs: BrickExecutionInput[]; execution_outputs: BrickExecutionOutput[]; emissionType: BrickEmissionType }
 
 export type BrickArgument = { id: string; label: string; type: ArgumentType; enumOptions: string[] | null; defaultValue: string | null }
 
@@ -43,15 +43,21 @@ export type BrickInput = { id: string; label: string; type: ConnectionType; defa
 
 export type BrickOutput = { id: string; label: string; type: ConnectionType }
 
-export type BrickOutputValue = { id: string; value: string }
+export type BrickInputValue = { id: string; value: Value }
 
-export type ConnectionType = "flow" | "string" | "number" | "boolean" | "enum"
+export type BrickOutputValue = { id: string; value: Value }
+
+export type ConnectionType = "flow" | "string" | "number" | "boolean" | "enum" | "any" | { list: ConnectionType } | { map: ConnectionType }
+
+export type Diagnostic = { severity: Severity; nodeId: string | null; edgeId: string | null; message: string; cycle?: Cycle | null }
+
+export type Cycle = { nodes: string[]; edges: string[] }
 
 export type Edge = { id: string; source: string; target: string; sourceHandle: string; targetHandle: string }
 
 export type ExecutionMode = "Normal" | "Stepped"
 
-export type ExecutionPhase = "Waiting" | "Queued" | "Running" | "Completed" | "Errored"
+export type ExecutionPhase = "Waiting" | "Queued" | "Paused" | "Running" | "Completed" | "Errored"
 
 /**
  * Result of executing the entire graph
@@ -64,22 +70,52 @@ export type Graph = { nodes: Node[]; edges: Edge[] }
 
 export type Node = { id: string; position: Point; data: NodeData; type: string }
 
-export type NodeData = { brickId: string; brick: Brick | null; arguments: Partial<{ [key in string]: string }>; defaults: Partial<{ [key in string]: string }> }
+export type NodeData = { brickId: string; brick: Brick | null; arguments: Partial<{ [key in string]: string }>; defaults: Partial<{ [key in string]: string }>; policy?: NodePolicy; breakpoint?: Breakpoint | null; brickVersion?: number }
+
+export type Breakpoint = { conditions?: BreakpointCondition[] }
+
+export type BreakpointCondition = { input: string; operator: ConditionOperator; value: Value }
+
+export type ConditionOperator = "equals" | "not-equals" | "less-than" | "greater-than"
+
+export type NodePolicy = { onError: ErrorPolicy; maxAttempts?: number | null; backoff?: Backoff; timeoutMs?: number | null }
 
-export type NodeExecutionState = { phase: ExecutionPhase; errorMessage: string | null; elapsedMs: number; outputs: BrickOutputValue[] | null }
+export type Backoff = { strategy: "none" } | { strategy: "fixed"; delayMs: number } | { strategy: "exponential"; initialMs: number; maxMs?: number | null }
+
+export type ErrorPolicy = "stop-run" | "continue" | "retry"
+
+export type NodeInspection = { waveId: string; outputs: BrickOutputValue[] | null; inputs: BrickInputValue[] | null }
+
+export type NodeExecutionState = { phase: ExecutionPhase; errorMessage: string | null; elapsedMs: number; outputs: BrickOutputValue[] | null; attempt: number; timedOut: boolean }
+
+export type WaveCache = { waveId: string; outputs: Partial<{ [key in string]: BrickOutputValue[] }> }
 
 export type Point = { x: number; y: number }
 
-const ARGS_MAP = { '':'{"execute_graph":["graph","mode"],"get_brick":["brick_id"],"get_bricks":[],"graph_updated":["graph"],"insert_node":["graph_path","brick_id","position"],"load_graph":["filename"],"node_execution_updated":["update"],"save_graph":["graph","filename"],"trigger_manual_node":["node_id"]}' }
-export type Router = { "": {execute_graph: (graph: Graph, mode: ExecutionMode) => Promise<ExecutionResult>, 
+export type Severity = "error" | "warning"
+
+export type Value = { type: "null" } | { type: "bool"; value: boolean } | { type: "integer"; value: number } | { type: "number"; value: number } | { type: "string"; value: string } | { type: "list"; value: Value[] } | { type: "map"; value: Partial<{ [key in string]: Value }> } | { type: "bytes"; value: number[] }
+
+const ARGS_MAP = { '':'{"connect":["graph_path","edge","convert"],"continue_execution":[],"create_subgraph":["graph_path","node_ids","id","label"],"execute_graph":["graph_path","graph","mode"],"get_brick":["brick_id"],"get_bricks":[],"get_execution_cache":[],"graph_updated":["graph"],"insert_node":["graph_path","brick_id","position"],"inspect_node":["node_id"],"load_graph":["filename"],"node_execution_updated":["update"],"override_output":["wave_id","node_id","output_id","value"],"pause_execution":[],"save_graph":["graph","filename"],"set_breakpoint":["node_id","breakpoint"],"step_execution":[],"trigger_manual_node":["node_id"],"validate_graph":["graph_path","graph"]}' }
+export type Router = { "": {connect: (graphPath: string, edge: Edge, convert: boolean) => Promise<Graph>, 
+continue_execution: () => Promise<null>, 
+create_subgraph: (graphPath: string, nodeIds: string[], id: string, label: string) => Promise<Graph>, 
+execute_graph: (graphPath: string, graph: Graph, mode: ExecutionMode) => Promise<ExecutionResult>, 
 get_brick: (brickId: string) => Promise<Brick | null>, 
 get_bricks: () => Promise<Brick[]>, 
+get_execution_cache: () => Promise<WaveCache[]>, 
 graph_updated: (graph: Graph) => Promise<void>, 
 insert_node: (graphPath: string, brickId: string, position: Point) => Promise<Graph>, 
+inspect_node: (nodeId: string) => Promise<NodeInspection[]>, 
 load_graph: (filename: string) => Promise<Graph>, 
 node_execution_updated: (update: ExecutionStateUpdate) => Promise<void>, 
+override_output: (waveId: string | null, nodeId: string, outputId: string, value: Value) => Promise<null>, 
+pause_execution: () => Promise<null>, 
 save_graph: (graph: Graph, filename: string) => Promise<string>, 
-trigger_manual_node: (nodeId: string) => Promise<null>} };
+set_breakpoint: (nodeId: string, breakpoint: Breakpoint | null) => Promise<null>, 
+step_execution: () => Promise<null>, 
+trigger_manual_node: (nodeId: string) => Promise<null>, 
+validate_graph: (graphPath: string, graph: Graph) => Promise<Diagnostic[]>} };
 
 
 export const createTauRPCProxy = () => createProxy<Router>(ARGS_MAP)